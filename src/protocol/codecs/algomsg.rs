@@ -1,15 +1,27 @@
-use std::io::{self, ErrorKind};
+use std::io::{self, ErrorKind, Read};
 
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
+use flate2::read::GzDecoder;
 use tokio_util::codec::{Decoder, Encoder};
 use tracing::{debug, warn, Span};
-use websocket_codec::Opcode;
+use websocket_codec::{Message, Opcode};
 
 use crate::protocol::{
-    codecs::{payload::Payload, tagmsg::TagMsgCodec, websocket::WebsocketCodec},
+    codecs::{
+        payload::Payload,
+        tagmsg::{Tag, TagMsgCodec, TAG_LEN},
+        websocket::WebsocketCodec,
+        CodecError,
+    },
     invalid_data,
 };
 
+/// The magic number at the start of every gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// The largest message go-algorand accepts, see `MaxMessageLength` in go-algorand/network/wsPeer.go.
+const MAX_INFLATED_LEN: usize = 6 * 1024 * 1024;
+
 /// Algorand message.
 #[derive(Debug)]
 pub struct AlgoMsg {
@@ -17,6 +29,18 @@ pub struct AlgoMsg {
     pub raw: Vec<u8>,
     /// Parsed message.
     pub payload: Payload,
+    /// Opcode of the WebSocket frame which delivered the message.
+    pub opcode: Opcode,
+}
+
+impl AlgoMsg {
+    /// Takes the tag from the raw message, since not all payloads are decoded.
+    pub fn tag(&self) -> Tag {
+        self.raw
+            .get(..TAG_LEN)
+            .and_then(|tag| Tag::try_from(Bytes::copy_from_slice(tag)).ok())
+            .unwrap_or(Tag::UnknownMsg)
+    }
 }
 
 pub struct AlgoMsgCodec {
@@ -40,38 +64,105 @@ impl Decoder for AlgoMsgCodec {
     type Error = io::Error;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let ws_msg = if let Some(src) = self.websocket.decode(src)? {
-            src
-        } else {
-            return Ok(None);
-        };
+        let ws_msg = loop {
+            let ws_msg = if let Some(src) = self.websocket.decode(src)? {
+                src
+            } else {
+                return Ok(None);
+            };
 
-        debug!(parent: &self.span, "got a WebSocket message: {:?}", ws_msg);
+            debug!(parent: &self.span, "got a WebSocket message: {:?}", ws_msg);
 
-        // Only binary messages are expected.
-        if ws_msg.opcode() != Opcode::Binary {
-            warn!(parent: &self.span, "not a binary opcode");
-            return Err(invalid_data!("expected a binary opcode"));
-        }
+            match ws_msg.opcode() {
+                Opcode::Binary => break ws_msg,
+                // The node should only send binary messages, but the opcode is kept so the tests can
+                // verify that.
+                Opcode::Text => {
+                    warn!(parent: &self.span, "got a text message");
+                    break ws_msg;
+                }
+                // The reading protocol replies to pings with a pong.
+                Opcode::Ping => {
+                    let data = ws_msg.data().to_vec();
+                    return Ok(Some(AlgoMsg {
+                        raw: data.clone(),
+                        payload: Payload::WsPing(data),
+                        opcode: Opcode::Ping,
+                    }));
+                }
+                // We never send pings, so pongs can be safely ignored.
+                Opcode::Pong => continue,
+                Opcode::Close => {
+                    debug!(parent: &self.span, "the peer closed the WebSocket connection");
+                    return Err(io::Error::new(
+                        ErrorKind::ConnectionAborted,
+                        "the peer closed the WebSocket connection",
+                    ));
+                }
+            }
+        };
 
         let mut ws_data =
             BytesMut::try_from(ws_msg.data().as_ref()).map_err(|_| ErrorKind::InvalidData)?;
+
+        // A tagged message always starts with an ASCII tag, so it can't be mistaken for gzip.
+        if ws_data.starts_with(&GZIP_MAGIC) {
+            debug!(parent: &self.span, "inflating a gzip-compressed payload");
+            ws_data = inflate(&ws_data)?;
+        }
         let raw = ws_data.to_vec();
 
         let payload = self
             .tagmsg
-            .decode(&mut ws_data)
-            .map_err(|_| invalid_data!("invalid algod message"))?
-            .ok_or_else(|| invalid_data!("missing algod message"))?;
+            .decode(&mut ws_data)?
+            .ok_or(CodecError::Truncated("algod message"))?;
 
-        Ok(Some(AlgoMsg { raw, payload }))
+        Ok(Some(AlgoMsg {
+            raw,
+            payload,
+            opcode: ws_msg.opcode(),
+        }))
     }
 }
 
+/// Decompresses a gzip-compressed message, up to [MAX_INFLATED_LEN] bytes.
+fn inflate(data: &[u8]) -> io::Result<BytesMut> {
+    let mut inflated = Vec::new();
+    GzDecoder::new(data)
+        .take(MAX_INFLATED_LEN as u64 + 1)
+        .read_to_end(&mut inflated)
+        .map_err(|_| invalid_data!("couldn't inflate a gzip-compressed message"))?;
+    if inflated.len() > MAX_INFLATED_LEN {
+        return Err(invalid_data!(format!(
+            "the inflated message exceeds {MAX_INFLATED_LEN} bytes"
+        )));
+    }
+
+    Ok(BytesMut::from(inflated.as_slice()))
+}
+
 impl Encoder<Payload> for AlgoMsgCodec {
     type Error = io::Error;
 
     fn encode(&mut self, message: Payload, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        // Control frames aren't tagged Algod messages.
+        if let Payload::WsPong(data) = message {
+            return self
+                .websocket
+                .encode(Message::pong(data), dst)
+                .map_err(|_| invalid_data!("couldn't encode a WebSocket pong message"));
+        }
+        if let Payload::WsClose = message {
+            return self
+                .websocket
+                .encode(Message::close(None), dst)
+                .map_err(|_| invalid_data!("couldn't encode a WebSocket close message"));
+        }
+        if let Payload::RawFrame(data) = message {
+            dst.extend_from_slice(&data);
+            return Ok(());
+        }
+
         let mut tag_msg = BytesMut::new();
 
         self.tagmsg
@@ -83,3 +174,149 @@ impl Encoder<Payload> for AlgoMsgCodec {
             .map_err(|_| invalid_data!("couldn't encode a WebSocket message"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, io::Write};
+
+    use flate2::{write::GzEncoder, Compression};
+    use websocket_codec::MessageCodec;
+
+    use super::*;
+    use crate::protocol::codecs::{msgpack::HashDigest, tagmsg::Tag, topic::MsgOfInterest};
+
+    #[test]
+    fn reply_to_ping_with_pong() {
+        let mut codec = AlgoMsgCodec::new(Span::none());
+
+        // An unmasked ping frame, as sent by the node.
+        let mut src = BytesMut::new();
+        MessageCodec::server()
+            .encode(Message::ping("ping"), &mut src)
+            .expect("couldn't encode a ping frame");
+
+        let msg = codec
+            .decode(&mut src)
+            .expect("a ping frame shouldn't be an error")
+            .expect("missing the ping message");
+        let data = match msg.payload {
+            Payload::WsPing(data) => data,
+            payload => panic!("unexpected payload: {payload:?}"),
+        };
+
+        let mut dst = BytesMut::new();
+        codec
+            .encode(Payload::WsPong(data), &mut dst)
+            .expect("couldn't encode a pong frame");
+
+        let pong = MessageCodec::server()
+            .decode(&mut dst)
+            .expect("invalid pong frame")
+            .expect("missing the pong frame");
+        assert_eq!(pong.opcode(), Opcode::Pong);
+        assert_eq!(pong.data().as_ref(), b"ping");
+    }
+
+    #[test]
+    fn keep_the_opcode_of_data_frames() {
+        let mut codec = AlgoMsgCodec::new(Span::none());
+
+        let mut tag_msg = BytesMut::new();
+        TagMsgCodec::new(Span::none())
+            .encode(Payload::MsgDigestSkip(HashDigest([b'z'; 32])), &mut tag_msg)
+            .expect("couldn't encode a tagmsg message");
+
+        let mut src = BytesMut::new();
+        MessageCodec::server()
+            .encode(Message::binary(tag_msg.to_vec()), &mut src)
+            .expect("couldn't encode a binary frame");
+        let msg = codec
+            .decode(&mut src)
+            .expect("couldn't decode a binary frame")
+            .expect("missing the binary message");
+        assert_eq!(msg.opcode, Opcode::Binary);
+
+        // Both the tag and the digest are ASCII, so the same message is valid UTF-8 text as well.
+        let text = String::from_utf8(tag_msg.to_vec()).expect("the message isn't valid UTF-8");
+        MessageCodec::server()
+            .encode(Message::text(text), &mut src)
+            .expect("couldn't encode a text frame");
+        let msg = codec
+            .decode(&mut src)
+            .expect("couldn't decode a text frame")
+            .expect("missing the text message");
+        assert_eq!(msg.opcode, Opcode::Text);
+    }
+
+    #[test]
+    fn encode_raw_frame_as_is() {
+        let mut codec = AlgoMsgCodec::new(Span::none());
+
+        let data = vec![0xff, 0x00, 0x01];
+        let mut dst = BytesMut::new();
+        codec
+            .encode(Payload::RawFrame(data.clone()), &mut dst)
+            .expect("couldn't encode a raw frame");
+        assert_eq!(&dst[..], data);
+    }
+
+    #[test]
+    fn skip_pong_and_reject_close() {
+        let mut codec = AlgoMsgCodec::new(Span::none());
+
+        let mut src = BytesMut::new();
+        MessageCodec::server()
+            .encode(Message::pong("pong"), &mut src)
+            .expect("couldn't encode a pong frame");
+        assert!(matches!(codec.decode(&mut src), Ok(None)));
+
+        MessageCodec::server()
+            .encode(Message::close(None), &mut src)
+            .expect("couldn't encode a close frame");
+        let err = codec.decode(&mut src).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ConnectionAborted);
+    }
+
+    #[test]
+    fn decode_gzip_compressed_msg_of_interest() {
+        let mut codec = AlgoMsgCodec::new(Span::none());
+
+        let msg = MsgOfInterest {
+            tags: HashSet::from([Tag::ProposalPayload, Tag::Txn]),
+        };
+        let mut tag_msg = BytesMut::new();
+        TagMsgCodec::new(Span::none())
+            .encode(Payload::MsgOfInterest(msg.clone()), &mut tag_msg)
+            .expect("couldn't encode a tagmsg message");
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&tag_msg)
+            .expect("couldn't compress the message");
+        let compressed = encoder.finish().expect("couldn't compress the message");
+
+        let mut src = BytesMut::new();
+        MessageCodec::server()
+            .encode(Message::binary(compressed), &mut src)
+            .expect("couldn't encode a binary frame");
+
+        let decoded = codec
+            .decode(&mut src)
+            .expect("couldn't decode a compressed message")
+            .expect("missing the compressed message");
+        assert_eq!(decoded.raw, tag_msg.to_vec());
+        assert!(matches!(decoded.payload, Payload::MsgOfInterest(moi) if moi.tags == msg.tags));
+    }
+
+    #[test]
+    fn reject_oversized_gzip_compressed_msg() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&vec![0; MAX_INFLATED_LEN + 1])
+            .expect("couldn't compress the message");
+        let compressed = encoder.finish().expect("couldn't compress the message");
+
+        let err = inflate(&compressed).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -50,3 +50,120 @@ pub mod payload;
 pub mod tagmsg;
 pub mod topic;
 pub mod websocket;
+
+use std::{fmt, io};
+
+/// Errors returned by the decoders for malformed messages.
+///
+/// The error is wrapped in an [io::Error] of the [io::ErrorKind::InvalidData] kind and can be
+/// retrieved with [CodecError::from_io].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodecError {
+    /// The message tag isn't known.
+    UnknownTag(String),
+    /// The data ended before the named field could be read.
+    Truncated(&'static str),
+    /// A topic key or value length is out of bounds.
+    InvalidTopicLength,
+    /// The named field isn't a valid UTF-8 string.
+    InvalidUtf8(&'static str),
+    /// The topics don't form the expected message.
+    UnexpectedTopics(&'static str),
+    /// A MsgOfInterest message has more tags than allowed.
+    TooManyTags(usize),
+    /// The named message couldn't be deserialized.
+    Deserialize(&'static str),
+    /// A proposal isn't internally consistent, for the given reason.
+    InvalidProposal(String),
+}
+
+impl CodecError {
+    /// Returns the codec error wrapped in the I/O error, if there is one.
+    pub fn from_io(err: &io::Error) -> Option<&Self> {
+        err.get_ref().and_then(|err| err.downcast_ref())
+    }
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownTag(tag) => write!(f, "unknown tag: {tag:?}"),
+            Self::Truncated(field) => write!(f, "missing the {field}"),
+            Self::InvalidTopicLength => write!(f, "invalid topic length"),
+            Self::InvalidUtf8(field) => write!(f, "the {field} is not a valid UTF-8 string"),
+            Self::UnexpectedTopics(msg) => write!(f, "unexpected topics for the {msg} message"),
+            Self::TooManyTags(count) => write!(f, "too many tags: {count}"),
+            Self::Deserialize(msg) => write!(f, "couldn't deserialize the {msg} message"),
+            Self::InvalidProposal(reason) => write!(f, "invalid proposal: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+impl From<CodecError> for io::Error {
+    fn from(err: CodecError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use tokio_util::codec::Decoder;
+    use tracing::Span;
+
+    use super::*;
+    use crate::protocol::codecs::tagmsg::TagMsgCodec;
+
+    fn decode_err(data: &[u8]) -> CodecError {
+        let err = TagMsgCodec::new(Span::none())
+            .decode(&mut BytesMut::from(data))
+            .expect_err("the message should be malformed");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        CodecError::from_io(&err)
+            .expect("not a codec error")
+            .clone()
+    }
+
+    #[test]
+    fn malformed_tags() {
+        assert_eq!(decode_err(b"M"), CodecError::Truncated("tag"));
+        assert_eq!(decode_err(b"ZZ"), CodecError::UnknownTag("ZZ".into()));
+        assert_eq!(decode_err(&[0xff, 0xfe]), CodecError::InvalidUtf8("tag"));
+    }
+
+    #[test]
+    fn malformed_topics() {
+        assert_eq!(decode_err(b"MI"), CodecError::Truncated("number of topics"));
+        // A topic key can't be empty.
+        assert_eq!(decode_err(b"MI\x01\x00"), CodecError::InvalidTopicLength);
+        assert_eq!(
+            decode_err(b"MI\x01\x04ta"),
+            CodecError::Truncated("topic key")
+        );
+        assert_eq!(
+            decode_err(b"MI\x01\x04tags\x05TX"),
+            CodecError::Truncated("topic value")
+        );
+        assert_eq!(
+            decode_err(b"MI\x01\x04tags\x02XX"),
+            CodecError::UnknownTag("XX".into())
+        );
+        assert_eq!(
+            decode_err(b"MI\x01\x03key\x01v"),
+            CodecError::UnexpectedTopics("MsgOfInterest")
+        );
+    }
+
+    #[test]
+    fn malformed_msgpack_payloads() {
+        // 0xc1 is never used in the msgpack format.
+        assert_eq!(decode_err(b"TX\xc1"), CodecError::Deserialize("Txn"));
+        assert_eq!(
+            decode_err(b"MS\x01\x02"),
+            CodecError::Deserialize("MsgDigestSkip")
+        );
+    }
+}
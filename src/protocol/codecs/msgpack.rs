@@ -1,20 +1,63 @@
 //! Message pack deserializer for algod messages.
 
 use std::{
+    collections::HashMap,
     convert::From,
     fmt::{self, Debug, Display, Formatter},
-    str,
+    io,
+    str::{self, FromStr},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use data_encoding::{BASE32_NOPAD, BASE64};
-use serde::{de::Visitor, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(test)]
+use ed25519_dalek::SigningKey;
+#[cfg(test)]
+use rand::rngs::OsRng;
+use serde::{
+    de::{MapAccess, SeqAccess, Visitor},
+    ser::{SerializeMap, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 use sha2::Digest;
 
+use crate::protocol::{codecs::CodecError, invalid_data};
+
 /// Period of time.
 type Period = u64;
 
 /// Algorand is organized in logical units (r = 0, 1...) called rounds in which new blocks are created.
-pub type Round = u64;
+///
+/// It is encoded as a plain integer, but kept apart from the other integers such as fees and amounts.
+#[derive(
+    Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Round(pub u64);
+
+impl Round {
+    /// Returns the round which comes the given number of rounds later, or `None` on overflow.
+    pub fn checked_add(self, rounds: u64) -> Option<Round> {
+        self.0.checked_add(rounds).map(Round)
+    }
+
+    /// Returns the round which came the given number of rounds earlier, stopping at round zero.
+    pub fn saturating_sub(self, rounds: u64) -> Round {
+        Round(self.0.saturating_sub(rounds))
+    }
+}
+
+impl Display for Round {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<u64> for Round {
+    fn from(round: u64) -> Self {
+        Round(round)
+    }
+}
 
 /// Each [Round] is divided into multiple steps.
 type Step = u64;
@@ -50,17 +93,21 @@ pub struct Response {
 /// its proposer, and the period in which it was proposed.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ProposalValue {
+    /// The period in which the block was originally proposed.
     #[serde(default, rename = "oper")]
-    original_period: Period,
+    pub original_period: Period,
 
+    /// The address of the original proposer.
     #[serde(rename = "oprop")]
-    original_proposer: Address,
+    pub original_proposer: Address,
 
+    /// The hash of the block contents.
     #[serde(rename = "dig")]
-    block_digest: HashDigest,
+    pub block_digest: HashDigest,
 
+    /// The hash of the block encoding.
     #[serde(rename = "encdig")]
-    encoding_digest: HashDigest,
+    pub encoding_digest: HashDigest,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -189,7 +236,7 @@ pub struct ProposalPayload {
 
     /// Round represents a protocol round index.
     #[serde(default, rename = "rnd")]
-    pub round: u64,
+    pub round: Round,
 
     /// The round at which the RewardsRate will be recalculated.
     #[serde(rename = "rwcalr")]
@@ -234,6 +281,77 @@ pub struct ProposalPayload {
     pub prior_vote: Option<UnauthenticatedVote>,
 }
 
+/// How far in the future a proposal's timestamp may be before it's considered implausible.
+const MAX_PROPOSAL_CLOCK_DRIFT: Duration = Duration::from_secs(60);
+
+impl ProposalPayload {
+    /// Returns the round of the proposed block.
+    pub fn round(&self) -> Round {
+        self.round
+    }
+
+    /// Checks the internal consistency of the proposal, without any knowledge of the chain.
+    ///
+    /// The protocol must be set, the timestamp must be neither negative nor in the future and
+    /// only the genesis round may lack the previous block hash.
+    pub fn validate_basic(&self) -> Result<(), CodecError> {
+        if self.protocol_current.is_empty() {
+            return Err(CodecError::InvalidProposal("the protocol is empty".into()));
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| CodecError::InvalidProposal(e.to_string()))?;
+        let latest = (now + MAX_PROPOSAL_CLOCK_DRIFT).as_secs();
+        if u64::try_from(self.timestamp).map_or(true, |timestamp| timestamp > latest) {
+            return Err(CodecError::InvalidProposal(format!(
+                "implausible timestamp: {}",
+                self.timestamp
+            )));
+        }
+
+        match (self.round, self.prevous_block_hash) {
+            (Round(0), Some(_)) => Err(CodecError::InvalidProposal(
+                "the genesis proposal has a previous block hash".into(),
+            )),
+            (round, None) if round != Round(0) => Err(CodecError::InvalidProposal(format!(
+                "the proposal for round {round} has no previous block hash"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Creates a valid proposal for the round, timestamped now.
+    #[cfg(test)]
+    pub fn test_proposal(round: Round) -> ProposalPayload {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("the clock is set before the epoch");
+
+        ProposalPayload {
+            round,
+            earn: 0,
+            fee_sink: Address::new([1; HASH_LEN]),
+            genensis_id: "private-v1".into(),
+            genesis_id_hash: HashDigest::from(&b"genesis".to_vec()),
+            leftover_fraction: 0,
+            original_period: 0,
+            original_proposal: Address::new([2; HASH_LEN]),
+            prevous_block_hash: (round != Round(0)).then(|| HashDigest::from(&b"prev".to_vec())),
+            prior_vote: None,
+            protocol_current: "future".into(),
+            rewards_pool: Address::new([3; HASH_LEN]),
+            rewards_rate: 0,
+            rewards_rate_recalc_round: 500_000,
+            seed_proof: None,
+            sortition_seed: None,
+            timestamp: now.as_secs() as i64,
+            tx_merke_root_hash: None,
+            tx_merke_root_hash256: None,
+        }
+    }
+}
+
 /// A vote is an endorsement of a particular proposal in Algorand.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AgreementVote {
@@ -249,6 +367,114 @@ pub struct AgreementVote {
     #[serde(rename = "sig")]
     pub sig: OneTimeSignature,
 }
+
+impl AgreementVote {
+    /// Returns the round the vote is cast in.
+    pub fn round(&self) -> Round {
+        self.raw_vote.round
+    }
+
+    /// Returns the period the vote is cast in.
+    pub fn period(&self) -> Period {
+        self.raw_vote.period
+    }
+
+    /// Returns the step the vote is cast in.
+    pub fn step(&self) -> Step {
+        self.raw_vote.step
+    }
+
+    /// Returns the proposal the vote endorses, if any.
+    pub fn proposal(&self) -> Option<&ProposalValue> {
+        self.raw_vote.proposal.as_ref()
+    }
+}
+
+/// A [VoteBundle] is a set of votes, all from the same round, period, and step, and for the
+/// same proposal value. It reflects the [unauthenticatedBundle] struct from the
+/// go-algorand/agreement/bundle.go file.
+///
+/// Bundles are relayed so that nodes which fall behind can quickly learn about a certified value.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VoteBundle {
+    /// Round represents a protocol round index.
+    #[serde(default, rename = "rnd")]
+    pub round: Round,
+
+    /// Time period.
+    #[serde(default, rename = "per")]
+    pub period: Period,
+
+    /// Step of the round.
+    #[serde(default, rename = "step")]
+    pub step: Step,
+
+    /// The proposal all the votes in the bundle are voting for.
+    #[serde(default, rename = "prop")]
+    pub proposal: Option<ProposalValue>,
+
+    /// Votes in the bundle.
+    #[serde(default, rename = "vote")]
+    pub votes: Vec<VoteAuthenticator>,
+
+    /// Equivocation votes in the bundle.
+    #[serde(default, rename = "eqv")]
+    pub equivocation_votes: Vec<EquivocationVoteAuthenticator>,
+}
+
+impl VoteBundle {
+    /// Creates a bundle without any votes for the certification step of the round.
+    #[cfg(test)]
+    pub fn empty(round: Round) -> VoteBundle {
+        VoteBundle {
+            round,
+            period: 0,
+            step: 2,
+            proposal: None,
+            votes: Vec::new(),
+            equivocation_votes: Vec::new(),
+        }
+    }
+}
+
+/// A [VoteAuthenticator] omits the round, period, step and proposal of a vote since these are
+/// already present in the [VoteBundle].
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VoteAuthenticator {
+    /// Sender address.
+    #[serde(rename = "snd")]
+    pub sender_addr: Address,
+
+    /// Unauthenticated credential.
+    #[serde(rename = "cred")]
+    pub unauthenticated_credential: UnauthenticatedCredential,
+
+    /// Signature.
+    #[serde(rename = "sig")]
+    pub sig: OneTimeSignature,
+}
+
+/// An [EquivocationVoteAuthenticator] holds a pair of votes from the same sender for two
+/// different proposals in the same round, period and step.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EquivocationVoteAuthenticator {
+    /// Sender address.
+    #[serde(rename = "snd")]
+    pub sender_addr: Address,
+
+    /// Unauthenticated credential.
+    #[serde(rename = "cred")]
+    pub unauthenticated_credential: UnauthenticatedCredential,
+
+    /// A signature pair for the two equivocating votes.
+    #[serde(default, rename = "sigs")]
+    pub sigs: Vec<OneTimeSignature>,
+
+    /// The two proposals the sender voted for.
+    #[serde(default, rename = "props")]
+    pub proposals: Vec<ProposalValue>,
+}
+
 /// Wraps a transaction in a signature.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SignedTransaction {
@@ -258,12 +484,206 @@ pub struct SignedTransaction {
     #[serde(rename = "msig", default, skip_serializing_if = "Option::is_none")]
     pub multisig: Option<MultisigSignature>,
 
+    /// The address authorized to sign the transaction, set when it isn't the sender's address.
+    #[serde(rename = "sgnr", default, skip_serializing_if = "Option::is_none")]
+    pub auth_addr: Option<Address>,
+
+    #[serde(rename = "txn")]
+    pub transaction: Transaction,
+}
+
+/// [EncodedBlockCert] defines how get-block response encodes a block and its certificate.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EncodedBlockCert {
+    /// Block header data.
+    pub block: BlockHeaderMsgPack,
+    /// Certificate.
+    pub cert: Certificate,
+}
+
+/// A Certificate contains a cryptographic proof that agreement was reached on a
+/// given block in a given round.
+///
+/// When a client first joins the network or has fallen behind and needs to catch
+/// up, certificates allow the client to verify that a block someone gives them
+/// is the real one.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Certificate {
+    /// Proposal value.
+    #[serde(default, rename = "prop")]
+    pub proposal: Option<CertificateProposal>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CertificateProposal {
+    /// Block header's hash.
+    #[serde(rename = "dig")]
+    pub block_digest: HashDigest,
+}
+
+/// BlockHeader
+/// Deserialized from MessagePack format.
+///
+/// See [block.go](https://github.com/algorand/go-algorand/blob/master/data/bookkeeping/block.go) for more details.
+// Comments below are simply copied from the go-algorand repo.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BlockHeaderMsgPack {
+    /// RewardsLevel specifies how many rewards, in MicroAlgos, have been distributed
+    /// to each config.Protocol.RewardUnit of MicroAlgos since genesis.
+    #[serde(default)]
+    pub earn: u64,
+
+    /// The FeeSink accepts transaction fees. It can only spend to the incentive pool.
+    #[serde(default, rename = "fees")]
+    pub fee_sink: Option<HashDigest>,
+
+    /// The number of leftover MicroAlgos after the distribution of RewardsRate/rewardUnits
+    /// MicroAlgos for every reward unit in the next round.
+    #[serde(default, rename = "frac")]
+    pub leftover_fraction: u64,
+
+    /// Genesis ID to which this block belongs.
+    #[serde(default, rename = "gen")]
+    pub genensis_id: String,
+
+    /// Genesis hash to which this block belongs.
+    #[serde(default, rename = "gh")]
+    pub genesis_id_hash: Option<HashDigest>,
+
+    /// The hash of the previous block.
+    #[serde(default, rename = "prev")]
+    pub prevous_block_hash: Option<HashDigest>,
+
+    /// Current protocol.
+    #[serde(default, rename = "proto")]
+    pub protocol_current: String,
+
+    /// The number of new MicroAlgos added to the participation stake from rewards at the next round.
+    #[serde(default, rename = "rate")]
+    pub rewards_rate: u64,
+
+    /// Round represents a protocol round index.
+    #[serde(default, rename = "rnd")]
+    pub round: Round,
+
+    /// The round at which the RewardsRate will be recalculated.
+    #[serde(default, rename = "rwcalr")]
+    pub rewards_rate_recalc_round: u64,
+
+    /// The RewardsPool accepts periodic injections from the FeeSink and continually
+    /// redistributes them to addresses as rewards.
+    #[serde(default, rename = "rwd")]
+    pub rewards_pool: Option<HashDigest>,
+
+    /// Sortition seed.
+    #[serde(rename = "seed", default)]
+    pub sortition_seed: Option<Ed25519Seed>,
+
+    /// TimeStamp in seconds since epoch.
+    #[serde(default, rename = "ts")]
+    pub timestamp: i64,
+
+    /// Root of transaction merkle tree using SHA512_256 hash function.
+    /// This commitment is computed based on the PaysetCommit type specified in the block's consensus protocol.
+    #[serde(default, rename = "txn")]
+    pub tx_merke_root_hash: Option<HashDigest>,
+
+    /// Root of transaction vector commitment merkle tree using SHA256 hash function.
+    #[serde(default, rename = "txn256")]
+    pub tx_merke_root_hash256: Option<HashDigest>,
+
+    /// Tracks the status of the state proofs, keyed by the state proof type.
+    #[serde(default, rename = "spt", skip_serializing_if = "HashMap::is_empty")]
+    pub state_proof_tracking: HashMap<u64, StateProofTrackingData>,
+
+    /// Transactions included in the block.
+    #[serde(default, rename = "txns", skip_serializing_if = "Vec::is_empty")]
+    pub payset: Vec<SignedTxnInBlock>,
+}
+
+/// Tracks the state proofs of a single type in a block header.
+///
+/// See [block.go](https://github.com/algorand/go-algorand/blob/master/data/bookkeeping/block.go) for more details.
+// Comments below are simply copied from the go-algorand repo.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct StateProofTrackingData {
+    /// StateProofVotersCommitment is the root of a vector commitment containing the online
+    /// accounts that will help sign a state proof. The VC root, and the state proof, happen
+    /// on blocks that are a multiple of ConsensusParams.StateProofRounds.
+    #[serde(default, rename = "v", with = "serde_bytes")]
+    pub voters_commitment: Vec<u8>,
+
+    /// StateProofOnlineTotalWeight is the total number of microalgos held by the online
+    /// accounts during the StateProof round.
+    #[serde(default, rename = "t")]
+    pub online_total_weight: u64,
+
+    /// StateProofNextRound is the next round for which we will accept a StateProof
+    /// transaction.
+    #[serde(default, rename = "n")]
+    pub next_round: Round,
+}
+
+impl BlockHeaderMsgPack {
+    /// Returns the block's transactions with their genesis ID and hash restored.
+    pub fn transactions(&self) -> Vec<SignedTransaction> {
+        self.payset
+            .iter()
+            .map(|stib| {
+                let mut transaction = stib.transaction.clone();
+                if stib.has_genesis_id {
+                    transaction.genesis_id = self.genensis_id.clone();
+                }
+                // The genesis hash is required by all current protocols, so the node strips it
+                // without setting the flag. It's restored whenever it's missing, as in
+                // go-algorand's DecodeSignedTxn.
+                if stib.has_genesis_hash || stib.transaction.genesis_hash == HashDigest::default() {
+                    transaction.genesis_hash = self.genesis_id_hash.unwrap_or_default();
+                }
+
+                SignedTransaction {
+                    sig: stib.sig,
+                    multisig: stib.multisig.clone(),
+                    auth_addr: stib.auth_addr,
+                    transaction,
+                }
+            })
+            .collect()
+    }
+}
+
+/// A signed transaction as it's encoded in a block.
+///
+/// See [signedtxn.go](https://github.com/algorand/go-algorand/blob/master/data/transactions/signedtxn.go) for more details.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SignedTxnInBlock {
+    /// Ed25519 signature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sig: Option<Ed25519Signature>,
+
+    /// Multisig signature.
+    #[serde(rename = "msig", default, skip_serializing_if = "Option::is_none")]
+    pub multisig: Option<MultisigSignature>,
+
+    /// The address authorized to sign the transaction, set when it isn't the sender's address.
+    #[serde(rename = "sgnr", default, skip_serializing_if = "Option::is_none")]
+    pub auth_addr: Option<Address>,
+
+    /// The transaction without the genesis fields it shares with the block.
     #[serde(rename = "txn")]
     pub transaction: Transaction,
+
+    /// The genesis ID was stripped from the transaction.
+    #[serde(default, rename = "hgi")]
+    pub has_genesis_id: bool,
+
+    /// The genesis hash was stripped from the transaction.
+    #[serde(default, rename = "hgh")]
+    pub has_genesis_hash: bool,
 }
 
 /// A transaction that can appear in a block.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Transaction {
     /// Paid by the sender to the FeeSink to prevent denial-of-service. The minimum fee on Algorand
     /// is currently 1000 microAlgos.
@@ -276,7 +696,8 @@ pub struct Transaction {
     pub first_valid: Round,
 
     /// The hash of the genesis block of the network for which the transaction is valid.
-    #[serde(rename = "gh")]
+    // Transactions in a block omit the hash when it matches the block's genesis hash.
+    #[serde(default, rename = "gh")]
     pub genesis_hash: HashDigest,
 
     /// The ending round for which the transaction is valid. After this round, the transaction will
@@ -327,17 +748,29 @@ pub struct Transaction {
 }
 
 /// Enum containing the types of transactions and their specific fields.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(tag = "type")]
 pub enum TransactionType {
     /// Payment transaction.
     #[serde(rename = "pay")]
     Payment(Payment),
+
+    /// Asset transfer transaction.
+    #[serde(rename = "axfer")]
+    AssetTransfer(AssetTransfer),
+
+    /// Key registration transaction.
+    #[serde(rename = "keyreg")]
+    KeyRegistration(KeyRegistration),
+
+    /// Application call transaction.
+    #[serde(rename = "appl")]
+    ApplicationCall(ApplicationCall),
     // Maybe include more types here later.
 }
 
 /// Fields for a payment transaction.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Payment {
     /// The address of the account that receives the amount.
     #[serde(rename = "rcv")]
@@ -354,11 +787,456 @@ pub struct Payment {
     pub close_remainder_to: Option<Address>,
 }
 
+/// Fields for an asset transfer transaction.
+///
+/// The same transaction type is used for an asset opt-in (a zero amount transfer to self),
+/// an asset revocation (clawback) and closing out an asset holding.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AssetTransfer {
+    /// The unique ID of the asset to be transferred.
+    #[serde(rename = "xaid")]
+    pub asset_id: u64,
+
+    /// The amount of the asset to be transferred. A zero amount transferred to self allocates
+    /// that asset in the account's asset map (opt-in).
+    #[serde(rename = "aamt", default)]
+    pub amount: u64,
+
+    /// The recipient of the asset transfer.
+    #[serde(rename = "arcv")]
+    pub receiver: Address,
+
+    /// The sender of the transfer. The regular sender field should be used and this one set to
+    /// the zero value for regular transfers between accounts. If this value is nonzero, it
+    /// indicates a clawback transaction where the sender is the asset's clawback address and
+    /// the asset sender is the address from which the funds will be withdrawn.
+    #[serde(rename = "asnd", default, skip_serializing_if = "Option::is_none")]
+    pub asset_sender: Option<Address>,
+
+    /// Specify this field to remove the asset holding from the sender account and reduce the
+    /// account's minimum balance (i.e. opt-out of the asset).
+    #[serde(rename = "aclose", default, skip_serializing_if = "Option::is_none")]
+    pub close_to: Option<Address>,
+}
+
+/// Fields for a key registration transaction.
+///
+/// A key registration with all participation fields omitted marks the account offline.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct KeyRegistration {
+    /// The root participation public key.
+    #[serde(rename = "votekey", default, skip_serializing_if = "Option::is_none")]
+    pub vote_pk: Option<Ed25519PublicKey>,
+
+    /// The VRF public key.
+    #[serde(rename = "selkey", default, skip_serializing_if = "Option::is_none")]
+    pub selection_pk: Option<VrfPublicKey>,
+
+    /// The 64 byte state proof public key commitment.
+    #[serde(rename = "sprfkey", default, skip_serializing_if = "Option::is_none")]
+    pub state_proof_pk: Option<StateProofCommitment>,
+
+    /// The first round that the participation key is valid.
+    #[serde(rename = "votefst", default)]
+    pub vote_first: Round,
+
+    /// The last round that the participation key is valid.
+    #[serde(rename = "votelst", default)]
+    pub vote_last: Round,
+
+    /// This is the dilution for the 2-level participation key.
+    #[serde(rename = "votekd", default)]
+    pub vote_key_dilution: u64,
+
+    /// All new Algorand accounts are participating by default. This means that they earn rewards.
+    /// Mark an account nonparticipating by setting this value to true and this account will no
+    /// longer earn rewards. It is unlikely that you will ever need to do this and exists mainly
+    /// for economic-related functions on the network.
+    #[serde(rename = "nonpart", default)]
+    pub nonparticipation: bool,
+}
+
+/// Fields for an application call transaction.
+///
+/// An application call with a zero application ID creates a new application.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ApplicationCall {
+    /// ID of the application being configured or empty if creating.
+    #[serde(rename = "apid", default)]
+    pub app_id: u64,
+
+    /// Defines what additional actions occur with the transaction: NoOp (0), OptIn (1),
+    /// CloseOut (2), ClearState (3), UpdateApplication (4) or DeleteApplication (5).
+    #[serde(rename = "apan", default)]
+    pub on_completion: u64,
+
+    /// Transaction specific arguments accessed from the application's approval program and
+    /// clear state program.
+    #[serde(rename = "apaa", default, skip_serializing_if = "Vec::is_empty")]
+    pub app_args: Vec<serde_bytes::ByteBuf>,
+
+    /// Logic executed for every application transaction, except when on-completion is set to
+    /// ClearState.
+    #[serde(
+        rename = "apap",
+        with = "serde_bytes",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub approval_program: Vec<u8>,
+
+    /// Logic executed for application transactions with on-completion set to ClearState.
+    #[serde(
+        rename = "apsu",
+        with = "serde_bytes",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub clear_state_program: Vec<u8>,
+
+    /// Lists the applications in addition to the application ID whose global states may be
+    /// accessed by this application's programs.
+    #[serde(rename = "apfa", default, skip_serializing_if = "Vec::is_empty")]
+    pub foreign_apps: Vec<u64>,
+
+    /// Lists the assets whose parameters may be accessed by this application's programs.
+    #[serde(rename = "apas", default, skip_serializing_if = "Vec::is_empty")]
+    pub foreign_assets: Vec<u64>,
+
+    /// List of accounts in addition to the sender that may be accessed from the application's
+    /// programs.
+    #[serde(rename = "apat", default, skip_serializing_if = "Vec::is_empty")]
+    pub accounts: Vec<Address>,
+}
+
+/// Domain separation prefix for a transaction ID.
+const HASH_ID_TRANSACTION: &[u8] = b"TX";
+/// Domain separation prefix for a transaction group ID.
+const HASH_ID_TX_GROUP: &[u8] = b"TG";
+
+/// The maximum length of a transaction note.
+pub const MAX_TXN_NOTE_LEN: usize = 1024;
+
+impl Transaction {
+    /// Returns the transaction ID - the hash of the canonically encoded transaction.
+    pub fn id(&self) -> HashDigest {
+        hash_obj(HASH_ID_TRANSACTION, self)
+    }
+
+    /// Creates a payment with fixed fee and validity rounds on a private network.
+    #[cfg(test)]
+    pub fn test_payment(sender: Address, receiver: Address, amount: u64) -> Transaction {
+        Transaction {
+            fee: 1000,
+            first_valid: Round(1),
+            genesis_hash: HashDigest([1; HASH_LEN]),
+            last_valid: Round(1001),
+            sender,
+            genesis_id: "private-v1".into(),
+            group: None,
+            lease: None,
+            note: Vec::new(),
+            rekey_to: None,
+            txn_type: TransactionType::Payment(Payment {
+                receiver,
+                amount,
+                close_remainder_to: None,
+            }),
+        }
+    }
+
+    /// Sets the note, which can't be longer than [MAX_TXN_NOTE_LEN] bytes.
+    pub fn with_note(mut self, note: Vec<u8>) -> Result<Self, String> {
+        if note.len() > MAX_TXN_NOTE_LEN {
+            return Err(format!("the note is too long: {} bytes", note.len()));
+        }
+
+        self.note = note;
+        Ok(self)
+    }
+
+    /// Sets the lease, which must be exactly 32 bytes long.
+    pub fn with_lease(mut self, lease: &[u8]) -> Result<Self, String> {
+        let lease = lease
+            .try_into()
+            .map_err(|_| format!("wrong lease length: {}", lease.len()))?;
+
+        self.lease = Some(HashDigest(lease));
+        Ok(self)
+    }
+}
+
+/// A [TxGroup] describes a group of transactions that must appear together in a specific order
+/// in a block.
+#[derive(Debug, Serialize)]
+struct TxGroup {
+    /// The IDs of the transactions in the group.
+    #[serde(rename = "txlist")]
+    tx_group_hashes: Vec<HashDigest>,
+}
+
+/// Computes the group ID of an atomic transaction group.
+///
+/// The group field of each transaction is ignored, as it's done in go-algorand.
+pub fn compute_group_id(txns: &[Transaction]) -> HashDigest {
+    let group = TxGroup {
+        tx_group_hashes: txns
+            .iter()
+            .map(|txn| {
+                Transaction {
+                    group: None,
+                    ..txn.clone()
+                }
+                .id()
+            })
+            .collect(),
+    };
+
+    hash_obj(HASH_ID_TX_GROUP, &group)
+}
+
+/// Hashes the canonically encoded object prefixed with the domain separation prefix.
+pub fn hash_obj<T: Serialize>(hash_id: &[u8], obj: &T) -> HashDigest {
+    CanonicalValue::from_obj(obj)
+        .expect("couldn't encode the object")
+        .hash(hash_id)
+}
+
+/// Encodes the object in the canonical msgpack form used by go-algorand: map keys are sorted and
+/// empty values are omitted.
+pub fn canonical_encode<T: Serialize>(obj: &T) -> io::Result<Vec<u8>> {
+    CanonicalValue::from_obj(obj)?.encode()
+}
+
+/// A generic msgpack value which serializes in the canonical form.
+#[derive(Debug, PartialEq)]
+pub(crate) enum CanonicalValue {
+    Nil,
+    Bool(bool),
+    Uint(u64),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bin(Vec<u8>),
+    Array(Vec<CanonicalValue>),
+    Map(Vec<(String, CanonicalValue)>),
+}
+
+impl CanonicalValue {
+    /// Converts the object into its canonical form.
+    pub(crate) fn from_obj<T: Serialize>(obj: &T) -> io::Result<Self> {
+        let encoded = rmp_serde::to_vec_named(obj).map_err(|e| invalid_data!(e.to_string()))?;
+        let value: Self =
+            rmp_serde::from_slice(&encoded).map_err(|e| invalid_data!(e.to_string()))?;
+
+        Ok(value.canonicalize())
+    }
+
+    /// Decodes the value as it is, without converting it into the canonical form.
+    pub(crate) fn decode(bytes: &[u8]) -> io::Result<Self> {
+        rmp_serde::from_slice(bytes).map_err(|e| invalid_data!(e.to_string()))
+    }
+
+    /// Encodes the value as it is.
+    pub(crate) fn encode(&self) -> io::Result<Vec<u8>> {
+        rmp_serde::to_vec(self).map_err(|e| invalid_data!(e.to_string()))
+    }
+
+    /// Hashes the encoded value prefixed with the domain separation prefix.
+    pub(crate) fn hash(&self, hash_id: &[u8]) -> HashDigest {
+        let mut data = hash_id.to_vec();
+        data.extend(self.encode().expect("couldn't encode the value"));
+
+        let mut hash = [0; HASH_LEN];
+        hash.copy_from_slice(&sha2::Sha512_256::digest(data));
+        HashDigest(hash)
+    }
+
+    /// Returns the value of the map entry with the given key.
+    pub(crate) fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Map(m) => m.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Inserts the entry into the map, replacing the value of an existing key. A new key is put
+    /// in its place in the sorted keys. The value is inserted as it is, so it may be empty.
+    pub(crate) fn insert(&mut self, key: &str, value: Self) -> io::Result<()> {
+        let m = match self {
+            Self::Map(m) => m,
+            _ => return Err(invalid_data!("the value isn't a map")),
+        };
+
+        match m.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value,
+            None => {
+                let pos = m.partition_point(|(k, _)| k.as_str() < key);
+                m.insert(pos, (key.into(), value));
+            }
+        }
+        Ok(())
+    }
+
+    /// Zero values are omitted from the maps by the canonical encoding.
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Nil => true,
+            Self::Bool(b) => !b,
+            Self::Uint(n) => *n == 0,
+            Self::Int(n) => *n == 0,
+            Self::Float(n) => *n == 0.0,
+            Self::Str(s) => s.is_empty(),
+            Self::Bin(b) => b.is_empty(),
+            Self::Array(a) => a.is_empty(),
+            Self::Map(m) => m.is_empty(),
+        }
+    }
+
+    /// Sorts map keys and removes empty map values recursively.
+    fn canonicalize(self) -> Self {
+        match self {
+            Self::Array(a) => Self::Array(a.into_iter().map(Self::canonicalize).collect()),
+            Self::Map(m) => {
+                let mut m: Vec<_> = m
+                    .into_iter()
+                    .map(|(k, v)| (k, v.canonicalize()))
+                    .filter(|(_, v)| !v.is_empty())
+                    .collect();
+                m.sort_by(|(a, _), (b, _)| a.cmp(b));
+                Self::Map(m)
+            }
+            value => value,
+        }
+    }
+}
+
+impl Serialize for CanonicalValue {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Nil => serializer.serialize_unit(),
+            Self::Bool(b) => serializer.serialize_bool(*b),
+            Self::Uint(n) => serializer.serialize_u64(*n),
+            Self::Int(n) => serializer.serialize_i64(*n),
+            Self::Float(n) => serializer.serialize_f64(*n),
+            Self::Str(s) => serializer.serialize_str(s),
+            Self::Bin(b) => serializer.serialize_bytes(b),
+            Self::Array(a) => {
+                let mut state = serializer.serialize_seq(Some(a.len()))?;
+                for v in a {
+                    state.serialize_element(v)?;
+                }
+                state.end()
+            }
+            Self::Map(m) => {
+                let mut state = serializer.serialize_map(Some(m.len()))?;
+                for (k, v) in m {
+                    state.serialize_entry(k, v)?;
+                }
+                state.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CanonicalValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(CanonicalValueVisitor)
+    }
+}
+
+/// Visitor for any msgpack value.
+struct CanonicalValueVisitor;
+
+impl<'de> Visitor<'de> for CanonicalValueVisitor {
+    type Value = CanonicalValue;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("expecting a msgpack value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(CanonicalValue::Nil)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(CanonicalValue::Nil)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        CanonicalValue::deserialize(deserializer)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(CanonicalValue::Bool(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(CanonicalValue::Uint(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        // Non-negative integers are encoded as unsigned ones.
+        Ok(match u64::try_from(v) {
+            Ok(v) => CanonicalValue::Uint(v),
+            Err(_) => CanonicalValue::Int(v),
+        })
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(CanonicalValue::Float(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(CanonicalValue::Str(v.to_string()))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(CanonicalValue::Bin(v.to_vec()))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = Vec::new();
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(CanonicalValue::Array(values))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entries = Vec::new();
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(CanonicalValue::Map(entries))
+    }
+}
+
 const CHECKSUM_LEN: usize = 4;
 const HASH_LEN: usize = 32;
 
+/// Domain separation prefix for a multisig address.
+const HASH_ID_MULTISIG_ADDR: &[u8] = b"MultisigAddr";
+
 /// Public key address.
-#[derive(Copy, Clone, Eq, PartialEq)]
+///
+/// Addresses are ordered lexicographically by their bytes.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub struct Address([u8; HASH_LEN]);
 
 impl Address {
@@ -367,6 +1245,32 @@ impl Address {
         Address(bytes)
     }
 
+    /// Returns the raw bytes of the address.
+    pub fn as_bytes(&self) -> &[u8; HASH_LEN] {
+        &self.0
+    }
+
+    /// Returns the public key the address is derived from.
+    pub fn public_key(&self) -> Ed25519PublicKey {
+        Ed25519PublicKey(self.0)
+    }
+
+    /// Creates the address of a newly generated key pair.
+    #[cfg(test)]
+    pub fn random() -> Address {
+        Address::new(SigningKey::generate(&mut OsRng).verifying_key().to_bytes())
+    }
+
+    /// Creates the address of a key pair derived from the seed.
+    ///
+    /// The same seed always gives the same address, so test accounts are reproducible.
+    #[cfg(test)]
+    pub fn from_seed(seed: u64) -> Address {
+        let secret = sha2::Sha512_256::digest(seed.to_be_bytes());
+        let key = SigningKey::from_bytes(&secret.into());
+        Address::new(key.verifying_key().to_bytes())
+    }
+
     /// Decode an address from a base64 string with a checksum.
     pub fn from_string(string: &str) -> Result<Address, String> {
         let checksum_address = match BASE32_NOPAD.decode(string.as_bytes()) {
@@ -430,9 +1334,44 @@ impl<'de> Deserialize<'de> for Address {
 }
 
 /// A SHA512_256 hash.
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
 pub struct HashDigest(pub [u8; 32]);
 
+impl HashDigest {
+    /// Decode a hash digest from a base64 string, as shown by [Display].
+    pub fn from_base64(string: &str) -> Result<HashDigest, String> {
+        let bytes = BASE64
+            .decode(string.as_bytes())
+            .map_err(|err| format!("error decoding base64: {err:?}"))?;
+
+        Self::from_slice(&bytes)
+    }
+
+    /// Decode a hash digest from an unpadded base32 string, as shown by [Debug].
+    pub fn from_base32(string: &str) -> Result<HashDigest, String> {
+        let bytes = BASE32_NOPAD
+            .decode(string.as_bytes())
+            .map_err(|err| format!("error decoding base32: {err:?}"))?;
+
+        Self::from_slice(&bytes)
+    }
+
+    fn from_slice(bytes: &[u8]) -> Result<HashDigest, String> {
+        bytes
+            .try_into()
+            .map(HashDigest)
+            .map_err(|_| format!("wrong hash digest length: {}", bytes.len()))
+    }
+}
+
+impl FromStr for HashDigest {
+    type Err = String;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Self::from_base64(string)
+    }
+}
+
 impl Display for HashDigest {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", BASE64.encode(&self.0))
@@ -509,6 +1448,37 @@ pub struct MultisigSignature {
     pub version: u8,
 }
 
+impl MultisigSignature {
+    /// Creates an unsigned multisig preimage for the given public keys.
+    pub fn new(version: u8, threshold: u8, keys: &[Ed25519PublicKey]) -> Self {
+        Self {
+            subsigs: keys
+                .iter()
+                .map(|key| MultisigSubsig {
+                    key: *key,
+                    sig: None,
+                })
+                .collect(),
+            threshold,
+            version,
+        }
+    }
+
+    /// Returns the address of the multisig account.
+    pub fn address(&self) -> Address {
+        let mut preimage = HASH_ID_MULTISIG_ADDR.to_vec();
+        preimage.push(self.version);
+        preimage.push(self.threshold);
+        for subsig in &self.subsigs {
+            preimage.extend(subsig.key.0);
+        }
+
+        let mut bytes = [0; HASH_LEN];
+        bytes.copy_from_slice(&sha2::Sha512_256::digest(preimage));
+        Address::new(bytes)
+    }
+}
+
 impl Serialize for MultisigSignature {
     fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
     where
@@ -595,6 +1565,52 @@ impl<'de> Deserialize<'de> for Ed25519Seed {
     }
 }
 
+/// Verifiable Random Function public key.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct VrfPublicKey(pub [u8; 32]);
+
+impl Serialize for VrfPublicKey {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&self.0[..])
+    }
+}
+
+impl<'de> Deserialize<'de> for VrfPublicKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(VrfPublicKey(deserializer.deserialize_bytes(VisitorU8_32)?))
+    }
+}
+
+/// A state proof key commitment (the root of the merkle signature scheme keys).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct StateProofCommitment(pub [u8; 64]);
+
+impl Serialize for StateProofCommitment {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&self.0[..])
+    }
+}
+
+impl<'de> Deserialize<'de> for StateProofCommitment {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(StateProofCommitment(
+            deserializer.deserialize_bytes(SignatureVisitor)?,
+        ))
+    }
+}
+
 /// Verifiable Random Function proof.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct VrfProof(pub [u8; 80]);
@@ -676,8 +1692,41 @@ impl<'de> Visitor<'de> for VisitorU8_32 {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use super::*;
 
+    #[test]
+    fn valid_proposals() {
+        for round in [Round(0), Round(7)] {
+            let proposal = ProposalPayload::test_proposal(round);
+
+            assert_eq!(proposal.round(), round);
+            assert_eq!(proposal.validate_basic(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn invalid_proposals() {
+        let mut no_protocol = ProposalPayload::test_proposal(Round(7));
+        no_protocol.protocol_current.clear();
+        assert!(no_protocol.validate_basic().is_err());
+
+        for timestamp in [-1, i64::MAX] {
+            let mut implausible_timestamp = ProposalPayload::test_proposal(Round(7));
+            implausible_timestamp.timestamp = timestamp;
+            assert!(implausible_timestamp.validate_basic().is_err());
+        }
+
+        let mut genesis_with_prev = ProposalPayload::test_proposal(Round(0));
+        genesis_with_prev.prevous_block_hash = Some(HashDigest::from(&b"prev".to_vec()));
+        assert!(genesis_with_prev.validate_basic().is_err());
+
+        let mut no_prev = ProposalPayload::test_proposal(Round(7));
+        no_prev.prevous_block_hash = None;
+        assert!(no_prev.validate_basic().is_err());
+    }
+
     #[test]
     fn address_decode() {
         let s = "737777777777777777777777777777777777777777777777777UFEJ2CI";
@@ -692,4 +1741,489 @@ mod tests {
 
         assert!(Address::from_string(invalid_csum).is_err());
     }
+
+    #[test]
+    fn address_from_seed() {
+        let addr = Address::from_seed(1);
+        assert_eq!(addr, Address::from_seed(1));
+        assert_eq!(
+            addr.encode_string(),
+            "KFIMPSMMPVVFD4P5PP6RLYW2AQJGESG24D4R7FWJVXRDELH4YW4LGXOMNA"
+        );
+        assert_ne!(addr, Address::from_seed(2));
+
+        let decoded = Address::from_string(&addr.encode_string()).expect("invalid address");
+        assert_eq!(decoded, addr);
+    }
+
+    #[test]
+    fn random_addresses_differ() {
+        let addr = Address::random();
+        assert_ne!(addr, Address::random());
+        assert_eq!(Address::from_string(&addr.encode_string()), Ok(addr));
+    }
+
+    #[test]
+    fn address_hash_and_ord() {
+        let (low, high) = (Address::new([1; HASH_LEN]), Address::new([2; HASH_LEN]));
+
+        let set = HashSet::from([high, low, high]);
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&low) && set.contains(&high));
+
+        // Only the last byte differs, so it decides the order.
+        let mut last_byte_high = [1; HASH_LEN];
+        last_byte_high[HASH_LEN - 1] = 3;
+        let mid = Address::new(last_byte_high);
+
+        let mut addrs = vec![high, mid, low];
+        addrs.sort();
+        assert_eq!(addrs, [low, mid, high]);
+        assert_eq!(mid.as_bytes(), &last_byte_high);
+    }
+
+    #[test]
+    fn hash_digest_base64_round_trip() {
+        let hash = HashDigest::from(&b"genesis".to_vec());
+
+        let encoded = hash.to_string();
+        assert_eq!(HashDigest::from_base64(&encoded), Ok(hash));
+        assert_eq!(encoded.parse::<HashDigest>(), Ok(hash));
+
+        // A valid base64 string which is too short for a hash digest.
+        assert!(HashDigest::from_base64("AAAA").is_err());
+    }
+
+    #[test]
+    fn hash_digest_base32_round_trip() {
+        let hash = HashDigest::from(&b"genesis".to_vec());
+
+        let encoded = format!("{hash:?}");
+        assert_eq!(HashDigest::from_base32(&encoded), Ok(hash));
+
+        // Base32 isn't accepted where base64 is expected.
+        assert!(encoded.parse::<HashDigest>().is_err());
+    }
+
+    #[test]
+    fn round_arithmetic() {
+        let round = Round(10);
+
+        assert_eq!(round.checked_add(5), Some(Round(15)));
+        assert_eq!(Round(u64::MAX).checked_add(1), None);
+        assert_eq!(round.saturating_sub(4), Round(6));
+        assert_eq!(round.saturating_sub(11), Round(0));
+        assert_eq!(round.to_string(), "10");
+    }
+
+    #[test]
+    fn round_is_encoded_as_an_integer() {
+        let bytes = rmp_serde::to_vec(&Round(1005)).expect("couldn't encode the round");
+        assert_eq!(bytes, [0xcd, 0x03, 0xed]);
+        let round: Round = rmp_serde::from_slice(&bytes).expect("couldn't decode the round");
+        assert_eq!(round, Round(1005));
+
+        // The REST API reports rounds as JSON numbers.
+        let round: Round = serde_json::from_str("42").expect("couldn't decode the round");
+        assert_eq!(round, Round(42));
+        assert_eq!(serde_json::to_string(&round).unwrap(), "42");
+    }
+
+    #[test]
+    fn multisig_address() {
+        let keys: Vec<_> = [
+            "DN7MBMCL5JQ3PFUQS7TMX5AH4EEKOBJVDUF4TCV6WERATKFLQF4MQUPZTA",
+            "BFRTECKTOOE7A5LHCF3TTEOH2A7BW46IYT2SX5VP6ANKEXHZYJY77SJTVM",
+            "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+        ]
+        .iter()
+        .map(|addr| Address::from_string(addr).unwrap().public_key())
+        .collect();
+
+        let msig = MultisigSignature::new(1, 2, &keys);
+        assert_eq!(
+            msig.address().encode_string(),
+            "RWJLJCMQAFZ2ATP2INM2GZTKNL6OULCCUBO5TQPXH3V2KR4AG7U5UA5JNM"
+        );
+    }
+
+    #[test]
+    fn application_call_encode_decode() {
+        // An approval program which always succeeds: `#pragma version 2; int 1`.
+        let program = vec![0x02, 0x20, 0x01, 0x01, 0x22];
+
+        let txn = Transaction {
+            fee: 1000,
+            first_valid: Round(1),
+            genesis_hash: HashDigest([2; HASH_LEN]),
+            last_valid: Round(1001),
+            sender: Address::new([3; HASH_LEN]),
+            genesis_id: "private-v1".into(),
+            group: None,
+            lease: None,
+            note: Vec::new(),
+            rekey_to: None,
+            txn_type: TransactionType::ApplicationCall(ApplicationCall {
+                app_args: vec![serde_bytes::ByteBuf::from(b"arg".to_vec())],
+                approval_program: program.clone(),
+                clear_state_program: program.clone(),
+                accounts: vec![Address::new([4; HASH_LEN])],
+                ..Default::default()
+            }),
+        };
+
+        let encoded = rmp_serde::to_vec_named(&txn).expect("couldn't encode the transaction");
+        let decoded: Transaction =
+            rmp_serde::from_slice(&encoded).expect("couldn't decode the transaction");
+        assert_eq!(txn, decoded);
+
+        let canonical = canonical_encode(&txn).expect("couldn't encode the transaction");
+        let decoded: Transaction =
+            rmp_serde::from_slice(&canonical).expect("couldn't decode the transaction");
+        let app_call = match decoded.txn_type {
+            TransactionType::ApplicationCall(app_call) => app_call,
+            txn_type => panic!("unexpected transaction type: {txn_type:?}"),
+        };
+        assert_eq!(app_call.app_id, 0);
+        assert_eq!(app_call.on_completion, 0);
+        assert_eq!(
+            app_call.app_args,
+            [serde_bytes::ByteBuf::from(b"arg".to_vec())]
+        );
+        assert_eq!(app_call.approval_program, program);
+        assert_eq!(app_call.clear_state_program, program);
+        assert_eq!(app_call.accounts, [Address::new([4; HASH_LEN])]);
+        assert!(app_call.foreign_apps.is_empty() && app_call.foreign_assets.is_empty());
+
+        // The app ID and on-completion are zero for an app-create, so they are omitted.
+        let value = CanonicalValue::decode(&canonical).expect("invalid canonical encoding");
+        assert_eq!(value.get("type"), Some(&CanonicalValue::Str("appl".into())));
+        assert_eq!(value.get("apap"), Some(&CanonicalValue::Bin(program)));
+        assert!(value.get("apid").is_none() && value.get("apan").is_none());
+    }
+
+    #[test]
+    fn asset_transfer_encode_decode() {
+        let txn = Transaction {
+            fee: 1000,
+            first_valid: Round(1),
+            genesis_hash: HashDigest([2; HASH_LEN]),
+            last_valid: Round(1001),
+            sender: Address::new([3; HASH_LEN]),
+            genesis_id: "private-v1".into(),
+            group: None,
+            lease: None,
+            note: Vec::new(),
+            rekey_to: None,
+            txn_type: TransactionType::AssetTransfer(AssetTransfer {
+                asset_id: 42,
+                amount: 500,
+                receiver: Address::new([4; HASH_LEN]),
+                asset_sender: None,
+                close_to: Some(Address::new([5; HASH_LEN])),
+            }),
+        };
+
+        let encoded = rmp_serde::to_vec_named(&txn).expect("couldn't encode the transaction");
+        let decoded: Transaction =
+            rmp_serde::from_slice(&encoded).expect("couldn't decode the transaction");
+
+        assert_eq!(txn, decoded);
+    }
+
+    // A key registration transaction in the canonical encoding used by go-algorand. The
+    // keyreg_canonical_encoding_matches_kmd kmd test checks the encoding of these fields against
+    // kmd.
+    const KEYREG_TXN: &str = concat!(
+        // A map with 13 entries.
+        "8d",
+        // fee: 1000
+        "a3666565cd03e8",
+        // fv: 10
+        "a266760a",
+        // gen: "private-v1"
+        "a367656eaa707269766174652d7631",
+        // gh: [1; 32]
+        "a26768c420",
+        "0101010101010101010101010101010101010101010101010101010101010101",
+        // lv: 1010
+        "a26c76cd03f2",
+        // selkey: [2; 32]
+        "a673656c6b6579c420",
+        "0202020202020202020202020202020202020202020202020202020202020202",
+        // snd: [3; 32]
+        "a3736e64c420",
+        "0303030303030303030303030303030303030303030303030303030303030303",
+        // sprfkey: [4; 64]
+        "a7737072666b6579c440",
+        "0404040404040404040404040404040404040404040404040404040404040404",
+        "0404040404040404040404040404040404040404040404040404040404040404",
+        // type: "keyreg"
+        "a474797065a66b6579726567",
+        // votefst: 10
+        "a7766f74656673740a",
+        // votekd: 10000
+        "a6766f74656b64cd2710",
+        // votekey: [5; 32]
+        "a7766f74656b6579c420",
+        "0505050505050505050505050505050505050505050505050505050505050505",
+        // votelst: 1000000
+        "a7766f74656c7374ce000f4240",
+    );
+
+    // A synthetic block header with state proof tracking data, assembled by hand from the field
+    // names of go-algorand's BlockHeader. The values are made up, a block fetched from the node is
+    // checked in the `fetched_block_keeps_state_proof_tracking` node test.
+    const STATE_PROOF_BLOCK: &str = concat!(
+        // A map with 3 entries.
+        "83",
+        // proto: "future"
+        "a570726f746fa6667574757265",
+        // rnd: 256
+        "a3726e64cd0100",
+        // spt: a map with a single entry for the basic state proof type (0)
+        "a37370748100",
+        // A map with 3 entries.
+        "83",
+        // n: 512
+        "a16ecd0200",
+        // t: 2_000_000_000_000_000
+        "a174cf00071afd498d0000",
+        // v: [9; 32]
+        "a176c420",
+        "0909090909090909090909090909090909090909090909090909090909090909",
+    );
+
+    #[test]
+    fn block_header_with_state_proof_tracking_decode() {
+        let bytes = data_encoding::HEXLOWER
+            .decode(STATE_PROOF_BLOCK.as_bytes())
+            .expect("invalid hex sample");
+
+        let block: BlockHeaderMsgPack =
+            rmp_serde::from_slice(&bytes).expect("couldn't decode the block header");
+        assert_eq!(block.round, Round(256));
+        let expected = StateProofTrackingData {
+            voters_commitment: vec![9; 32],
+            online_total_weight: 2_000_000_000_000_000,
+            next_round: Round(512),
+        };
+        assert_eq!(block.state_proof_tracking.get(&0), Some(&expected));
+
+        // The tracking data survives re-encoding.
+        let encoded = rmp_serde::to_vec_named(&block).expect("couldn't encode the block header");
+        let decoded: BlockHeaderMsgPack =
+            rmp_serde::from_slice(&encoded).expect("couldn't decode the block header");
+        assert_eq!(decoded.state_proof_tracking, block.state_proof_tracking);
+    }
+
+    #[test]
+    fn key_registration_decode() {
+        let bytes = data_encoding::HEXLOWER
+            .decode(KEYREG_TXN.as_bytes())
+            .expect("invalid hex sample");
+
+        let txn: Transaction =
+            rmp_serde::from_slice(&bytes).expect("couldn't decode the transaction");
+        assert_eq!(txn.sender, Address::new([3; HASH_LEN]));
+        assert_eq!(
+            txn.txn_type,
+            TransactionType::KeyRegistration(KeyRegistration {
+                vote_pk: Some(Ed25519PublicKey([5; 32])),
+                selection_pk: Some(VrfPublicKey([2; 32])),
+                state_proof_pk: Some(StateProofCommitment([4; 64])),
+                vote_first: Round(10),
+                vote_last: Round(1_000_000),
+                vote_key_dilution: 10_000,
+                nonparticipation: false,
+            })
+        );
+
+        let encoded = rmp_serde::to_vec_named(&txn).expect("couldn't encode the transaction");
+        let decoded: Transaction =
+            rmp_serde::from_slice(&encoded).expect("couldn't decode the transaction");
+        assert_eq!(txn, decoded);
+
+        // The canonical encoding must reproduce the original bytes.
+        assert_eq!(
+            canonical_encode(&txn).expect("couldn't encode the transaction"),
+            bytes
+        );
+    }
+
+    fn payment_txn(sender: u8, receiver: u8, amount: u64) -> Transaction {
+        Transaction::test_payment(
+            Address::new([sender; HASH_LEN]),
+            Address::new([receiver; HASH_LEN]),
+            amount,
+        )
+    }
+
+    #[test]
+    fn transaction_id() {
+        let txn = payment_txn(2, 3, 1000);
+
+        assert_eq!(
+            format!("{:?}", txn.id()),
+            "SXB5PYA6LZQ6CWGML2A34L432ZHBUG7QZL5MGZGEMCMIZI3TEBWA"
+        );
+    }
+
+    // The payment created by payment_txn(2, 3, 1000), hand-written with sorted keys and without
+    // empty fields. The canonical_encoding_matches_kmd kmd test checks the encoding against kmd.
+    const PAYMENT_TXN: &str = concat!(
+        // A map with 9 entries.
+        "89",
+        // amt: 1000
+        "a3616d74cd03e8",
+        // fee: 1000
+        "a3666565cd03e8",
+        // fv: 1
+        "a2667601",
+        // gen: "private-v1"
+        "a367656eaa707269766174652d7631",
+        // gh: [1; 32]
+        "a26768c420",
+        "0101010101010101010101010101010101010101010101010101010101010101",
+        // lv: 1001
+        "a26c76cd03e9",
+        // rcv: [3; 32]
+        "a3726376c420",
+        "0303030303030303030303030303030303030303030303030303030303030303",
+        // snd: [2; 32]
+        "a3736e64c420",
+        "0202020202020202020202020202020202020202020202020202020202020202",
+        // type: "pay"
+        "a474797065a3706179",
+    );
+
+    #[test]
+    fn payment_canonical_encoding() {
+        let bytes = data_encoding::HEXLOWER
+            .decode(PAYMENT_TXN.as_bytes())
+            .expect("invalid hex sample");
+        let txn = payment_txn(2, 3, 1000);
+
+        // The empty note and the missing close address are omitted.
+        assert_eq!(
+            canonical_encode(&txn).expect("couldn't encode the transaction"),
+            bytes
+        );
+    }
+
+    #[test]
+    fn note_length_is_validated() {
+        let txn = payment_txn(2, 3, 1000);
+
+        let txn = txn
+            .with_note(vec![b'z'; MAX_TXN_NOTE_LEN])
+            .expect("a note of the maximum length should be accepted");
+        assert_eq!(txn.note.len(), MAX_TXN_NOTE_LEN);
+
+        assert!(txn.with_note(vec![b'z'; MAX_TXN_NOTE_LEN + 1]).is_err());
+    }
+
+    #[test]
+    fn lease_length_is_validated() {
+        let txn = payment_txn(2, 3, 1000);
+
+        assert!(txn.clone().with_lease(&[7; HASH_LEN - 1]).is_err());
+        assert!(txn.clone().with_lease(&[7; HASH_LEN + 1]).is_err());
+
+        let txn = txn
+            .with_lease(&[7; HASH_LEN])
+            .expect("a 32-byte lease should be accepted");
+        assert_eq!(txn.lease, Some(HashDigest([7; HASH_LEN])));
+    }
+
+    #[test]
+    fn group_id() {
+        let mut txns = [payment_txn(2, 3, 1000), payment_txn(3, 2, 2000)];
+        let group_id = compute_group_id(&txns);
+        assert_eq!(
+            group_id.to_string(),
+            "I4UHt58Lcz85PxblimSbu6kHly+oibkV7RLvGBuFmJg="
+        );
+
+        // The group ID doesn't depend on the group fields.
+        for txn in &mut txns {
+            txn.group = Some(group_id);
+        }
+        assert_eq!(compute_group_id(&txns), group_id);
+    }
+
+    /// The subset of the block fields needed to restore the transactions.
+    #[derive(Serialize)]
+    struct Block {
+        gen: &'static str,
+        gh: HashDigest,
+        rnd: Round,
+        txns: Vec<SignedTxnInBlock>,
+    }
+
+    /// Returns a block with a single transaction stripped of its genesis fields, along with the
+    /// ID of the original transaction.
+    fn block_with_stripped_txn(has_genesis_hash: bool) -> (BlockHeaderMsgPack, HashDigest) {
+        let genesis_hash = HashDigest([7; 32]);
+        let mut txn = Transaction {
+            sender: Address::new([1; 32]),
+            fee: 1000,
+            first_valid: Round(1),
+            last_valid: Round(1000),
+            note: Vec::new(),
+            genesis_id: "ziggurat".into(),
+            genesis_hash,
+            group: None,
+            lease: None,
+            txn_type: TransactionType::Payment(Payment {
+                receiver: Address::new([2; 32]),
+                amount: 1000,
+                close_remainder_to: None,
+            }),
+            rekey_to: None,
+        };
+        let tx_id = txn.id();
+
+        // The block strips the genesis fields of its transactions.
+        txn.genesis_id = String::new();
+        txn.genesis_hash = HashDigest::default();
+        let block = Block {
+            gen: "ziggurat",
+            gh: genesis_hash,
+            rnd: Round(3),
+            txns: vec![SignedTxnInBlock {
+                sig: Some(Ed25519Signature([3; 64])),
+                multisig: None,
+                auth_addr: None,
+                transaction: txn,
+                has_genesis_id: true,
+                has_genesis_hash,
+            }],
+        };
+        let block = rmp_serde::to_vec_named(&block).expect("couldn't encode the block");
+
+        (
+            rmp_serde::from_slice(&block).expect("couldn't decode the block"),
+            tx_id,
+        )
+    }
+
+    #[test]
+    fn restore_genesis_fields_of_block_transactions() {
+        let (block, tx_id) = block_with_stripped_txn(true);
+        let txns = block.transactions();
+        assert_eq!(txns.len(), 1);
+        assert_eq!(txns[0].sig, Some(Ed25519Signature([3; 64])));
+        assert_eq!(txns[0].transaction.id(), tx_id);
+    }
+
+    #[test]
+    fn restore_required_genesis_hash_of_block_transactions() {
+        // The node doesn't set the flag for the genesis hash, as it's required by the protocol.
+        let (block, tx_id) = block_with_stripped_txn(false);
+        let txns = block.transactions();
+        assert_eq!(txns.len(), 1);
+        assert_eq!(txns[0].transaction.genesis_hash, HashDigest([7; 32]));
+        assert_eq!(txns[0].transaction.id(), tx_id);
+    }
 }
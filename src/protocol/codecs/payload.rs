@@ -6,9 +6,13 @@ use tracing::Span;
 
 use crate::protocol::{
     codecs::{
-        msgpack::{AgreementVote, HashDigest, NetPrioResponse, ProposalPayload, SignedTransaction},
+        msgpack::{
+            AgreementVote, HashDigest, NetPrioResponse, ProposalPayload, SignedTransaction,
+            VoteBundle,
+        },
         tagmsg::Tag,
-        topic::{MsgOfInterest, TopicCodec, TopicMsgResp, UniEnsBlockReq},
+        topic::{MsgOfInterest, TopicCodec, TopicMsgResp, UniCatchupReq, UniEnsBlockReq},
+        CodecError,
     },
     invalid_data,
 };
@@ -22,13 +26,29 @@ pub enum Payload {
     AgreementVote(Box<AgreementVote>),
     Ping(PingData),
     PingReply(PingData),
+    UniCatchupReq(UniCatchupReq),
     UniEnsBlockReq(UniEnsBlockReq),
     TopicMsgResp(TopicMsgResp),
     NetPrioResponse(NetPrioResponse),
     MsgDigestSkip(HashDigest),
     Transaction(SignedTransaction),
+    VoteBundle(Box<VoteBundle>),
     RawBytes(Vec<u8>),
-    NotImplemented,
+    /// WebSocket ping control frame data.
+    WsPing(Vec<u8>),
+    /// WebSocket pong control frame data.
+    WsPong(Vec<u8>),
+    /// WebSocket close control frame, sent without a status code.
+    WsClose,
+    /// Bytes written to the wire as is, without the tag and the WebSocket framing.
+    RawFrame(Vec<u8>),
+    /// A message with an unknown tag or a tag whose payload isn't decoded yet.
+    ///
+    /// The payload bytes are kept as is, so the message can still be inspected or sent again.
+    Unknown {
+        tag: Tag,
+        raw: Vec<u8>,
+    },
 }
 
 /// Payload data for the [Ping] and [PingReply] messages.
@@ -74,32 +94,47 @@ impl Decoder for PayloadCodec {
                 self.topic.tag = Some(tag);
                 self.topic
                     .decode(src)?
-                    .ok_or_else(|| invalid_data!("payload not found"))?
-            }
-            Tag::ProposalPayload => {
-                Payload::ProposalPayload(rmp_serde::from_slice(src).map_err(|_| {
-                    invalid_data!("couldn't deserialize the ProposalPayload message")
-                })?)
+                    .ok_or(CodecError::Truncated("payload"))?
             }
-            Tag::AgreementVote => Payload::AgreementVote(
+            Tag::ProposalPayload => Payload::ProposalPayload(
                 rmp_serde::from_slice(src)
-                    .map_err(|_| invalid_data!("couldn't deserialize the AgreementVote message"))?,
+                    .map_err(|_| CodecError::Deserialize("ProposalPayload"))?,
+            ),
+            Tag::AgreementVote => Payload::AgreementVote(
+                rmp_serde::from_slice(src).map_err(|_| CodecError::Deserialize("AgreementVote"))?,
             ),
             Tag::MsgDigestSkip => Payload::MsgDigestSkip(HashDigest(
                 src.to_vec()
                     .try_into()
-                    .map_err(|_| invalid_data!("invalid hash digest for MsgDigestSkip"))?,
+                    .map_err(|_| CodecError::Deserialize("MsgDigestSkip"))?,
             )),
-            Tag::NetPrioResponse => {
-                Payload::NetPrioResponse(rmp_serde::from_slice(src).map_err(|_| {
-                    invalid_data!("couldn't deserialize the NetPrioResponse message")
-                })?)
+            Tag::NetPrioResponse => Payload::NetPrioResponse(
+                rmp_serde::from_slice(src)
+                    .map_err(|_| CodecError::Deserialize("NetPrioResponse"))?,
+            ),
+            Tag::Ping | Tag::PingReply => {
+                let nonce = src
+                    .to_vec()
+                    .try_into()
+                    .map_err(|_| CodecError::Deserialize("Ping"))?;
+                if tag == Tag::Ping {
+                    Payload::Ping(PingData { nonce })
+                } else {
+                    Payload::PingReply(PingData { nonce })
+                }
             }
             Tag::Txn => Payload::Transaction(
-                rmp_serde::from_slice(src)
-                    .map_err(|_| invalid_data!("couldn't deserialize the Txn message"))?,
+                rmp_serde::from_slice(src).map_err(|_| CodecError::Deserialize("Txn"))?,
+            ),
+            Tag::VoteBundle => Payload::VoteBundle(
+                rmp_serde::from_slice(src).map_err(|_| CodecError::Deserialize("VoteBundle"))?,
             ),
-            _ => return Ok(Some(Payload::NotImplemented)),
+            _ => {
+                return Ok(Some(Payload::Unknown {
+                    tag,
+                    raw: src.split().to_vec(),
+                }))
+            }
         };
 
         tracing::debug!(parent: &self.span, "decoded the payload");
@@ -112,7 +147,7 @@ impl Encoder<Payload> for PayloadCodec {
 
     fn encode(&mut self, message: Payload, dst: &mut BytesMut) -> Result<(), Self::Error> {
         let raw_data = match message {
-            Payload::MsgOfInterest(_) | Payload::UniEnsBlockReq(_) => {
+            Payload::MsgOfInterest(_) | Payload::UniCatchupReq(_) | Payload::UniEnsBlockReq(_) => {
                 return self
                     .topic
                     .encode(message, dst)
@@ -122,9 +157,11 @@ impl Encoder<Payload> for PayloadCodec {
                 .map_err(|_| invalid_data!("couldn't encode a payload message"))?,
             Payload::AgreementVote(av) => rmp_serde::encode::to_vec(&av)
                 .map_err(|_| invalid_data!("couldn't encode an agreement vote message"))?,
+            Payload::VoteBundle(vb) => rmp_serde::encode::to_vec_named(&vb)
+                .map_err(|_| invalid_data!("couldn't encode a vote bundle message"))?,
             Payload::MsgDigestSkip(hash) => hash.0.to_vec(),
-            Payload::Ping(ping) => ping.nonce.to_vec(),
-            Payload::RawBytes(data) => data.to_vec(),
+            Payload::Ping(ping) | Payload::PingReply(ping) => ping.nonce.to_vec(),
+            Payload::RawBytes(data) | Payload::Unknown { raw: data, .. } => data.to_vec(),
             Payload::NetPrioResponse(npr) => rmp_serde::encode::to_vec(&npr)
                 .map_err(|_| invalid_data!("couldn't encode a NetPrioResponse message"))?,
             _ => unimplemented!(),
@@ -134,3 +171,203 @@ impl Encoder<Payload> for PayloadCodec {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signer, SigningKey};
+    use rand::rngs::OsRng;
+
+    use super::*;
+    use crate::protocol::{
+        codecs::{
+            msgpack::{canonical_encode, Address, Ed25519Signature, Round, Transaction, VrfProof},
+            tagmsg::{TagMsgCodec, TAG_LEN},
+        },
+        net_prio::{NetPrioSigner, OneTimeSigner, DEFAULT_KEY_DILUTION},
+    };
+
+    fn decode_payload(tag: Tag, data: &[u8]) -> io::Result<Option<Payload>> {
+        let mut codec = PayloadCodec::new(Span::none());
+        codec.tag = Some(tag);
+
+        codec.decode(&mut BytesMut::from(data))
+    }
+
+    fn encode_payload(payload: Payload) -> io::Result<BytesMut> {
+        let mut dst = BytesMut::new();
+        PayloadCodec::new(Span::none()).encode(payload, &mut dst)?;
+
+        Ok(dst)
+    }
+
+    #[test]
+    fn encode_decode_ping_reply() {
+        let nonce = [1, 2, 3, 4, 5, 6, 7, 8];
+
+        let data = encode_payload(Payload::PingReply(PingData { nonce }))
+            .expect("couldn't encode the ping reply");
+        assert_eq!(&data[..], nonce);
+
+        let payload = decode_payload(Tag::PingReply, &data)
+            .expect("couldn't decode the ping reply")
+            .expect("missing payload");
+        assert!(matches!(payload, Payload::PingReply(reply) if reply.nonce == nonce));
+    }
+
+    #[test]
+    fn unknown_payloads_preserve_the_bytes() {
+        let data = [0xde, 0xad, 0xbe, 0xef];
+
+        for tag in [Tag::UnknownMsg, Tag::StateProofSig] {
+            let payload = decode_payload(tag, &data)
+                .expect("couldn't decode the payload")
+                .expect("missing payload");
+            assert!(
+                matches!(&payload, Payload::Unknown { tag: t, raw } if *t == tag && raw == &data),
+                "unexpected payload: {payload:?}"
+            );
+
+            // The message is passed through unchanged when it's sent again.
+            let mut msg = BytesMut::new();
+            TagMsgCodec::new(Span::none())
+                .encode(payload, &mut msg)
+                .expect("couldn't encode the payload");
+            assert_eq!(&msg[..TAG_LEN], tag.get_tag_str().as_bytes());
+            assert_eq!(&msg[TAG_LEN..], data);
+        }
+    }
+
+    #[test]
+    fn encode_decode_net_prio_response() {
+        let signer = NetPrioSigner {
+            signer: OneTimeSigner::generate(DEFAULT_KEY_DILUTION),
+            sender: Address::new([1; 32]),
+            round: Round(42),
+        };
+        let npr = signer.respond("challenge".into());
+
+        let data = encode_payload(Payload::NetPrioResponse(npr.clone()))
+            .expect("couldn't encode the response");
+        let payload = decode_payload(Tag::NetPrioResponse, &data)
+            .expect("couldn't decode the response")
+            .expect("missing payload");
+        let decoded = match payload {
+            Payload::NetPrioResponse(decoded) => decoded,
+            payload => panic!("unexpected payload: {payload:?}"),
+        };
+
+        assert_eq!(decoded.response.nonce, "challenge");
+        assert_eq!(decoded.round, npr.round);
+        assert_eq!(decoded.sender_addr, npr.sender_addr);
+        assert_eq!(decoded.sig.sig, npr.sig.sig);
+        assert_eq!(decoded.sig.pk, npr.sig.pk);
+        assert_eq!(decoded.sig.pk2sig, npr.sig.pk2sig);
+    }
+
+    #[test]
+    fn decode_signed_transaction() {
+        let key = SigningKey::generate(&mut OsRng);
+        let sender = Address::new(key.verifying_key().to_bytes());
+        let txn = Transaction::test_payment(sender, Address::new([3; 32]), 1000);
+
+        // Transactions are signed with the ID prefix, as go-algorand does.
+        let mut msg = b"TX".to_vec();
+        msg.extend(canonical_encode(&txn).expect("couldn't encode the transaction"));
+        let sig = Ed25519Signature(key.sign(&msg).to_bytes());
+
+        let stxn = SignedTransaction {
+            sig: Some(sig),
+            multisig: None,
+            auth_addr: None,
+            transaction: txn.clone(),
+        };
+        let data = canonical_encode(&stxn).expect("couldn't encode the signed transaction");
+
+        let payload = decode_payload(Tag::Txn, &data)
+            .expect("couldn't decode the signed transaction")
+            .expect("missing payload");
+        let stxn = match payload {
+            Payload::Transaction(stxn) => stxn,
+            payload => panic!("unexpected payload: {payload:?}"),
+        };
+
+        assert_eq!(stxn.sig, Some(sig));
+        assert_eq!(stxn.transaction, txn);
+    }
+
+    // A vote bundle with a single vote, assembled by hand from the field names of go-algorand's
+    // unauthenticatedBundle in the canonical encoding.
+    const VOTE_BUNDLE: &str = concat!(
+        // A map with 3 entries.
+        "83",
+        // rnd: 7
+        "a3726e6407",
+        // step: 2
+        "a47374657002",
+        // vote: an array with a single vote
+        "a4766f746591",
+        // A map with 3 entries.
+        "83",
+        // cred: {pf: [8; 80]}
+        "a46372656481a27066c450",
+        "0808080808080808080808080808080808080808080808080808080808080808",
+        "0808080808080808080808080808080808080808080808080808080808080808",
+        "08080808080808080808080808080808",
+        // sig: a map with 6 entries
+        "a373696786",
+        // p: [2; 32]
+        "a170c420",
+        "0202020202020202020202020202020202020202020202020202020202020202",
+        // p1s: [4; 64]
+        "a3703173c440",
+        "0404040404040404040404040404040404040404040404040404040404040404",
+        "0404040404040404040404040404040404040404040404040404040404040404",
+        // p2: [3; 32]
+        "a27032c420",
+        "0303030303030303030303030303030303030303030303030303030303030303",
+        // p2s: [5; 64]
+        "a3703273c440",
+        "0505050505050505050505050505050505050505050505050505050505050505",
+        "0505050505050505050505050505050505050505050505050505050505050505",
+        // ps: [0; 64], go-algorand always encodes it
+        "a27073c440",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        // s: [1; 64]
+        "a173c440",
+        "0101010101010101010101010101010101010101010101010101010101010101",
+        "0101010101010101010101010101010101010101010101010101010101010101",
+        // snd: [6; 32]
+        "a3736e64c420",
+        "0606060606060606060606060606060606060606060606060606060606060606",
+    );
+
+    #[test]
+    fn decode_vote_bundle() {
+        let data = data_encoding::HEXLOWER
+            .decode(VOTE_BUNDLE.as_bytes())
+            .expect("invalid hex sample");
+
+        let payload = decode_payload(Tag::VoteBundle, &data)
+            .expect("couldn't decode the vote bundle")
+            .expect("missing payload");
+        let bundle = match payload {
+            Payload::VoteBundle(bundle) => bundle,
+            payload => panic!("unexpected payload: {payload:?}"),
+        };
+
+        assert_eq!((bundle.round, bundle.period, bundle.step), (Round(7), 0, 2));
+        assert!(bundle.proposal.is_none());
+        assert!(bundle.equivocation_votes.is_empty());
+
+        assert_eq!(bundle.votes.len(), 1);
+        let vote = &bundle.votes[0];
+        assert_eq!(vote.sender_addr, Address::new([6; 32]));
+        assert_eq!(
+            vote.unauthenticated_credential.vrf_proof,
+            Some(VrfProof([8; 80]))
+        );
+        assert_eq!(vote.sig.sig, Ed25519Signature([1; 64]));
+        assert_eq!(vote.sig.pk2sig, Ed25519Signature([5; 64]));
+    }
+}
@@ -5,7 +5,10 @@ use tokio_util::codec::{Decoder, Encoder};
 use tracing::*;
 
 use crate::protocol::{
-    codecs::payload::{Payload, PayloadCodec},
+    codecs::{
+        payload::{Payload, PayloadCodec},
+        CodecError,
+    },
     invalid_data,
 };
 
@@ -25,6 +28,7 @@ pub enum Tag {
     StateProofSig,
     TopicMsgResp,
     Txn,
+    UniCatchupReq,
     UniEnsBlockReq,
     VoteBundle,
 
@@ -46,19 +50,40 @@ impl Tag {
             Self::StateProofSig => "SP",
             Self::TopicMsgResp => "TS",
             Self::Txn => "TX",
+            Self::UniCatchupReq => "UC",
             Self::UniEnsBlockReq => "UE",
             Self::VoteBundle => "VB",
             Self::RawBytes => "",
         }
     }
+
+    /// Returns an iterator over the official go-algorand tags, in the declaration order.
+    pub fn iter() -> impl Iterator<Item = Self> {
+        // The exhaustive match makes a new tag fail to compile until it's added here.
+        std::iter::successors(Some(Self::UnknownMsg), |tag| match tag {
+            Self::UnknownMsg => Some(Self::AgreementVote),
+            Self::AgreementVote => Some(Self::MsgOfInterest),
+            Self::MsgOfInterest => Some(Self::MsgDigestSkip),
+            Self::MsgDigestSkip => Some(Self::NetPrioResponse),
+            Self::NetPrioResponse => Some(Self::Ping),
+            Self::Ping => Some(Self::PingReply),
+            Self::PingReply => Some(Self::ProposalPayload),
+            Self::ProposalPayload => Some(Self::StateProofSig),
+            Self::StateProofSig => Some(Self::TopicMsgResp),
+            Self::TopicMsgResp => Some(Self::Txn),
+            Self::Txn => Some(Self::UniCatchupReq),
+            Self::UniCatchupReq => Some(Self::UniEnsBlockReq),
+            Self::UniEnsBlockReq => Some(Self::VoteBundle),
+            Self::VoteBundle | Self::RawBytes => None,
+        })
+    }
 }
 
 impl TryFrom<Bytes> for Tag {
     type Error = io::Error;
 
     fn try_from(tag: bytes::Bytes) -> Result<Self, Self::Error> {
-        let tag = std::str::from_utf8(&tag)
-            .map_err(|_| invalid_data!("couldn't convert the tag to a UTF-8 string"))?;
+        let tag = std::str::from_utf8(&tag).map_err(|_| CodecError::InvalidUtf8("tag"))?;
 
         Self::try_from(tag)
     }
@@ -80,9 +105,10 @@ impl TryFrom<&str> for Tag {
             "SP" => Self::StateProofSig,
             "TS" => Self::TopicMsgResp,
             "TX" => Self::Txn,
+            "UC" => Self::UniCatchupReq,
             "UE" => Self::UniEnsBlockReq,
             "VB" => Self::VoteBundle,
-            _ => return Err(invalid_data!("unexpected tag")),
+            _ => return Err(CodecError::UnknownTag(tag.into()).into()),
         })
     }
 }
@@ -95,17 +121,26 @@ impl From<&Payload> for Tag {
             Payload::AgreementVote(_) => Self::AgreementVote,
             Payload::Ping(_) => Self::Ping,
             Payload::PingReply(_) => Self::PingReply,
+            Payload::UniCatchupReq(_) => Self::UniCatchupReq,
             Payload::UniEnsBlockReq(_) => Self::UniEnsBlockReq,
             Payload::TopicMsgResp(_) => Self::TopicMsgResp,
             Payload::NetPrioResponse(_) => Self::NetPrioResponse,
             Payload::MsgDigestSkip(_) => Self::MsgDigestSkip,
             Payload::Transaction(_) => Self::Txn,
+            Payload::VoteBundle(_) => Self::VoteBundle,
             Payload::RawBytes(_) => Self::RawBytes,
-            Payload::NotImplemented => Self::UnknownMsg,
+            Payload::Unknown { tag, .. } => tag,
+            // WebSocket control frames and raw frames are not tagged.
+            Payload::WsPing(_) | Payload::WsPong(_) | Payload::WsClose | Payload::RawFrame(_) => {
+                Self::UnknownMsg
+            }
         }
     }
 }
 
+/// Length of a tag at the beginning of each Algod message.
+pub const TAG_LEN: usize = 2;
+
 /// [TagMsgCodec] is the codec for tagged Algod messages.
 #[derive(Clone)]
 pub struct TagMsgCodec {
@@ -130,8 +165,9 @@ impl Decoder for TagMsgCodec {
     type Error = io::Error;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        const TAG_LEN: usize = 2;
-
+        if src.len() < TAG_LEN {
+            return Err(CodecError::Truncated("tag").into());
+        }
         let tag = Tag::try_from(src.split_to(TAG_LEN).freeze())?;
         debug!(parent: &self.span, "decoded a tag: {:?}", tag);
 
@@ -1,18 +1,13 @@
-use std::{
-    collections::HashSet,
-    io::{self, ErrorKind},
-};
+use std::{collections::HashSet, io};
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use tokio_util::codec::{Decoder, Encoder};
 
-use crate::{
-    protocol::{
-        codecs::{msgpack::Round, payload::Payload, tagmsg::Tag},
-        invalid_data,
-    },
-    // TODO(Rqnsom): See if we can move these to msgpack.rs - should be possible easily
-    setup::node::rest_api::message::{BlockHeaderMsgPack, Certificate},
+use crate::protocol::codecs::{
+    msgpack::{BlockHeaderMsgPack, Certificate, Round},
+    payload::Payload,
+    tagmsg::Tag,
+    CodecError,
 };
 
 /// Topic keys.
@@ -24,6 +19,20 @@ const TOPIC_KEY_ERROR: &str = "Error";
 const TOPIC_KEY_NONCE: &str = "nonce";
 const TOPIC_KEY_CERT_DATA: &str = "certData";
 const TOPIC_KEY_BLOCK_DATA: &str = "blockData";
+// Not a go-algorand topic, see UniEnsBlockReq::max_blocks.
+const TOPIC_KEY_MAX_BLOCKS: &str = "maxBlocks";
+
+/// The maximum number of topics in a single message.
+const MAX_TOPICS: usize = 32;
+
+/// The maximum length of a topic key.
+const MAX_TOPIC_KEY_LEN: usize = 64;
+
+/// The largest topic value length algod accepts.
+const MAX_VARINT_VALUE: usize = (u32::MAX / 4) as usize;
+
+/// The maximum number of tags in a single [MsgOfInterest] message.
+const MAX_MSG_OF_INTEREST_TAGS: usize = 32;
 
 /// [MsgOfInterest] contains a tag list in which the node is interested.
 #[derive(Debug, Clone)]
@@ -32,6 +41,23 @@ pub struct MsgOfInterest {
     pub tags: HashSet<Tag>,
 }
 
+impl MsgOfInterest {
+    /// Creates a [MsgOfInterest] from the tag list, which can't have more than 32 tags.
+    ///
+    /// An empty list is valid and unsubscribes from all messages.
+    pub fn new<I: IntoIterator<Item = Tag>>(tags: I) -> io::Result<Self> {
+        // As in algod, the limit applies to the list before the duplicates are dropped.
+        let tags = tags.into_iter().collect::<Vec<_>>();
+        if tags.len() > MAX_MSG_OF_INTEREST_TAGS {
+            return Err(CodecError::TooManyTags(tags.len()).into());
+        }
+
+        Ok(Self {
+            tags: tags.into_iter().collect(),
+        })
+    }
+}
+
 /// Universal block request types.
 #[derive(Debug, Copy, Clone)]
 pub enum UniEnsBlockReqType {
@@ -52,6 +78,26 @@ pub struct UniEnsBlockReq {
     pub round_key: Round,
     /// Nonce for a unique request identification.
     pub nonce: u64,
+    /// An experimental key asking for the number of consecutive blocks, starting with the
+    /// `round_key` block.
+    ///
+    /// go-algorand doesn't define ranged block requests and the node doesn't recognise the key,
+    /// so it's only useful for checking that unknown topics are ignored. The topic is omitted
+    /// when it's not set.
+    pub max_blocks: Option<u64>,
+}
+
+/// Universal catchup request message.
+///
+/// The request was replaced by the [UniEnsBlockReq] message in go-algorand and is kept only for
+/// backward compatibility. The response uses the same topics as the [UniEnsBlockReq] response,
+/// so it's decoded as [TopicMsgResp::UniEnsBlockRsp].
+#[derive(Debug, Clone)]
+pub struct UniCatchupReq {
+    /// Round in which block was created.
+    pub round_key: Round,
+    /// Nonce for a unique request identification.
+    pub nonce: u64,
 }
 
 /// [TopicMsgResp] contains all possible responses which are received in the form of topics.
@@ -74,6 +120,18 @@ pub struct UniEnsBlockRsp {
     pub request_hash: Bytes,
 }
 
+impl UniEnsBlockRsp {
+    /// Returns the round of the block, if the response contains one.
+    pub fn block_round(&self) -> Option<Round> {
+        self.block.as_ref().map(|block| block.round)
+    }
+
+    /// Returns whether the response contains a certificate.
+    pub fn has_cert(&self) -> bool {
+        self.cert.is_some()
+    }
+}
+
 /// Universal error response message.
 #[derive(Debug, Clone, Default)]
 pub struct ErrorRsp {
@@ -88,21 +146,28 @@ impl TryFrom<Vec<Topic>> for MsgOfInterest {
 
     fn try_from(mut topics: Vec<Topic>) -> Result<Self, Self::Error> {
         if topics.len() != 1 {
-            return Err(invalid_data!("expected a single topic"));
+            return Err(CodecError::UnexpectedTopics("MsgOfInterest").into());
         }
 
         let tag_topic = topics.pop().unwrap();
         if tag_topic.key != TOPIC_KEY_TAGS {
-            return Err(invalid_data!("expected 'tags' topic"));
+            return Err(CodecError::UnexpectedTopics("MsgOfInterest").into());
+        }
+
+        let tags = String::from_utf8(tag_topic.value.to_vec())
+            .map_err(|_| CodecError::InvalidUtf8("tags value"))?;
+
+        // An empty value means no tags, rather than a single empty tag.
+        if tags.is_empty() {
+            return Self::new([]);
         }
 
-        let tags: HashSet<_> = String::from_utf8(tag_topic.value.to_vec())
-            .map_err(|_| invalid_data!("'tags' value is not a valid UTF-8 string"))?
+        let tags = tags
             .split(',')
             .map(Tag::try_from)
-            .collect::<io::Result<_>>()?;
+            .collect::<io::Result<Vec<_>>>()?;
 
-        Ok(Self { tags })
+        Self::new(tags)
     }
 }
 
@@ -110,13 +175,18 @@ impl TryFrom<Vec<Topic>> for TopicMsgResp {
     type Error = io::Error;
 
     fn try_from(topics: Vec<Topic>) -> Result<Self, Self::Error> {
-        // Simply use the number of topics to identify underlying messages.
-        match topics.len() {
-            2 => Ok(TopicMsgResp::ErrorRsp(ErrorRsp::try_from(topics)?)),
-            3 => Ok(TopicMsgResp::UniEnsBlockRsp(Box::new(
+        // The number of topics is ambiguous, as a response with only a block or only a certificate
+        // has as many topics as an error response, so the keys identify the underlying messages.
+        let has_key = |key: &str| topics.iter().any(|topic| topic.key == key);
+
+        if has_key(TOPIC_KEY_ERROR) {
+            Ok(TopicMsgResp::ErrorRsp(ErrorRsp::try_from(topics)?))
+        } else if has_key(TOPIC_KEY_BLOCK_DATA) || has_key(TOPIC_KEY_CERT_DATA) {
+            Ok(TopicMsgResp::UniEnsBlockRsp(Box::new(
                 UniEnsBlockRsp::try_from(topics)?,
-            ))),
-            _ => Err(invalid_data!("unexpected number of topics")),
+            )))
+        } else {
+            Err(CodecError::UnexpectedTopics("TopicMsgResp").into())
         }
     }
 }
@@ -131,14 +201,10 @@ impl TryFrom<Vec<Topic>> for ErrorRsp {
             match topic.key.as_str() {
                 TOPIC_KEY_ERROR => {
                     err_rsp.error = String::from_utf8(topic.value.to_vec())
-                        .map_err(|_| invalid_data!("error value is not a valid UTF-8 string"))?
+                        .map_err(|_| CodecError::InvalidUtf8("error value"))?
                 }
                 TOPIC_KEY_HASH => err_rsp.request_hash = topic.value,
-                _ => {
-                    return Err(invalid_data!(
-                        "unexpected topic for an error response message"
-                    ))
-                }
+                _ => return Err(CodecError::UnexpectedTopics("TopicMsgResp").into()),
             }
         }
 
@@ -149,25 +215,21 @@ impl TryFrom<Vec<Topic>> for ErrorRsp {
 impl TryFrom<Vec<Topic>> for UniEnsBlockRsp {
     type Error = io::Error;
 
-    fn try_from(mut topics: Vec<Topic>) -> Result<Self, Self::Error> {
+    fn try_from(topics: Vec<Topic>) -> Result<Self, Self::Error> {
         let mut err_rsp = UniEnsBlockRsp::default();
 
-        while let Some(topic) = topics.pop() {
+        for topic in topics {
             match topic.key.as_str() {
                 TOPIC_KEY_BLOCK_DATA => {
                     err_rsp.block = rmp_serde::from_slice(&topic.value)
-                        .map_err(|_| invalid_data!("couldn't deserialize the block data"))?
+                        .map_err(|_| CodecError::Deserialize("block data"))?
                 }
                 TOPIC_KEY_CERT_DATA => {
                     err_rsp.cert = rmp_serde::from_slice(&topic.value)
-                        .map_err(|_| invalid_data!("couldn't deserialize the cert data"))?
+                        .map_err(|_| CodecError::Deserialize("cert data"))?
                 }
                 TOPIC_KEY_HASH => err_rsp.request_hash = topic.value,
-                _ => {
-                    return Err(invalid_data!(
-                        "unexpected topic for an error response message"
-                    ))
-                }
+                _ => return Err(CodecError::UnexpectedTopics("TopicMsgResp").into()),
             }
         }
 
@@ -185,17 +247,33 @@ impl UniEnsBlockReqType {
     }
 }
 
-impl From<UniEnsBlockReq> for Vec<Topic> {
-    fn from(msg: UniEnsBlockReq) -> Self {
-        let u64_to_bytes = |num| {
-            let mut value = BytesMut::new();
-            value.put_u64_le(num);
-            value.freeze()
+/// Encode a number as a topic value.
+fn u64_to_bytes(num: u64) -> Bytes {
+    let mut value = BytesMut::new();
+    value.put_u64_le(num);
+    value.freeze()
+}
+
+impl From<UniCatchupReq> for Vec<Topic> {
+    fn from(msg: UniCatchupReq) -> Self {
+        let round_key_topic = Topic {
+            key: TOPIC_KEY_ROUND.into(),
+            value: u64_to_bytes(msg.round_key.0),
+        };
+        let nonce_topic = Topic {
+            key: TOPIC_KEY_NONCE.into(),
+            value: u64_to_bytes(msg.nonce),
         };
 
+        vec![round_key_topic, nonce_topic]
+    }
+}
+
+impl From<UniEnsBlockReq> for Vec<Topic> {
+    fn from(msg: UniEnsBlockReq) -> Self {
         let round_key_topic = Topic {
             key: TOPIC_KEY_ROUND.into(),
-            value: u64_to_bytes(msg.round_key),
+            value: u64_to_bytes(msg.round_key.0),
         };
         let data_type_topic = Topic {
             key: TOPIC_KEY_DATA_TYPE.into(),
@@ -206,12 +284,21 @@ impl From<UniEnsBlockReq> for Vec<Topic> {
             value: u64_to_bytes(msg.nonce),
         };
 
-        vec![round_key_topic, data_type_topic, nonce_topic]
+        let mut topics = vec![round_key_topic, data_type_topic, nonce_topic];
+        if let Some(max_blocks) = msg.max_blocks {
+            topics.push(Topic {
+                key: TOPIC_KEY_MAX_BLOCKS.into(),
+                value: u64_to_bytes(max_blocks),
+            });
+        }
+
+        topics
     }
 }
 
 impl From<MsgOfInterest> for Vec<Topic> {
     fn from(msg: MsgOfInterest) -> Self {
+        // An empty tag set is encoded as an empty value of the tags topic.
         let value = msg
             .tags
             .into_iter()
@@ -245,35 +332,39 @@ pub struct TopicCodec {
 impl TopicCodec {
     /// Unmarshall topics from a byte stream.
     fn unmarshall_topics(&mut self, src: &mut BytesMut) -> Result<Vec<Topic>, io::Error> {
+        if src.is_empty() {
+            return Err(CodecError::Truncated("number of topics").into());
+        }
+
         // The maximum number of topics allowed is 32.
-        let num_topics = src.get_u8();
-        let mut topics = Vec::with_capacity(num_topics as usize);
+        let num_topics = src.get_u8() as usize;
+        if num_topics > MAX_TOPICS {
+            return Err(CodecError::UnexpectedTopics("topic list").into());
+        }
+        let mut topics = Vec::with_capacity(num_topics);
 
         for _ in 0..num_topics {
+            if src.is_empty() {
+                return Err(CodecError::Truncated("topic key length").into());
+            }
+
             // Each topic key can be 64 characters long and cannot be size 0.
             let key_len = src.get_u8() as usize;
+            if key_len == 0 || key_len > MAX_TOPIC_KEY_LEN {
+                return Err(CodecError::InvalidTopicLength.into());
+            }
             if key_len > src.len() {
-                return Err(invalid_data!("invalid topic length"));
+                return Err(CodecError::Truncated("topic key").into());
             }
             let key = src.copy_to_bytes(key_len).to_vec();
 
-            // For handled messages so far, the max data size fits into u8/u16 integers.
-            let val_len = if src[0] & 0x80 == 0 {
-                src.get_u8() as usize
-            } else {
-                // The varint functions encode and decode single integer values using a variable-length encoding;
-                // smaller values require fewer bytes. For a specification,
-                // see https://developers.google.com/protocol-buffers/docs/encoding.
-                // Original comment source: https://pkg.go.dev/encoding/binary#pkg-overview
-                let tmp = src.get_u16_le() as usize;
-                ((tmp & 0x7f00) >> 1) | tmp & 0x7f
-            };
+            let val_len = TopicCodec::get_varint(src)?;
             if val_len > src.len() {
-                return Err(invalid_data!("invalid topic length"));
+                return Err(CodecError::Truncated("topic value").into());
             }
             let val = src.copy_to_bytes(val_len).to_vec();
 
-            let key = String::from_utf8(key).map_err(|_| ErrorKind::InvalidData)?;
+            let key = String::from_utf8(key).map_err(|_| CodecError::InvalidUtf8("topic key"))?;
             let value = Bytes::from(val);
             topics.push(Topic { key, value });
         }
@@ -282,7 +373,7 @@ impl TopicCodec {
     }
 
     /// Marshall topics to a byte stream.
-    fn marshall_topics(&mut self, topics: Vec<Topic>) -> BytesMut {
+    fn marshall_topics(&mut self, topics: Vec<Topic>) -> Result<BytesMut, io::Error> {
         // The maximum number of topics allowed is 32.
         let num_topics = topics.len() as u8;
 
@@ -294,54 +385,57 @@ impl TopicCodec {
             raw_data.put_u8(topic.key.len() as u8);
             raw_data.put(topic.key.as_bytes());
 
-            TopicCodec::put_varint(&mut raw_data, topic.value.len());
+            TopicCodec::put_varint(&mut raw_data, topic.value.len())?;
             raw_data.put(topic.value);
         }
 
-        raw_data
+        Ok(raw_data)
     }
 
     // The varint functions encode and decode single integer values using a variable-length encoding;
     // smaller values require fewer bytes. For a specification,
     // see https://developers.google.com/protocol-buffers/docs/encoding.
+    // Original comment source: https://pkg.go.dev/encoding/binary#pkg-overview
     /// Write a variable-length integer value to the byte stream.
-    #[rustfmt::skip]
-    fn put_varint(raw_data: &mut BytesMut, len: usize) {
-        if len < ((u8::MAX as usize) >> 1) {
-            raw_data.put_u8(len as u8);
-            return;
+    ///
+    /// Fails for the lengths algod can't handle.
+    fn put_varint(raw_data: &mut BytesMut, len: usize) -> Result<(), io::Error> {
+        if len > MAX_VARINT_VALUE {
+            return Err(CodecError::InvalidTopicLength.into());
         }
 
-        // TODO(Rqnsom): prepare a more generic approach for varint (maybe create a crate)
-        // or even maybe use existing `unsigned-varint` crate here
-        if len < ((u16::MAX as usize) >> 2) {
-            let len = len as u16;
+        let mut len = len;
+        while len >= 0x80 {
+            // Lower seven bits with the continuation bit set.
+            raw_data.put_u8((len as u8 & 0x7f) | 0x80);
+            len >>= 7;
+        }
+        raw_data.put_u8(len as u8);
 
-            //  byte  =  current byte       | previous byte leftovers | continuation bit
-            let byte1 = (len & 0x7f)                                  | 0x80;
-            let byte2 = (len & 0x5f00) << 1 | ((len & 0x80) << 1);
-            // The first two columns could be combined, but it's more readable this way.
+        Ok(())
+    }
 
-            let len = byte1 | byte2;
-            raw_data.put_u16_le(len);
-            return;
-        }
+    /// Read a variable-length integer value from the byte stream.
+    fn get_varint(src: &mut BytesMut) -> Result<usize, io::Error> {
+        let mut value = 0usize;
 
-        if len < ((u32::MAX as usize) >> 4) {
-            let len = len as u32;
+        for shift in (0..usize::BITS).step_by(7) {
+            if src.is_empty() {
+                return Err(CodecError::Truncated("topic value length").into());
+            }
 
-            //  byte  =  current byte           | previous byte leftovers | continuation bit
-            let byte1 = (len & 0x7f)                                      | 0x80;
-            let byte2 = (len & 0x5f00) << 1     | (len & 0x80) << 1       | 0x8000;
-            let byte3 = (len & 0x1f0000) << 2   | (len & 0xc000) << 2     | 0x800000;
-            let byte4 = (len & 0x0f000000) << 3 | (len & 0xe00000) << 3;
+            let byte = src.get_u8();
+            value |= ((byte & 0x7f) as usize) << shift;
+            if value > MAX_VARINT_VALUE {
+                return Err(CodecError::InvalidTopicLength.into());
+            }
 
-            let len = byte1 | byte2 | byte3 | byte4;
-            raw_data.put_u32_le(len);
-            return;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
         }
 
-        unimplemented!("algod can't handle massive data lengths");
+        Err(CodecError::InvalidTopicLength.into())
     }
 }
 
@@ -351,12 +445,18 @@ impl Decoder for TopicCodec {
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
         let tag = self.tag.expect("tag not set");
-        let topics = self.unmarshall_topics(src)?;
 
         let payload = match tag {
-            Tag::MsgOfInterest => Payload::MsgOfInterest(MsgOfInterest::try_from(topics)?),
-            Tag::TopicMsgResp => Payload::TopicMsgResp(TopicMsgResp::try_from(topics)?),
-            _ => Payload::NotImplemented,
+            Tag::MsgOfInterest => {
+                Payload::MsgOfInterest(MsgOfInterest::try_from(self.unmarshall_topics(src)?)?)
+            }
+            Tag::TopicMsgResp => {
+                Payload::TopicMsgResp(TopicMsgResp::try_from(self.unmarshall_topics(src)?)?)
+            }
+            _ => Payload::Unknown {
+                tag,
+                raw: src.split().to_vec(),
+            },
         };
 
         Ok(Some(payload))
@@ -369,11 +469,12 @@ impl Encoder<Payload> for TopicCodec {
     fn encode(&mut self, message: Payload, dst: &mut BytesMut) -> Result<(), Self::Error> {
         let topics: Vec<Topic> = match message {
             Payload::MsgOfInterest(msg) => msg.into(),
+            Payload::UniCatchupReq(msg) => msg.into(),
             Payload::UniEnsBlockReq(msg) => msg.into(),
             _ => panic!("a topic encoder can only encode topic messages"),
         };
 
-        dst.put(self.marshall_topics(topics));
+        dst.put(self.marshall_topics(topics)?);
 
         Ok(())
     }
@@ -382,6 +483,9 @@ impl Encoder<Payload> for TopicCodec {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        protocol::codecs::msgpack::HashDigest, setup::node::rest_api::message::EncodedBlockCert,
+    };
 
     #[rustfmt::skip]
     const VALID_TOPIC_BYTE_STREAM: [u8; 16] = [
@@ -408,6 +512,36 @@ mod tests {
             .is_err());
     }
 
+    #[test]
+    fn unmarshall_too_many_topics() {
+        let mut bytes_mut = BytesMut::new();
+        bytes_mut.put_u8(33);
+        for _ in 0..33 {
+            bytes_mut.extend_from_slice(&[1, b'k', 1, b'v']);
+        }
+
+        assert!(TopicCodec::default()
+            .unmarshall_topics(&mut bytes_mut)
+            .is_err());
+    }
+
+    #[test]
+    fn unmarshall_zero_length_key() {
+        #[rustfmt::skip]
+        let byte_stream = [
+            1, // one topic
+            0, // invalid key length
+            3, b'v', b'a', b'l', // "val"
+        ];
+
+        let mut bytes_mut = BytesMut::new();
+        bytes_mut.extend_from_slice(&byte_stream);
+
+        assert!(TopicCodec::default()
+            .unmarshall_topics(&mut bytes_mut)
+            .is_err());
+    }
+
     #[test]
     fn unmarshall_valid_byte_stream() {
         let mut bytes_mut = BytesMut::new();
@@ -443,6 +577,299 @@ mod tests {
         let mut bytes_mut = BytesMut::new();
         bytes_mut.extend_from_slice(&VALID_TOPIC_BYTE_STREAM);
 
-        assert_eq!(bytes_mut, TopicCodec::default().marshall_topics(topics));
+        assert_eq!(
+            bytes_mut,
+            TopicCodec::default()
+                .marshall_topics(topics)
+                .expect("couldn't marshall the topics")
+        );
+    }
+
+    #[test]
+    fn marshall_unmarshall_large_topic_value() {
+        let value = Bytes::from(vec![0xab; 300]);
+        let topics = vec![Topic {
+            key: "block".into(),
+            value: value.clone(),
+        }];
+
+        let mut bytes_mut = TopicCodec::default()
+            .marshall_topics(topics)
+            .expect("couldn't marshall the topics");
+        let mut topics = TopicCodec::default()
+            .unmarshall_topics(&mut bytes_mut)
+            .expect("couldn't unmarshall the byte stream");
+
+        assert_eq!(topics.len(), 1);
+        let topic = topics.remove(0);
+        assert_eq!(topic.key, "block");
+        assert_eq!(topic.value, value);
+        assert!(bytes_mut.is_empty());
+    }
+
+    #[test]
+    fn varint_encode_decode() {
+        for len in [
+            0,
+            1,
+            0x7f,
+            0x80,
+            300,
+            0x3fff,
+            0x4000,
+            0x1f_ffff,
+            MAX_VARINT_VALUE,
+        ] {
+            let mut bytes_mut = BytesMut::new();
+            TopicCodec::put_varint(&mut bytes_mut, len).expect("couldn't encode the varint");
+            assert_eq!(TopicCodec::get_varint(&mut bytes_mut).unwrap(), len);
+            assert!(bytes_mut.is_empty());
+        }
+
+        // 300 = 0b10_0101100
+        let mut bytes_mut = BytesMut::new();
+        TopicCodec::put_varint(&mut bytes_mut, 300).expect("couldn't encode the varint");
+        assert_eq!(&bytes_mut[..], &[0xac, 0x02]);
+
+        let err = TopicCodec::put_varint(&mut BytesMut::new(), MAX_VARINT_VALUE + 1).unwrap_err();
+        assert_eq!(
+            CodecError::from_io(&err),
+            Some(&CodecError::InvalidTopicLength)
+        );
+    }
+
+    #[test]
+    fn encode_decode_empty_msg_of_interest() {
+        let msg = MsgOfInterest::new([]).expect("an empty tag list should be valid");
+
+        let mut dst = BytesMut::new();
+        TopicCodec::default()
+            .encode(Payload::MsgOfInterest(msg), &mut dst)
+            .expect("couldn't encode the MsgOfInterest message");
+        assert_eq!(&dst[..], &[1, 4, b't', b'a', b'g', b's', 0]);
+
+        let mut codec = TopicCodec {
+            tag: Some(Tag::MsgOfInterest),
+        };
+        let payload = codec
+            .decode(&mut dst)
+            .expect("couldn't decode the MsgOfInterest message");
+        assert!(matches!(payload, Some(Payload::MsgOfInterest(msg)) if msg.tags.is_empty()));
+    }
+
+    #[test]
+    fn reject_too_many_msg_of_interest_tags() {
+        // The tag list is limited before the duplicates are dropped.
+        let err = MsgOfInterest::new(vec![Tag::Txn; MAX_MSG_OF_INTEREST_TAGS + 1]).unwrap_err();
+        assert_eq!(
+            CodecError::from_io(&err),
+            Some(&CodecError::TooManyTags(MAX_MSG_OF_INTEREST_TAGS + 1))
+        );
+
+        let msg = MsgOfInterest::new(vec![Tag::Txn; MAX_MSG_OF_INTEREST_TAGS])
+            .expect("duplicate tags should be accepted");
+        assert_eq!(msg.tags, HashSet::from([Tag::Txn]));
+
+        let tags = vec![Tag::Txn.get_tag_str(); MAX_MSG_OF_INTEREST_TAGS + 1].join(",");
+        let topics = vec![Topic {
+            key: TOPIC_KEY_TAGS.into(),
+            value: Bytes::from(tags),
+        }];
+        let err = MsgOfInterest::try_from(topics).unwrap_err();
+        assert_eq!(
+            CodecError::from_io(&err),
+            Some(&CodecError::TooManyTags(MAX_MSG_OF_INTEREST_TAGS + 1))
+        );
+
+        let tags = vec![Tag::Txn.get_tag_str(); MAX_MSG_OF_INTEREST_TAGS].join(",");
+        let topics = vec![Topic {
+            key: TOPIC_KEY_TAGS.into(),
+            value: Bytes::from(tags),
+        }];
+        assert!(MsgOfInterest::try_from(topics).is_ok());
+    }
+
+    #[test]
+    fn encode_uni_catchup_req() {
+        let message = Payload::UniCatchupReq(UniCatchupReq {
+            round_key: Round(3),
+            nonce: 7,
+        });
+
+        let mut dst = BytesMut::new();
+        TopicCodec::default()
+            .encode(message, &mut dst)
+            .expect("couldn't encode the UniCatchupReq message");
+
+        let mut topics = TopicCodec::default()
+            .unmarshall_topics(&mut dst)
+            .expect("couldn't unmarshall the UniCatchupReq message");
+        assert_eq!(topics.len(), 2);
+
+        let nonce = topics.pop().unwrap();
+        assert_eq!(nonce.key, TOPIC_KEY_NONCE);
+        assert_eq!(nonce.value, u64_to_bytes(7));
+
+        let round = topics.pop().unwrap();
+        assert_eq!(round.key, TOPIC_KEY_ROUND);
+        assert_eq!(round.value, u64_to_bytes(3));
+    }
+
+    #[test]
+    fn uni_ens_block_rsp_accessors() {
+        let rsp = UniEnsBlockRsp::default();
+        assert_eq!(rsp.block_round(), None);
+        assert!(!rsp.has_cert());
+
+        // A block header with only the round set.
+        let block = rmp_serde::from_slice(&[0x81, 0xa3, b'r', b'n', b'd', 0x05])
+            .expect("couldn't decode the block header");
+        let cert = Certificate { proposal: None };
+        let rsp = UniEnsBlockRsp {
+            block: Some(block),
+            cert: Some(cert),
+            ..Default::default()
+        };
+        assert_eq!(rsp.block_round(), Some(Round(5)));
+        assert!(rsp.has_cert());
+    }
+
+    #[test]
+    fn encode_ranged_uni_ens_block_req() {
+        let message = UniEnsBlockReq {
+            data_type: UniEnsBlockReqType::BlockAndCert,
+            round_key: Round(3),
+            nonce: 7,
+            max_blocks: None,
+        };
+
+        let mut dst = BytesMut::new();
+        TopicCodec::default()
+            .encode(Payload::UniEnsBlockReq(message.clone()), &mut dst)
+            .expect("couldn't encode the UniEnsBlockReq message");
+        let topics = TopicCodec::default()
+            .unmarshall_topics(&mut dst)
+            .expect("couldn't unmarshall the UniEnsBlockReq message");
+        assert!(topics.iter().all(|topic| topic.key != TOPIC_KEY_MAX_BLOCKS));
+
+        let message = UniEnsBlockReq {
+            max_blocks: Some(4),
+            ..message
+        };
+        let mut dst = BytesMut::new();
+        TopicCodec::default()
+            .encode(Payload::UniEnsBlockReq(message), &mut dst)
+            .expect("couldn't encode the UniEnsBlockReq message");
+        let mut topics = TopicCodec::default()
+            .unmarshall_topics(&mut dst)
+            .expect("couldn't unmarshall the UniEnsBlockReq message");
+        assert_eq!(topics.len(), 4);
+
+        let max_blocks = topics.pop().unwrap();
+        assert_eq!(max_blocks.key, TOPIC_KEY_MAX_BLOCKS);
+        assert_eq!(max_blocks.value, u64_to_bytes(4));
+    }
+
+    #[test]
+    fn decode_block_only_and_cert_only_rsp() {
+        let hash = || Topic {
+            key: TOPIC_KEY_HASH.into(),
+            value: Bytes::from_static(b"hash"),
+        };
+        let decode =
+            |topics| match TopicMsgResp::try_from(topics).expect("couldn't decode the response") {
+                TopicMsgResp::UniEnsBlockRsp(rsp) => rsp,
+                rsp => panic!("unexpected response: {rsp:?}"),
+            };
+
+        let block = Topic {
+            key: TOPIC_KEY_BLOCK_DATA.into(),
+            value: Bytes::from_static(&[0x81, 0xa3, b'r', b'n', b'd', 0x05]),
+        };
+        let rsp = decode(vec![block, hash()]);
+        assert_eq!(rsp.block_round(), Some(Round(5)));
+        assert!(!rsp.has_cert());
+
+        let cert = Topic {
+            key: TOPIC_KEY_CERT_DATA.into(),
+            value: Bytes::from_static(&[0x80]),
+        };
+        let rsp = decode(vec![cert, hash()]);
+        assert_eq!(rsp.block_round(), None);
+        assert!(rsp.has_cert());
+    }
+
+    #[test]
+    fn decode_error_rsp() {
+        let topics = vec![
+            Topic {
+                key: TOPIC_KEY_ERROR.into(),
+                value: Bytes::from_static(b"requested data type is unsupported"),
+            },
+            Topic {
+                key: TOPIC_KEY_HASH.into(),
+                value: Bytes::from_static(b"hash"),
+            },
+        ];
+        assert!(matches!(
+            TopicMsgResp::try_from(topics),
+            Ok(TopicMsgResp::ErrorRsp(rsp)) if rsp.error == "requested data type is unsupported"
+        ));
+
+        // Only the request hash is not a response.
+        let topics = vec![Topic {
+            key: TOPIC_KEY_HASH.into(),
+            value: Bytes::from_static(b"hash"),
+        }];
+        assert!(TopicMsgResp::try_from(topics).is_err());
+    }
+
+    #[test]
+    fn decode_the_same_block_from_rest_and_topic_responses() {
+        let mut block = vec![0x82, 0xa3, b'g', b'e', b'n', 0xa8];
+        block.extend(b"ziggurat");
+        block.extend([0xa3, b'r', b'n', b'd', 5]);
+        let mut cert = vec![
+            0x81, 0xa4, b'p', b'r', b'o', b'p', 0x81, 0xa3, b'd', b'i', b'g',
+        ];
+        cert.extend([0xc4, 32]);
+        cert.extend([1; 32]);
+
+        // The get-block REST endpoint returns a map with the block and its certificate.
+        let mut rest_rsp = vec![0x82, 0xa5, b'b', b'l', b'o', b'c', b'k'];
+        rest_rsp.extend(&block);
+        rest_rsp.extend([0xa4, b'c', b'e', b'r', b't']);
+        rest_rsp.extend(&cert);
+        let rest_rsp: EncodedBlockCert =
+            rmp_serde::from_slice(&rest_rsp).expect("couldn't decode the REST response");
+
+        let topics = vec![
+            Topic {
+                key: TOPIC_KEY_BLOCK_DATA.into(),
+                value: Bytes::from(block),
+            },
+            Topic {
+                key: TOPIC_KEY_CERT_DATA.into(),
+                value: Bytes::from(cert),
+            },
+            Topic {
+                key: TOPIC_KEY_HASH.into(),
+                value: Bytes::from_static(b"hash"),
+            },
+        ];
+        let topic_rsp = match TopicMsgResp::try_from(topics).expect("couldn't decode the response")
+        {
+            TopicMsgResp::UniEnsBlockRsp(rsp) => rsp,
+            rsp => panic!("unexpected response: {rsp:?}"),
+        };
+
+        let block = topic_rsp.block.expect("missing block");
+        assert_eq!(block.round, rest_rsp.block.round);
+        assert_eq!(block.genensis_id, rest_rsp.block.genensis_id);
+
+        let digest = |cert: &Certificate| cert.proposal.as_ref().map(|prop| prop.block_digest);
+        let cert = topic_rsp.cert.expect("missing certificate");
+        assert_eq!(digest(&cert), Some(HashDigest([1; 32])));
+        assert_eq!(digest(&cert), digest(&rest_rsp.cert));
     }
 }
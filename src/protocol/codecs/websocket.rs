@@ -37,3 +37,17 @@ impl Encoder<Vec<u8>> for WebsocketCodec {
             .map_err(|_| io::ErrorKind::InvalidData.into())
     }
 }
+
+impl Encoder<websocket_codec::Message> for WebsocketCodec {
+    type Error = io::Error;
+
+    fn encode(
+        &mut self,
+        item: websocket_codec::Message,
+        dst: &mut BytesMut,
+    ) -> Result<(), Self::Error> {
+        self.codec
+            .encode(item, dst)
+            .map_err(|_| io::ErrorKind::InvalidData.into())
+    }
+}
@@ -1,19 +1,29 @@
-use std::io;
+use std::{collections::HashMap, fmt, io, net::SocketAddr};
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
+use data_encoding::BASE64;
 use futures_util::{sink::SinkExt, stream::TryStreamExt, StreamExt};
 use pea2pea::{protocols::Handshake, Connection, ConnectionSide, Pea2Pea};
-use tokio_util::codec::{BytesCodec, Framed};
+use tokio::net::TcpStream;
+use tokio_util::codec::{BytesCodec, Encoder, Framed};
 use tracing::*;
 
-use crate::{protocol::constants::USER_AGENT, tools::inner_node::InnerNode};
+use crate::{
+    protocol::{
+        codecs::{algomsg::AlgoMsgCodec, payload::Payload},
+        constants::USER_AGENT,
+        net_prio::NetPrioSigner,
+    },
+    tools::inner_node::InnerNode,
+};
 
 pub const X_AG_ALGORAND_VERSION: &str = "2.1";
 pub const X_AG_ACCEPT_VERSION: &str = X_AG_ALGORAND_VERSION;
 const SEC_WEBSOCKET_VERSION: &str = "13";
-const X_AG_INSTANCE_NAME: &str = "synth_node"; // Can be shared between different synthetic nodes
-const X_AG_NODE_RANDOM: &str = "cGVhMnBlYQ=="; // Can be shared between different synthetic nodes
-const X_AG_ALGORAND_GENESIS: &str = "private-v1";
+// The defaults can be shared between different synthetic nodes, see HandshakeCfg::with_unique_identity.
+const X_AG_INSTANCE_NAME: &str = "synth_node";
+const X_AG_NODE_RANDOM: &str = "cGVhMnBlYQ==";
+pub const X_AG_ALGORAND_GENESIS: &str = "private-v1";
 
 // Info from RFC 6455, section 4.1, page 18:
 //
@@ -43,26 +53,72 @@ impl SecWebSocket {
         let accept = tungstenite::handshake::derive_accept_key(key.as_bytes());
         Self { key, accept }
     }
+
+    /// Verifies the Sec-WebSocket-Accept header of the handshake response matches the key.
+    pub fn verify(&self, response_headers: &[httparse::Header]) -> Result<(), HandshakeError> {
+        let accept = response_headers
+            .iter()
+            .find(|h| h.name.eq_ignore_ascii_case("sec-websocket-accept"))
+            .ok_or(HandshakeError::MissingAccept)?;
+
+        if self.accept.as_bytes() != accept.value {
+            return Err(HandshakeError::InvalidAccept);
+        }
+
+        Ok(())
+    }
+}
+
+/// Errors found in the handshake response.
+///
+/// The error is wrapped in an [io::Error] of the [io::ErrorKind::InvalidData] kind when the
+/// handshake fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandshakeError {
+    /// The response has no Sec-WebSocket-Accept header.
+    MissingAccept,
+    /// The Sec-WebSocket-Accept header doesn't match the Sec-WebSocket-Key of the request.
+    InvalidAccept,
+}
+
+impl fmt::Display for HandshakeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingAccept => write!(f, "missing Sec-WebSocket-Accept"),
+            Self::InvalidAccept => write!(f, "invalid Sec-WebSocket-Accept"),
+        }
+    }
+}
+
+impl std::error::Error for HandshakeError {}
+
+impl From<HandshakeError> for io::Error {
+    fn from(err: HandshakeError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
 }
 
+/// Configuration of the handshake headers.
+///
+/// The headers set to `None` are omitted from the handshake messages.
 #[derive(Clone, Debug)]
 pub struct HandshakeCfg {
-    /// Genesis HTTP path for genesis ID to identify the chain.
-    pub gossip_genesis: String,
     /// WebSocket protocol version.
-    pub ws_version: String,
+    pub ws_version: Option<String>,
     /// User agent is the HTTP header which identifies the user agent.
-    pub user_agent: String,
+    pub user_agent: Option<String>,
     /// Node random HTTP header the node uses to make sure it's not talking to itself.
-    pub ar_node_random: String,
-    /// Genesis HTTP header for genesis ID to identify the chain.
+    pub ar_node_random: Option<String>,
+    /// Genesis ID to identify the chain, sent in the HTTP header and by default in the gossip request path.
     pub ar_genesis: String,
+    /// Genesis ID sent in the gossip request path instead of [HandshakeCfg::ar_genesis].
+    pub gossip_genesis: Option<String>,
     /// An HTTP header for protocol version.
-    pub ar_version: String,
+    pub ar_version: Option<String>,
     /// An HTTP header for the accept protocol version. Client uses this to advertise supported protocol versions.
-    pub ar_accept_version: String,
+    pub ar_accept_version: Option<String>,
     /// Instance name HTTP header by which an inbound connection reports an ID to distinguish multiple local nodes.
-    pub ar_instance_name: String,
+    pub ar_instance_name: Option<String>,
     /// Telemetry ID HTTP header for telemetry-id for logging.
     pub ar_tel_id: Option<String>,
     /// Address location HTTP header by which an inbound connection reports its public address.
@@ -71,25 +127,200 @@ pub struct HandshakeCfg {
     pub challenge: Option<String>,
     /// A key-accept pair for a Sec-WebSocket-Key header.
     pub ws_key: Option<SecWebSocket>,
+    /// Signer used to answer the network priority challenge received from the node.
+    pub prio_signer: Option<NetPrioSigner>,
+    /// Protocol versions the initiator tries in order until the node accepts one.
+    ///
+    /// Each candidate is sent as both the version and the accept version header. The configured
+    /// headers are used as is if there are no candidates.
+    pub version_candidates: Vec<String>,
 }
 
 impl Default for HandshakeCfg {
     fn default() -> Self {
         Self {
-            gossip_genesis: X_AG_ALGORAND_GENESIS.into(),
-            ar_instance_name: X_AG_INSTANCE_NAME.into(),
-            ws_version: SEC_WEBSOCKET_VERSION.into(),
-            user_agent: USER_AGENT.into(),
-            ar_node_random: X_AG_NODE_RANDOM.into(),
+            ar_instance_name: Some(X_AG_INSTANCE_NAME.into()),
+            ws_version: Some(SEC_WEBSOCKET_VERSION.into()),
+            user_agent: Some(USER_AGENT.into()),
+            ar_node_random: Some(X_AG_NODE_RANDOM.into()),
             ar_genesis: X_AG_ALGORAND_GENESIS.into(),
-            ar_accept_version: X_AG_ACCEPT_VERSION.into(),
-            ar_version: X_AG_ALGORAND_VERSION.into(),
+            gossip_genesis: None,
+            ar_accept_version: Some(X_AG_ACCEPT_VERSION.into()),
+            ar_version: Some(X_AG_ALGORAND_VERSION.into()),
             // One could use 'd12c01a5-4ca4-4be3-a394-68c8913f3883' as a valid example.
             ar_tel_id: None,
             ar_location: None,
             challenge: None,
             ws_key: None,
+            prio_signer: None,
+            version_candidates: Vec::new(),
+        }
+    }
+}
+
+impl HandshakeCfg {
+    /// Builds the HTTP handshake request sent to the node at `host`.
+    fn build_request(&self, host: SocketAddr, sec_ws: &SecWebSocket) -> Bytes {
+        let mut req = Vec::new();
+        let mut req_header = |mut header: String| {
+            header.push_str("\r\n");
+            req.extend_from_slice(header.as_bytes());
+        };
+
+        // The genesis in the path and the header match unless the path is overridden.
+        let gossip_genesis = self.gossip_genesis.as_ref().unwrap_or(&self.ar_genesis);
+        req_header(format!("GET /v1/{gossip_genesis}/gossip HTTP/1.1"));
+        req_header(format!("Host: {host}"));
+        if let Some(ref user_agent) = self.user_agent {
+            req_header(format!("User-Agent: {user_agent}"));
+        }
+        req_header("Connection: Upgrade".into());
+        req_header(format!("Sec-WebSocket-Key: {}", sec_ws.key));
+        if let Some(ref ws_version) = self.ws_version {
+            req_header(format!("Sec-WebSocket-Version: {ws_version}"));
+        }
+        req_header("Upgrade: websocket".into());
+        if let Some(ref accept_version) = self.ar_accept_version {
+            req_header(format!("X-Algorand-Accept-Version: {accept_version}"));
+        }
+        if let Some(ref instance_name) = self.ar_instance_name {
+            req_header(format!("X-Algorand-Instancename: {instance_name}"));
+        }
+        if let Some(ref location) = self.ar_location {
+            req_header(format!("X-Algorand-Location: {location}"));
+        }
+        if let Some(ref node_random) = self.ar_node_random {
+            req_header(format!("X-Algorand-Noderandom: {node_random}"));
+        }
+        if let Some(ref telid) = self.ar_tel_id {
+            req_header(format!("X-Algorand-Telid: {telid}"));
+        }
+        if let Some(ref version) = self.ar_version {
+            req_header(format!("X-Algorand-Version: {version}"));
+        }
+        req_header(format!("X-Algorand-Genesis: {}", self.ar_genesis));
+        req_header("".into()); // A HTTP header ends with '\r\n'
+
+        Bytes::from(req)
+    }
+
+    /// Returns the configurations the handshake is attempted with, one for each version candidate.
+    fn attempts(&self) -> Vec<Self> {
+        if self.version_candidates.is_empty() {
+            return vec![self.clone()];
+        }
+
+        self.version_candidates
+            .iter()
+            .map(|version| Self {
+                ar_version: Some(version.clone()),
+                ar_accept_version: Some(version.clone()),
+                ..self.clone()
+            })
+            .collect()
+    }
+
+    /// Replaces the instance name and the node random with unique values, so the peers can tell
+    /// multiple synthetic nodes apart.
+    pub fn with_unique_identity(mut self) -> Self {
+        self.ar_instance_name = Some(format!(
+            "{X_AG_INSTANCE_NAME}_{:016x}",
+            rand::random::<u64>()
+        ));
+        self.ar_node_random = Some(BASE64.encode(&rand::random::<[u8; 8]>()));
+        self
+    }
+
+    /// Sets the genesis ID used in the genesis header and, unless overridden, the gossip request path.
+    pub fn with_genesis(mut self, genesis: &str) -> Self {
+        self.ar_genesis = genesis.into();
+        self
+    }
+}
+
+/// Algorand headers advertised by the node in its handshake response.
+#[derive(Clone, Debug, Default)]
+pub struct HandshakeResponse {
+    /// Protocol version the node agreed to use.
+    pub ar_version: Option<String>,
+    /// Genesis ID of the chain the node belongs to.
+    pub ar_genesis: Option<String>,
+    /// Instance name used to distinguish multiple local nodes.
+    pub ar_instance_name: Option<String>,
+    /// Random value the node uses to make sure it's not talking to itself.
+    pub ar_node_random: Option<String>,
+    /// Public address of the node.
+    pub ar_location: Option<String>,
+    /// Telemetry ID of the node.
+    pub ar_tel_id: Option<String>,
+    /// Network priority challenge sent by the node.
+    pub challenge: Option<String>,
+}
+
+impl HandshakeResponse {
+    /// Collects the Algorand headers from the parsed handshake response.
+    fn from_headers(headers: &[httparse::Header]) -> Self {
+        let mut headers = headers
+            .iter()
+            .map(|h| {
+                let value = String::from_utf8_lossy(h.value).into_owned();
+                (h.name.to_ascii_lowercase(), value)
+            })
+            .collect::<HashMap<_, _>>();
+
+        Self {
+            ar_version: headers.remove("x-algorand-version"),
+            ar_genesis: headers.remove("x-algorand-genesis"),
+            ar_instance_name: headers.remove("x-algorand-instancename"),
+            ar_node_random: headers.remove("x-algorand-noderandom"),
+            ar_location: headers.remove("x-algorand-location"),
+            ar_tel_id: headers.remove("x-algorand-telid"),
+            challenge: headers.remove("x-algorand-prioritychallenge"),
+        }
+    }
+}
+
+/// Returns the status code and the total length of the HTTP response, the body included.
+fn parse_response_len(rsp: &[u8]) -> io::Result<(u16, usize)> {
+    let mut rsp_headers = [httparse::EMPTY_HEADER; 32];
+    let mut parsed_rsp = httparse::Response::new(&mut rsp_headers);
+    let header_len = match parsed_rsp.parse(rsp) {
+        Ok(httparse::Status::Complete(len)) => len,
+        Ok(httparse::Status::Partial) => return Err(io::ErrorKind::UnexpectedEof.into()),
+        Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+    };
+
+    let body_len: usize = parsed_rsp
+        .headers
+        .iter()
+        .find(|h| h.name.to_ascii_lowercase() == "content-length")
+        .and_then(|h| std::str::from_utf8(h.value).ok()?.trim().parse().ok())
+        .unwrap_or(0);
+
+    Ok((parsed_rsp.code.unwrap_or_default(), header_len + body_len))
+}
+
+impl InnerNode {
+    /// Encodes a message which is sent before the writing protocol takes over the connection.
+    fn encode_msg(&self, message: Payload) -> io::Result<Bytes> {
+        let mut dst = BytesMut::new();
+        AlgoMsgCodec::new(self.node().span().clone()).encode(message, &mut dst)?;
+
+        Ok(dst.freeze())
+    }
+
+    /// Subscribes to the configured message tags right after the handshake, if any are set.
+    async fn send_msg_of_interest(
+        &self,
+        framed: &mut Framed<&mut TcpStream, BytesCodec>,
+    ) -> io::Result<()> {
+        if let Some(ref msg_of_interest) = self.msg_of_interest {
+            info!(parent: self.node().span(), "subscribing to {:?}", msg_of_interest.tags);
+            let message = Payload::MsgOfInterest(msg_of_interest.clone());
+            framed.send(self.encode_msg(message)?).await?;
         }
+
+        Ok(())
     }
 }
 
@@ -101,6 +332,8 @@ impl Handshake for InnerNode {
         let stream = self.borrow_stream(&mut conn);
         let cfg = &self.handshake_cfg;
 
+        let local_addr = stream.local_addr()?;
+
         match node_conn_side {
             ConnectionSide::Initiator => {
                 let mut framed = Framed::new(stream, BytesCodec::default());
@@ -111,72 +344,114 @@ impl Handshake for InnerNode {
                     SecWebSocket::generate()
                 };
 
-                let mut req = Vec::new();
-                let mut req_header = |mut header: String| {
-                    header.push_str("\r\n");
-                    req.extend_from_slice(header.as_bytes());
-                };
+                // The rejected attempts are retried over the same connection, which the node
+                // keeps alive after a non-101 response.
+                let mut attempts = cfg.attempts().into_iter().peekable();
+                let mut last_rsp = None;
+                while let Some(attempt_cfg) = attempts.next() {
+                    let req = attempt_cfg.build_request(conn_addr, &sec_ws);
+                    info!(parent: self.node().span(), "sending a handshake request: {:?}", req);
+                    framed.send(req).await?;
 
-                req_header(format!("GET /v1/{}/gossip HTTP/1.1", cfg.gossip_genesis));
-                req_header(format!("Host: {conn_addr}"));
-                req_header(format!("User-Agent: {}", cfg.user_agent));
-                req_header("Connection: Upgrade".into());
-                req_header(format!("Sec-WebSocket-Key: {}", sec_ws.key));
-                req_header(format!("Sec-WebSocket-Version: {}", cfg.ws_version));
-                req_header("Upgrade: websocket".into());
-                req_header(format!(
-                    "X-Algorand-Accept-Version: {}",
-                    cfg.ar_accept_version
-                ));
-                req_header(format!("X-Algorand-Instancename: {}", cfg.ar_instance_name));
-                if let Some(ref location) = cfg.ar_location {
-                    req_header(format!("X-Algorand-Location: {location}"));
-                }
-                req_header(format!("X-Algorand-Noderandom: {}", cfg.ar_node_random));
-                if let Some(ref telid) = cfg.ar_tel_id {
-                    req_header(format!("X-Algorand-Telid: {telid}"));
-                }
-                req_header(format!("X-Algorand-Version: {}", cfg.ar_version));
-                req_header(format!("X-Algorand-Genesis: {}", cfg.ar_genesis));
-                req_header("".into()); // A HTTP header ends with '\r\n'
+                    let rsp = framed
+                        .try_next()
+                        .await?
+                        .ok_or(io::ErrorKind::UnexpectedEof)?;
+                    info!(parent: self.node().span(), "received a handshake response: {:?}", rsp);
 
-                let req = Bytes::from(req);
-                info!(parent: self.node().span(), "sending a handshake request: {:?}", req);
-                framed.send(req).await.unwrap();
+                    let (status, rsp_len) = match parse_response_len(&rsp) {
+                        Ok(parsed) => parsed,
+                        Err(_) => {
+                            last_rsp = Some(rsp);
+                            break;
+                        }
+                    };
+                    if status == 101 || attempts.peek().is_none() {
+                        last_rsp = Some(rsp);
+                        break;
+                    }
+                    warn!(
+                        parent: self.node().span(),
+                        "the handshake with version {:?} was rejected with {status}, retrying",
+                        attempt_cfg.ar_version
+                    );
 
-                let rsp = framed.try_next().await.unwrap().unwrap();
-                info!(parent: self.node().span(), "received a handshake response: {:?}", rsp);
+                    // Discard the rest of the rejection body before the next attempt.
+                    let mut received = rsp.len();
+                    while received < rsp_len {
+                        received += framed
+                            .try_next()
+                            .await?
+                            .ok_or(io::ErrorKind::UnexpectedEof)?
+                            .len();
+                    }
+                }
+                let rsp = last_rsp.ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "no handshake attempts configured",
+                    )
+                })?;
 
                 let mut rsp_headers = [httparse::EMPTY_HEADER; 32];
                 let mut parsed_rsp = httparse::Response::new(&mut rsp_headers);
-                parsed_rsp.parse(&rsp).unwrap();
+                if let Err(e) = parsed_rsp.parse(&rsp) {
+                    error!(parent: self.node().span(), "invalid handshake response: {e}");
+                    return Err(io::ErrorKind::InvalidData.into());
+                }
 
-                // Verify Sec-Websocket-Accept
-                if let Some(swk) = parsed_rsp
-                    .headers
-                    .iter()
-                    .find(|h| h.name.to_ascii_lowercase() == "sec-websocket-accept")
+                if let Err(e) = sec_ws.verify(parsed_rsp.headers) {
+                    error!(parent: self.node().span(), "{e}");
+                    return Err(e.into());
+                }
+                trace!(parent: self.node().span(), "valid Sec-WebSocket-Accept");
+
+                let handshake_rsp = HandshakeResponse::from_headers(parsed_rsp.headers);
+
+                // Answer the network priority challenge right after the handshake.
+                if let (Some(prio_signer), Some(challenge)) =
+                    (&cfg.prio_signer, handshake_rsp.challenge.clone())
                 {
-                    if sec_ws.accept.as_bytes() != swk.value {
-                        error!(parent: self.node().span(), "invalid Sec-WebSocket-Accept");
-                        return Err(io::ErrorKind::InvalidData.into());
-                    }
-                    trace!(parent: self.node().span(), "valid Sec-WebSocket-Accept");
-                } else {
-                    error!(parent: self.node().span(), "missing Sec-WebSocket-Accept");
-                    return Err(io::ErrorKind::InvalidData.into());
-                };
+                    let message = Payload::NetPrioResponse(prio_signer.respond(challenge));
+
+                    info!(parent: self.node().span(), "sending a signed NetPrioResponse");
+                    framed.send(self.encode_msg(message)?).await?;
+                }
+                self.send_msg_of_interest(&mut framed).await?;
+
+                self.handshake_responses
+                    .lock()
+                    .expect("poisoned lock")
+                    .insert(conn_addr, handshake_rsp);
             }
             ConnectionSide::Responder => {
-                let peer_addr = stream.peer_addr().unwrap();
+                let peer_addr = stream.peer_addr()?;
                 let mut framed = Framed::new(stream, BytesCodec::default());
 
-                let req = framed.next().await.unwrap().unwrap();
+                let req = framed.next().await.ok_or(io::ErrorKind::UnexpectedEof)??;
                 info!(parent: self.node().span(), "{:?}: received a handshake request: {:?}", peer_addr, req);
 
                 let mut req_headers = [httparse::EMPTY_HEADER; 32];
                 let mut parsed_req = httparse::Request::new(&mut req_headers);
-                parsed_req.parse(&req).unwrap();
+                if let Err(e) = parsed_req.parse(&req) {
+                    error!(parent: self.node().span(), "invalid handshake request: {e}");
+                    return Err(io::ErrorKind::InvalidData.into());
+                }
+
+                // A matching node random means we're talking to ourselves.
+                if parsed_req.headers.iter().any(|h| {
+                    h.name.to_ascii_lowercase() == "x-algorand-noderandom"
+                        && cfg.ar_node_random.as_deref().map(str::as_bytes) == Some(h.value)
+                }) {
+                    error!(parent: self.node().span(), "{:?}: detected a connection to self", peer_addr);
+                    framed
+                        .send(Bytes::from_static(b"HTTP/1.1 508 Loop Detected\r\n\r\n"))
+                        .await?;
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "the peer uses the same node random",
+                    ));
+                }
 
                 let swa = if let Some(ws_key) = self.handshake_cfg.ws_key.clone() {
                     ws_key.accept
@@ -201,12 +476,21 @@ impl Handshake for InnerNode {
                 rsp_header("Upgrade: websocket".into());
                 rsp_header("Connection: Upgrade".into());
                 rsp_header(format!("Sec-Websocket-Accept: {swa}"));
-                rsp_header(format!("X-Algorand-Instancename: {}", cfg.ar_instance_name));
+                if let Some(ref instance_name) = cfg.ar_instance_name {
+                    rsp_header(format!("X-Algorand-Instancename: {instance_name}"));
+                }
                 if let Some(ref location) = cfg.ar_location {
                     rsp_header(format!("X-Algorand-Location: {location}"));
                 }
-                rsp_header(format!("X-Algorand-Noderandom: {}", cfg.ar_node_random));
-                rsp_header(format!("X-Algorand-Version: {}", cfg.ar_accept_version));
+                if let Some(ref node_random) = cfg.ar_node_random {
+                    rsp_header(format!("X-Algorand-Noderandom: {node_random}"));
+                }
+                if let Some(ref telid) = cfg.ar_tel_id {
+                    rsp_header(format!("X-Algorand-Telid: {telid}"));
+                }
+                if let Some(ref accept_version) = cfg.ar_accept_version {
+                    rsp_header(format!("X-Algorand-Version: {accept_version}"));
+                }
                 rsp_header(format!("X-Algorand-Genesis: {}", cfg.ar_genesis));
                 if let Some(ref challenge) = cfg.challenge {
                     rsp_header(format!("X-Algorand-Prioritychallenge: {challenge}"));
@@ -215,10 +499,159 @@ impl Handshake for InnerNode {
 
                 let rsp = Bytes::from(rsp);
                 info!(parent: self.node().span(), "sending a handshake response: {:?}", rsp);
-                framed.send(rsp).await.unwrap();
+                framed.send(rsp).await?;
+
+                self.send_msg_of_interest(&mut framed).await?;
             }
         }
 
+        // Only the successful handshakes are recorded, and the peers which have disconnected in
+        // the meantime are dropped, so the map doesn't grow with every connection attempt.
+        {
+            let mut local_addrs = self.local_addrs.lock().expect("poisoned lock");
+            local_addrs.retain(|addr, _| self.node().is_connected(*addr));
+            local_addrs.insert(conn_addr, local_addr);
+        }
+
         Ok(conn)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_line_uses_configured_genesis() {
+        let cfg = HandshakeCfg::default().with_genesis("testnet-v1.0");
+        let host = "127.0.0.1:4161".parse().unwrap();
+
+        let req = cfg.build_request(host, &SecWebSocket::generate());
+        let req = std::str::from_utf8(&req).unwrap();
+
+        let mut lines = req.split("\r\n");
+        assert_eq!(lines.next(), Some("GET /v1/testnet-v1.0/gossip HTTP/1.1"));
+        assert!(req.contains("X-Algorand-Genesis: testnet-v1.0\r\n"));
+        assert!(!req.contains(X_AG_ALGORAND_GENESIS));
+    }
+
+    #[test]
+    fn request_line_uses_gossip_genesis_override() {
+        let cfg = HandshakeCfg {
+            gossip_genesis: Some(X_AG_ALGORAND_GENESIS.into()),
+            ..Default::default()
+        }
+        .with_genesis("testnet-v1.0");
+        let host = "127.0.0.1:4161".parse().unwrap();
+
+        let req = cfg.build_request(host, &SecWebSocket::generate());
+        let req = std::str::from_utf8(&req).unwrap();
+
+        let mut lines = req.split("\r\n");
+        assert_eq!(
+            lines.next(),
+            Some(format!("GET /v1/{X_AG_ALGORAND_GENESIS}/gossip HTTP/1.1").as_str())
+        );
+        assert!(req.contains("X-Algorand-Genesis: testnet-v1.0\r\n"));
+    }
+
+    #[test]
+    fn omitted_headers_are_not_sent() {
+        let cfg = HandshakeCfg {
+            ar_instance_name: None,
+            ar_version: Some(String::new()),
+            ..Default::default()
+        };
+        let host = "127.0.0.1:4161".parse().unwrap();
+
+        let req = cfg.build_request(host, &SecWebSocket::generate());
+        let req = std::str::from_utf8(&req).unwrap();
+
+        assert!(!req.contains("X-Algorand-Instancename"));
+        // An empty value is still sent.
+        assert!(req.contains("X-Algorand-Version: \r\n"));
+        assert!(req.contains(&format!("User-Agent: {USER_AGENT}\r\n")));
+    }
+
+    #[test]
+    fn version_candidates_are_attempted_in_order() {
+        let cfg = HandshakeCfg {
+            version_candidates: vec!["9.9".into(), X_AG_ALGORAND_VERSION.into()],
+            ..Default::default()
+        };
+
+        let versions = cfg
+            .attempts()
+            .into_iter()
+            .map(|attempt| (attempt.ar_version, attempt.ar_accept_version))
+            .collect::<Vec<_>>();
+        let expected = |version: &str| (Some(version.to_string()), Some(version.to_string()));
+        assert_eq!(versions, [expected("9.9"), expected(X_AG_ALGORAND_VERSION)]);
+
+        // Without candidates the configured headers are used as is.
+        let cfg = HandshakeCfg {
+            ar_version: None,
+            ..Default::default()
+        };
+        let attempts = cfg.attempts();
+        assert_eq!(attempts.len(), 1);
+        assert!(attempts[0].ar_version.is_none());
+    }
+
+    #[test]
+    fn rejection_length_includes_the_body() {
+        let rsp = b"HTTP/1.1 412 Precondition Failed\r\n\
+            Content-Length: 10\r\n\r\n\
+            Requested";
+
+        let (status, len) = parse_response_len(rsp).expect("invalid handshake response");
+        assert_eq!(status, 412);
+        assert_eq!(len, rsp.len() + 1);
+
+        assert!(parse_response_len(b"HTTP/1.1 101 Switching").is_err());
+    }
+
+    #[test]
+    fn matching_accept_is_verified() {
+        let sec_ws = SecWebSocket::generate();
+        let headers = [httparse::Header {
+            name: "Sec-Websocket-Accept",
+            value: sec_ws.accept.as_bytes(),
+        }];
+
+        assert_eq!(sec_ws.verify(&headers), Ok(()));
+    }
+
+    #[test]
+    fn tampered_accept_is_rejected() {
+        let sec_ws = SecWebSocket::generate();
+        let mut tampered = sec_ws.accept.clone().into_bytes();
+        tampered[0] ^= 1;
+        let headers = [httparse::Header {
+            name: "sec-websocket-accept",
+            value: &tampered,
+        }];
+
+        assert_eq!(sec_ws.verify(&headers), Err(HandshakeError::InvalidAccept));
+        assert_eq!(sec_ws.verify(&[]), Err(HandshakeError::MissingAccept));
+    }
+
+    #[test]
+    fn response_headers_are_collected() {
+        let rsp = b"HTTP/1.1 101 Switching Protocols\r\n\
+            Upgrade: websocket\r\n\
+            X-Algorand-Version: 2.1\r\n\
+            x-algorand-genesis: testnet-v1.0\r\n\
+            X-Algorand-Prioritychallenge: challenge\r\n\r\n";
+
+        let mut headers = [httparse::EMPTY_HEADER; 32];
+        let mut parsed_rsp = httparse::Response::new(&mut headers);
+        parsed_rsp.parse(rsp).expect("invalid handshake response");
+
+        let rsp = HandshakeResponse::from_headers(parsed_rsp.headers);
+        assert_eq!(rsp.ar_version.as_deref(), Some(X_AG_ALGORAND_VERSION));
+        assert_eq!(rsp.ar_genesis.as_deref(), Some("testnet-v1.0"));
+        assert_eq!(rsp.challenge.as_deref(), Some("challenge"));
+        assert!(rsp.ar_location.is_none());
+    }
+}
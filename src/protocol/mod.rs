@@ -3,6 +3,7 @@
 pub mod codecs;
 pub mod constants;
 pub mod handshake;
+pub mod net_prio;
 #[allow(dead_code)]
 pub mod payload_factory;
 mod reading;
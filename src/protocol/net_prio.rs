@@ -0,0 +1,246 @@
+//! Signing of the network priority responses.
+//!
+//! When a node accepts a connection, it may challenge the peer with a nonce sent in the
+//! `X-Algorand-Prioritychallenge` handshake header. The peer proves it controls a participating
+//! account by signing the challenge with the account's participation (voting) key. The signature
+//! is a two-level ephemeral [OneTimeSignature], as implemented in go-algorand/crypto/onetimesig.go.
+
+use std::{fmt, num::NonZeroU64};
+
+use ed25519_dalek::{Signer, SigningKey};
+use rand::rngs::OsRng;
+use serde::Serialize;
+
+use crate::protocol::codecs::msgpack::{
+    Address, Ed25519PublicKey, Ed25519Signature, NetPrioResponse, OneTimeSignature, Response, Round,
+};
+
+/// Domain separation prefix for a batch subkey identifier.
+const HASH_ID_SUBKEY_BATCH: &[u8] = b"OT1";
+/// Domain separation prefix for an offset subkey identifier.
+const HASH_ID_SUBKEY_OFFSET: &[u8] = b"OT2";
+/// Domain separation prefix for a network priority response.
+const HASH_ID_NET_PRIO_RESPONSE: &[u8] = b"NPR";
+
+/// The default key dilution for participation keys in go-algorand.
+pub const DEFAULT_KEY_DILUTION: NonZeroU64 = match NonZeroU64::new(10_000) {
+    Some(key_dilution) => key_dilution,
+    None => unreachable!(),
+};
+
+/// Identifies a batch subkey signed by the master key.
+#[derive(Serialize)]
+struct SubkeyBatchId {
+    #[serde(skip_serializing_if = "is_zero")]
+    batch: u64,
+    pk: Ed25519PublicKey,
+}
+
+/// Identifies an offset subkey signed by the batch subkey.
+#[derive(Serialize)]
+struct SubkeyOffsetId {
+    #[serde(skip_serializing_if = "is_zero")]
+    batch: u64,
+    #[serde(skip_serializing_if = "is_zero")]
+    off: u64,
+    pk: Ed25519PublicKey,
+}
+
+// Zero values are omitted by the canonical msgpack encoding.
+fn is_zero(num: &u64) -> bool {
+    *num == 0
+}
+
+/// Prepends a domain separation prefix to the msgpack encoded message.
+fn hash_rep<T: Serialize>(hash_id: &[u8], msg: &T) -> Vec<u8> {
+    let mut data = hash_id.to_vec();
+    data.extend(rmp_serde::to_vec_named(msg).expect("couldn't encode the message"));
+    data
+}
+
+/// Participation key secrets used to produce [OneTimeSignature]s.
+#[derive(Clone)]
+pub struct OneTimeSigner {
+    /// Master key, registered on the ledger as the account's voting key.
+    master: SigningKey,
+    /// Number of offset subkeys in a single batch.
+    key_dilution: NonZeroU64,
+}
+
+impl fmt::Debug for OneTimeSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Don't leak the secret key.
+        f.debug_struct("OneTimeSigner")
+            .field("voting_key", &self.voting_key())
+            .field("key_dilution", &self.key_dilution)
+            .finish()
+    }
+}
+
+impl OneTimeSigner {
+    /// Generates new random participation key secrets.
+    pub fn generate(key_dilution: NonZeroU64) -> Self {
+        Self {
+            master: SigningKey::generate(&mut OsRng),
+            key_dilution,
+        }
+    }
+
+    /// Creates participation key secrets from the master secret key.
+    pub fn from_secret(secret: [u8; 32], key_dilution: NonZeroU64) -> Self {
+        Self {
+            master: SigningKey::from_bytes(&secret),
+            key_dilution,
+        }
+    }
+
+    /// Returns the voting key which verifies signatures made by this signer.
+    pub fn voting_key(&self) -> Ed25519PublicKey {
+        Ed25519PublicKey(self.master.verifying_key().to_bytes())
+    }
+
+    /// Signs the domain separated message for a given round.
+    pub fn sign(&self, round: Round, msg: &[u8]) -> OneTimeSignature {
+        let batch = round.0 / self.key_dilution.get();
+        let offset = round.0 % self.key_dilution.get();
+
+        // The node generates the subkeys in advance and deletes them once used. Fresh subkeys
+        // verify the same way since each one is certified by the key above it.
+        let batch_key = SigningKey::generate(&mut OsRng);
+        let offset_key = SigningKey::generate(&mut OsRng);
+
+        let batch_id = SubkeyBatchId {
+            batch,
+            pk: Ed25519PublicKey(batch_key.verifying_key().to_bytes()),
+        };
+        let offset_id = SubkeyOffsetId {
+            batch,
+            off: offset,
+            pk: Ed25519PublicKey(offset_key.verifying_key().to_bytes()),
+        };
+
+        let pk2sig = self.master.sign(&hash_rep(HASH_ID_SUBKEY_BATCH, &batch_id));
+        let pk1sig = batch_key.sign(&hash_rep(HASH_ID_SUBKEY_OFFSET, &offset_id));
+        let sig = offset_key.sign(msg);
+
+        OneTimeSignature {
+            sig: Ed25519Signature(sig.to_bytes()),
+            pk: offset_id.pk,
+            pksigold: Ed25519Signature([0; 64]),
+            pk2: batch_id.pk,
+            pk1sig: Ed25519Signature(pk1sig.to_bytes()),
+            pk2sig: Ed25519Signature(pk2sig.to_bytes()),
+        }
+    }
+}
+
+/// Answers network priority challenges on behalf of a participating account.
+#[derive(Clone, Debug)]
+pub struct NetPrioSigner {
+    /// Participation key secrets of the account.
+    pub signer: OneTimeSigner,
+    /// The participating account.
+    pub sender: Address,
+    /// The round for which the response is signed.
+    pub round: Round,
+}
+
+impl NetPrioSigner {
+    /// Creates a signed response to the challenge.
+    pub fn respond(&self, challenge: String) -> NetPrioResponse {
+        let response = Response { nonce: challenge };
+        let sig = self
+            .signer
+            .sign(self.round, &hash_rep(HASH_ID_NET_PRIO_RESPONSE, &response));
+
+        NetPrioResponse {
+            response,
+            round: self.round,
+            sender_addr: self.sender,
+            sig,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    use super::*;
+
+    fn verify_ed25519(pk: &Ed25519PublicKey, msg: &[u8], sig: &Ed25519Signature) -> bool {
+        let pk = VerifyingKey::from_bytes(&pk.0).expect("invalid public key");
+        pk.verify(msg, &Signature::from_bytes(&sig.0)).is_ok()
+    }
+
+    // Mirrors OneTimeSignatureVerifier.Verify from go-algorand.
+    fn verify(
+        voting_key: &Ed25519PublicKey,
+        round: Round,
+        key_dilution: NonZeroU64,
+        msg: &[u8],
+        sig: &OneTimeSignature,
+    ) -> bool {
+        let key_dilution = key_dilution.get();
+        let batch = round.0 / key_dilution;
+        let batch_id = SubkeyBatchId { batch, pk: sig.pk2 };
+        let offset_id = SubkeyOffsetId {
+            batch,
+            off: round.0 % key_dilution,
+            pk: sig.pk,
+        };
+
+        verify_ed25519(
+            voting_key,
+            &hash_rep(HASH_ID_SUBKEY_BATCH, &batch_id),
+            &sig.pk2sig,
+        ) && verify_ed25519(
+            &sig.pk2,
+            &hash_rep(HASH_ID_SUBKEY_OFFSET, &offset_id),
+            &sig.pk1sig,
+        ) && verify_ed25519(&sig.pk, msg, &sig.sig)
+    }
+
+    #[test]
+    fn sign_and_verify_net_prio_response() {
+        let signer = NetPrioSigner {
+            signer: OneTimeSigner::generate(DEFAULT_KEY_DILUTION),
+            sender: Address::new([1; 32]),
+            round: Round(12_345),
+        };
+
+        let rsp = signer.respond("challenge".into());
+        let msg = hash_rep(HASH_ID_NET_PRIO_RESPONSE, &rsp.response);
+        let voting_key = signer.signer.voting_key();
+
+        assert!(verify(
+            &voting_key,
+            rsp.round,
+            DEFAULT_KEY_DILUTION,
+            &msg,
+            &rsp.sig
+        ));
+        // A signature for a different round must not verify.
+        assert!(!verify(
+            &voting_key,
+            rsp.round
+                .checked_add(DEFAULT_KEY_DILUTION.get())
+                .expect("round overflow"),
+            DEFAULT_KEY_DILUTION,
+            &msg,
+            &rsp.sig
+        ));
+    }
+
+    #[test]
+    fn batch_id_encoding_omits_zero_values() {
+        let batch_id = SubkeyBatchId {
+            batch: 0,
+            pk: Ed25519PublicKey([7; 32]),
+        };
+
+        let mut expected = vec![0x81, 0xa2, b'p', b'k', 0xc4, 32];
+        expected.extend([7; 32]);
+        assert_eq!(rmp_serde::to_vec_named(&batch_id).unwrap(), expected);
+    }
+}
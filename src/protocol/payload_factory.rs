@@ -1,35 +1,70 @@
 use data_encoding::BASE64;
 use rand::Rng;
 
-use crate::protocol::codecs::payload::Payload;
+use crate::protocol::codecs::{msgpack::HashDigest, payload::Payload};
+
+/// A function customizing the payload before it's generated.
+///
+/// Any cloneable closure can be used, which allows the customizer to hold its own state.
+pub trait PayloadCustomizer: FnMut(&mut Payload) + Send {
+    /// Clones the customizer along with its state.
+    fn clone_box(&self) -> Box<dyn PayloadCustomizer>;
+}
+
+impl<F> PayloadCustomizer for F
+where
+    F: FnMut(&mut Payload) + Clone + Send + 'static,
+{
+    fn clone_box(&self) -> Box<dyn PayloadCustomizer> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn PayloadCustomizer> {
+    fn clone(&self) -> Self {
+        // Dereference to clone the inner customizer rather than the box itself.
+        (**self).clone_box()
+    }
+}
 
 /// A factory for creating payloads.
 #[derive(Clone)]
 pub struct PayloadFactory {
     payload: Payload,
-    customize_payload: fn(&mut Payload) -> (),
+    customize_payload: Box<dyn PayloadCustomizer>,
 }
 
 impl PayloadFactory {
     /// Create a new payload factory using specified payload as a template and a function to customize
     /// the payload. If no customization is needed, just pass None and use default customizer.
-    pub fn new(payload: Payload, customize_payload: Option<fn(&mut Payload) -> ()>) -> Self {
+    pub fn new(payload: Payload, customize_payload: Option<Box<dyn PayloadCustomizer>>) -> Self {
         let default_customize_payload = |msg: &mut Payload| match msg {
             Payload::UniEnsBlockReq(message) => {
                 message.nonce += 1;
             }
+            Payload::UniCatchupReq(message) => {
+                message.nonce += 1;
+            }
             Payload::MsgDigestSkip(message) => {
                 message.0 = rand::thread_rng().gen::<[u8; 32]>();
             }
             Payload::NetPrioResponse(message) => {
                 message.response.nonce = BASE64.encode(&rand::thread_rng().gen::<[u8; 32]>());
             }
+            // A new lease would invalidate the signature, so signed transactions need a customizer
+            // which signs them again.
+            Payload::Transaction(message)
+                if message.sig.is_none() && message.multisig.is_none() =>
+            {
+                // A unique lease makes the transaction ID unique as well.
+                message.transaction.lease = Some(HashDigest(rand::thread_rng().gen::<[u8; 32]>()));
+            }
             _ => {}
         };
 
         Self {
             payload,
-            customize_payload: customize_payload.unwrap_or(default_customize_payload),
+            customize_payload: customize_payload.unwrap_or(Box::new(default_customize_payload)),
         }
     }
 
@@ -48,8 +83,36 @@ impl PayloadFactory {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use super::*;
-    use crate::protocol::codecs::topic::{UniEnsBlockReq, UniEnsBlockReqType};
+    use crate::protocol::codecs::{
+        msgpack::{Address, Ed25519Signature, Round, SignedTransaction, Transaction},
+        topic::{UniEnsBlockReq, UniEnsBlockReqType},
+    };
+
+    fn unsigned_txn() -> SignedTransaction {
+        SignedTransaction {
+            sig: None,
+            multisig: None,
+            auth_addr: None,
+            transaction: Transaction::test_payment(
+                Address::new([2; 32]),
+                Address::new([3; 32]),
+                1000,
+            ),
+        }
+    }
+
+    fn txn_ids(payloads: &[Payload]) -> HashSet<String> {
+        payloads
+            .iter()
+            .map(|payload| match payload {
+                Payload::Transaction(stxn) => format!("{:?}", stxn.transaction.id()),
+                _ => panic!("unexpected payload: {payload:?}"),
+            })
+            .collect()
+    }
 
     #[test]
     #[ignore = "internal test"]
@@ -57,8 +120,9 @@ mod tests {
         let mut factory = PayloadFactory::new(
             Payload::UniEnsBlockReq(UniEnsBlockReq {
                 data_type: UniEnsBlockReqType::BlockAndCert,
-                round_key: 1,
+                round_key: Round(1),
                 nonce: 123,
+                max_blocks: None,
             }),
             None,
         );
@@ -71,4 +135,58 @@ mod tests {
             assert_eq!(message.nonce, 224);
         }
     }
+
+    #[test]
+    fn generate_distinct_transactions() {
+        let mut factory = PayloadFactory::new(Payload::Transaction(unsigned_txn()), None);
+
+        let payloads = factory.generate_payloads(100);
+        assert_eq!(txn_ids(&payloads).len(), 100);
+    }
+
+    #[test]
+    fn generate_transactions_with_stateful_customizer() {
+        let mut counter = 0u64;
+        let customizer = move |msg: &mut Payload| {
+            if let Payload::Transaction(stxn) = msg {
+                counter += 1;
+                stxn.transaction.note = counter.to_be_bytes().to_vec();
+            }
+        };
+        let mut factory = PayloadFactory::new(
+            Payload::Transaction(unsigned_txn()),
+            Some(Box::new(customizer)),
+        );
+
+        let payloads = factory.generate_payloads(100);
+        assert_eq!(txn_ids(&payloads).len(), 100);
+
+        // A cloned factory continues from the same state.
+        let mut cloned = factory.clone();
+        for factory in [&mut factory, &mut cloned] {
+            match factory.generate_next() {
+                Payload::Transaction(stxn) => {
+                    assert_eq!(stxn.transaction.note, 101u64.to_be_bytes())
+                }
+                payload => panic!("unexpected payload: {payload:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn keep_signed_transactions_intact() {
+        let stxn = SignedTransaction {
+            sig: Some(Ed25519Signature([4; 64])),
+            ..unsigned_txn()
+        };
+        let mut factory = PayloadFactory::new(Payload::Transaction(stxn.clone()), None);
+
+        match factory.generate_next() {
+            Payload::Transaction(generated) => {
+                assert_eq!(generated.sig, stxn.sig);
+                assert_eq!(generated.transaction, stxn.transaction);
+            }
+            payload => panic!("unexpected payload: {payload:?}"),
+        }
+    }
 }
@@ -1,10 +1,16 @@
 use std::{io, net::SocketAddr};
 
-use pea2pea::{protocols::Reading, ConnectionSide, Pea2Pea};
+use pea2pea::{
+    protocols::{Reading, Writing},
+    ConnectionSide, Pea2Pea,
+};
 use tracing::*;
 
 use crate::{
-    protocol::codecs::algomsg::{AlgoMsg, AlgoMsgCodec},
+    protocol::codecs::{
+        algomsg::{AlgoMsg, AlgoMsgCodec},
+        payload::Payload,
+    },
     tools::inner_node::InnerNode,
 };
 
@@ -21,11 +27,33 @@ impl Reading for InnerNode {
     async fn process_message(&self, source: SocketAddr, msg: Self::Message) -> io::Result<()> {
         let span = self.node().span();
 
-        debug!(
-            parent: span,
-            "sending a message received from {source} to the synthetic node's inbound queue: {:?}",
-            msg.payload
-        );
+        // Keep the connection alive without involving the synthetic node.
+        if let Payload::WsPing(data) = msg.payload {
+            trace!(parent: span, "replying to a WebSocket ping from {source}");
+            self.unicast(source, Payload::WsPong(data))?;
+            return Ok(());
+        }
+
+        // Answer pings like a real node would, the ping is still forwarded to the synthetic node.
+        if let Payload::Ping(ping) = &msg.payload {
+            if self.reply_to_pings {
+                trace!(parent: span, "replying to a Ping from {source}");
+                self.unicast(source, Payload::PingReply(ping.clone()))?;
+            }
+        }
+
+        self.stats.lock().expect("poisoned lock").record(&msg);
+
+        if let Some(recorded) = self
+            .recorded_messages
+            .lock()
+            .expect("poisoned lock")
+            .as_mut()
+        {
+            recorded.push(msg.raw.clone());
+        }
+
+        self.log_received(source, &msg);
         self.inbound_tx
             .send((source, msg))
             .await
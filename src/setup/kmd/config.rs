@@ -1,6 +1,7 @@
 //! Utilities for kmd configuration.
 
 use std::{
+    fs,
     net::SocketAddr,
     path::{Path, PathBuf},
     str::FromStr,
@@ -12,9 +13,15 @@ use tokio::time::timeout;
 use crate::setup::{
     self,
     constants::LOAD_FILE_TIMEOUT_SECS,
-    kmd::constants::{KMD_DIR, REST_ADDR_FILE, TOKEN_FILE},
+    kmd::constants::{KMD_DIR_PREFIX, REST_ADDR_FILE, TOKEN_FILE},
 };
 
+/// Parses a version such as `v0.5` into its numeric components.
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    version.split('.').map(|num| num.parse().ok()).collect()
+}
+
 /// Startup configuration for the kmd daemon.
 #[derive(Debug, Clone, Default)]
 pub struct KmdConfig {
@@ -29,20 +36,8 @@ pub struct KmdConfig {
 impl KmdConfig {
     /// Creates a new [KmdConfig].
     pub async fn new(node_path: &Path) -> anyhow::Result<Self> {
-        let mut token = String::new();
-
-        let path = node_path.join(KMD_DIR);
-        if !path.exists() {
-            return Err(anyhow!("couldn't find the {:?} directory", path));
-        }
-
-        timeout(LOAD_FILE_TIMEOUT_SECS, async {
-            let token_path = path.join(TOKEN_FILE);
-
-            token = setup::try_read_to_string(&token_path).await;
-        })
-        .await
-        .expect("couldn't fetch the kmd's token");
+        let path = Self::kmd_dir(node_path)?;
+        let token = Self::read_token(&path).await?;
 
         Ok(KmdConfig {
             path,
@@ -51,6 +46,54 @@ impl KmdConfig {
         })
     }
 
+    /// Returns the kmd's directory within the node's directory.
+    ///
+    /// The directory name contains the kmd's version, which changes between releases, so the
+    /// directory with the latest version is chosen.
+    pub fn kmd_dir(node_path: &Path) -> Result<PathBuf> {
+        let mut latest: Option<(Vec<u64>, PathBuf)> = None;
+
+        for entry in fs::read_dir(node_path)? {
+            let path = entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+
+            let version = match path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(KMD_DIR_PREFIX))
+                .and_then(parse_version)
+            {
+                Some(version) => version,
+                None => continue,
+            };
+
+            if latest
+                .as_ref()
+                .map_or(true, |(latest, _)| version > *latest)
+            {
+                latest = Some((version, path));
+            }
+        }
+
+        latest.map(|(_, path)| path).ok_or_else(|| {
+            anyhow!("couldn't find the {KMD_DIR_PREFIX}* directory in {node_path:?}")
+        })
+    }
+
+    /// Reads the REST API token from the kmd's directory.
+    pub async fn read_token(path: &Path) -> Result<String> {
+        let token_path = path.join(TOKEN_FILE);
+
+        timeout(
+            LOAD_FILE_TIMEOUT_SECS,
+            setup::try_read_to_string(&token_path),
+        )
+        .await
+        .map_err(|_| anyhow!("couldn't fetch the kmd's token"))
+    }
+
     /// Fetches the kmd's address.
     pub async fn load_addr(&mut self) -> Result<()> {
         let mut rest_addr = String::new();
@@ -70,3 +113,29 @@ impl KmdConfig {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use tempfile::TempDir;
+    use ziggurat_core_utils::err_constants::ERR_TEMPDIR_NEW;
+
+    use super::*;
+
+    #[test]
+    fn choose_the_latest_kmd_dir() {
+        let node_dir = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        for dir in ["kmd-0.5", "kmd-0.6", "kmd-invalid"] {
+            fs::create_dir(node_dir.path().join(dir)).unwrap();
+        }
+
+        let path = KmdConfig::kmd_dir(node_dir.path()).expect("couldn't find the kmd directory");
+        assert_eq!(path, node_dir.path().join("kmd-0.6"));
+    }
+
+    #[test]
+    fn compare_kmd_versions() {
+        assert_eq!(parse_version("v0.5"), Some(vec![0, 5]));
+        assert!(parse_version("0.10") > parse_version("0.9"));
+        assert_eq!(parse_version("0.x"), None);
+    }
+}
@@ -2,9 +2,9 @@
 
 use tokio::time::Duration;
 
-/// Directory of the kmd instance.
+/// Prefix of the kmd instance's directory, which is suffixed with the kmd's version, e.g. `kmd-v0.5`.
 /// This directory is generated automatically within the node's directory when the node is created.
-pub const KMD_DIR: &str = "kmd-v0.5";
+pub const KMD_DIR_PREFIX: &str = "kmd-";
 
 /// Security token file needed for the REST API authentication.
 pub const TOKEN_FILE: &str = "kmd.token";
@@ -14,3 +14,6 @@ pub const REST_ADDR_FILE: &str = "kmd.net";
 
 /// Timeout when waiting for kmd instance to start responding.
 pub const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The maximum number of sign requests sent to the kmd instance at once.
+pub const MAX_CONCURRENT_SIGN_REQUESTS: usize = 16;
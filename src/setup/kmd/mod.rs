@@ -8,11 +8,12 @@ pub mod rest_api;
 use std::{
     fs, io,
     net::SocketAddr,
-    path::Path,
+    path::{Path, PathBuf},
     process::{Child, Command, Stdio},
 };
 
 use anyhow::anyhow;
+use futures_util::{stream, StreamExt, TryStreamExt};
 use tokio::{
     io::AsyncWriteExt,
     net::TcpStream,
@@ -21,19 +22,24 @@ use tokio::{
 
 use self::rest_api::message::{ListKeysResponse, SignTransactionResponse};
 use crate::{
-    protocol::codecs::msgpack::Transaction,
+    protocol::codecs::{
+        msgpack::{Address, Ed25519PublicKey, MultisigSignature, Transaction},
+        tagmsg::Tag,
+    },
     setup::{
         constants::ALGORAND_SETUP_DIR,
         get_algorand_work_path,
         kmd::{
             config::KmdConfig,
-            constants::{CONNECTION_TIMEOUT, REST_ADDR_FILE},
+            constants::{CONNECTION_TIMEOUT, MAX_CONCURRENT_SIGN_REQUESTS, REST_ADDR_FILE},
             rest_api::{
                 client::ClientV1,
-                message::{InitWalletHandleResponse, ListWalletsResponse},
+                message::{
+                    InitWalletHandleResponse, ListWalletsResponse, RenewWalletHandleResponse,
+                },
             },
         },
-        node::ChildExitCode,
+        node::{rest_api::message::TransactionParams, ChildExitCode},
         node_meta_data::NodeMetaData,
     },
 };
@@ -41,6 +47,10 @@ use crate::{
 pub struct KmdBuilder {
     /// Node's process metadata read from Ziggurat configuration files.
     meta: NodeMetaData,
+    /// The REST API token, read from the kmd's directory if not set.
+    token: Option<String>,
+    /// The kmd's data directory, found within the node's directory if not set.
+    data_dir: Option<PathBuf>,
 }
 
 impl KmdBuilder {
@@ -49,18 +59,48 @@ impl KmdBuilder {
         let setup_path = get_algorand_work_path()?.join(ALGORAND_SETUP_DIR);
         let meta = NodeMetaData::new(&setup_path)?;
 
-        Ok(Self { meta })
+        Ok(Self {
+            meta,
+            token: None,
+            data_dir: None,
+        })
+    }
+
+    /// Sets the REST API token, so it isn't read from the kmd's directory.
+    pub fn token(mut self, token: String) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Sets the kmd's data directory, for kmd instances which live outside the node's directory.
+    pub fn data_dir(mut self, path: PathBuf) -> Self {
+        self.data_dir = Some(path);
+        self
     }
 
     /// Creates a [Kmd] according to configuration.
     pub async fn build(&self, node_path: &Path) -> anyhow::Result<Kmd> {
-        if !node_path.exists() {
-            return Err(anyhow!("couldn't find the {:?} directory", node_path));
-        }
+        let path = match &self.data_dir {
+            Some(path) => path.clone(),
+            None => {
+                if !node_path.exists() {
+                    return Err(anyhow!("couldn't find the {:?} directory", node_path));
+                }
+                KmdConfig::kmd_dir(node_path)?
+            }
+        };
+        let token = match &self.token {
+            Some(token) => token.clone(),
+            None => KmdConfig::read_token(&path).await?,
+        };
 
         Ok(Kmd {
             child: None,
-            conf: KmdConfig::new(node_path).await?,
+            conf: KmdConfig {
+                path,
+                rest_api_addr: None,
+                token,
+            },
             meta: self.meta.clone(),
             rest_client: None,
         })
@@ -174,6 +214,22 @@ impl Kmd {
         Err(anyhow!("the kmd instance is not started"))
     }
 
+    /// Create a new wallet and return its ID.
+    pub async fn create_wallet(
+        &mut self,
+        wallet_name: String,
+        wallet_password: String,
+        wallet_driver_name: String,
+    ) -> anyhow::Result<String> {
+        if let Some(rest_client) = &self.rest_client {
+            return rest_client
+                .create_wallet(wallet_name, wallet_password, wallet_driver_name)
+                .await;
+        }
+
+        Err(anyhow!("the kmd instance is not started"))
+    }
+
     /// Unlock the wallet and return a wallet handle token that can be used for subsequent operations.
     pub async fn get_wallet_handle_token(
         &mut self,
@@ -189,6 +245,30 @@ impl Kmd {
         Err(anyhow!("the kmd instance is not started"))
     }
 
+    /// Renew the wallet handle token and reset its expiration timer.
+    pub async fn renew_wallet_handle(
+        &mut self,
+        wallet_handle_token: String,
+    ) -> anyhow::Result<RenewWalletHandleResponse> {
+        if let Some(rest_client) = &self.rest_client {
+            return rest_client.renew_wallet_handle(wallet_handle_token).await;
+        }
+
+        Err(anyhow!("the kmd instance is not started"))
+    }
+
+    /// Release the wallet handle token so it can't be used anymore.
+    pub async fn release_wallet_handle(
+        &mut self,
+        wallet_handle_token: String,
+    ) -> anyhow::Result<()> {
+        if let Some(rest_client) = &self.rest_client {
+            return rest_client.release_wallet_handle(wallet_handle_token).await;
+        }
+
+        Err(anyhow!("the kmd instance is not started"))
+    }
+
     /// Get the list of public keys in the wallet.
     pub async fn get_keys(
         &mut self,
@@ -201,6 +281,46 @@ impl Kmd {
         Err(anyhow!("the kmd instance is not started"))
     }
 
+    /// Generate a new key in the wallet and return its address.
+    pub async fn generate_key(&mut self, wallet_handle_token: String) -> anyhow::Result<String> {
+        if let Some(rest_client) = &self.rest_client {
+            return rest_client.generate_key(wallet_handle_token).await;
+        }
+
+        Err(anyhow!("the kmd instance is not started"))
+    }
+
+    /// Export the private key of the address and return its 32-byte secret seed.
+    pub async fn export_key(
+        &mut self,
+        wallet_handle_token: String,
+        wallet_password: String,
+        address: String,
+    ) -> anyhow::Result<[u8; 32]> {
+        if let Some(rest_client) = &self.rest_client {
+            return rest_client
+                .export_key(wallet_handle_token, wallet_password, address)
+                .await;
+        }
+
+        Err(anyhow!("the kmd instance is not started"))
+    }
+
+    /// Import the private key from its 32-byte secret seed and return the address.
+    pub async fn import_key(
+        &mut self,
+        wallet_handle_token: String,
+        private_key: [u8; 32],
+    ) -> anyhow::Result<String> {
+        if let Some(rest_client) = &self.rest_client {
+            return rest_client
+                .import_key(wallet_handle_token, private_key)
+                .await;
+        }
+
+        Err(anyhow!("the kmd instance is not started"))
+    }
+
     /// Sign a transaction.
     pub async fn sign_transaction(
         &self,
@@ -210,12 +330,124 @@ impl Kmd {
     ) -> anyhow::Result<SignTransactionResponse> {
         if let Some(rest_client) = &self.rest_client {
             return rest_client
-                .sign_transaction(wallet_handle_token, wallet_password, transaction)
+                .sign_transaction(wallet_handle_token, wallet_password, None, transaction)
+                .await;
+        }
+
+        Err(anyhow!("the kmd instance is not started"))
+    }
+
+    /// Sign a transaction with the key, e.g. the key of a rekeyed account's auth address.
+    ///
+    /// kmd sets the auth address of the signed transaction when the key isn't the sender's key.
+    pub async fn sign_transaction_with_key(
+        &self,
+        wallet_handle_token: String,
+        wallet_password: String,
+        public_key: Ed25519PublicKey,
+        transaction: &Transaction,
+    ) -> anyhow::Result<SignTransactionResponse> {
+        if let Some(rest_client) = &self.rest_client {
+            return rest_client
+                .sign_transaction(
+                    wallet_handle_token,
+                    wallet_password,
+                    Some(public_key),
+                    transaction,
+                )
                 .await;
         }
 
         Err(anyhow!("the kmd instance is not started"))
     }
+
+    /// Sign a multisig transaction with the key and return the updated multisig signature.
+    pub async fn sign_multisig_transaction(
+        &self,
+        wallet_handle_token: String,
+        wallet_password: String,
+        public_key: Ed25519PublicKey,
+        partial_multisig: &MultisigSignature,
+        transaction: &Transaction,
+    ) -> anyhow::Result<MultisigSignature> {
+        if let Some(rest_client) = &self.rest_client {
+            return rest_client
+                .sign_multisig_transaction(
+                    wallet_handle_token,
+                    wallet_password,
+                    public_key,
+                    partial_multisig,
+                    transaction,
+                )
+                .await;
+        }
+
+        Err(anyhow!("the kmd instance is not started"))
+    }
+
+    /// Signs the transaction and tags it as a [Tag::Txn] message.
+    ///
+    /// The returned bytes can be sent to the node as is.
+    pub async fn sign_tagged_transaction(
+        &self,
+        wallet_handle_token: String,
+        wallet_password: String,
+        transaction: &Transaction,
+    ) -> anyhow::Result<Vec<u8>> {
+        let signed_txn = self
+            .sign_transaction(wallet_handle_token, wallet_password, transaction)
+            .await?
+            .signed_transaction;
+
+        Ok(tag_signed_transaction(signed_txn))
+    }
+
+    /// Creates a payment of `amount` micro-Algos, signs it and tags it as a [Tag::Txn] message.
+    ///
+    /// The returned bytes can be sent to the node as is.
+    pub async fn make_signed_payment(
+        &self,
+        wallet_handle_token: String,
+        wallet_password: String,
+        sender: Address,
+        receiver: Address,
+        amount: u64,
+        params: &TransactionParams,
+    ) -> anyhow::Result<Vec<u8>> {
+        let txn = params
+            .payment(sender, receiver, amount)
+            .ok_or_else(|| anyhow!("the last valid round overflows"))?;
+
+        self.sign_tagged_transaction(wallet_handle_token, wallet_password, &txn)
+            .await
+    }
+
+    /// Signs the transactions and tags each of them as a [Tag::Txn] message.
+    ///
+    /// The sign requests are sent concurrently, but the returned messages keep the order of the
+    /// transactions.
+    pub async fn sign_transactions(
+        &self,
+        wallet_handle_token: String,
+        wallet_password: String,
+        transactions: &[Transaction],
+    ) -> anyhow::Result<Vec<Vec<u8>>> {
+        stream::iter(transactions)
+            .map(|txn| {
+                self.sign_transaction(wallet_handle_token.clone(), wallet_password.clone(), txn)
+            })
+            .buffered(MAX_CONCURRENT_SIGN_REQUESTS)
+            .map_ok(|rsp| tag_signed_transaction(rsp.signed_transaction))
+            .try_collect()
+            .await
+    }
+}
+
+/// Prepends the [Tag::Txn] tag to the signed transaction, so it can be sent to the node as is.
+fn tag_signed_transaction(mut signed_txn: Vec<u8>) -> Vec<u8> {
+    let mut tagged_msg = Tag::Txn.get_tag_str().as_bytes().to_vec();
+    tagged_msg.append(&mut signed_txn);
+    tagged_msg
 }
 
 impl Drop for Kmd {
@@ -226,3 +458,363 @@ impl Drop for Kmd {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use bytes::BytesMut;
+    use tempfile::TempDir;
+    use tokio_util::codec::Decoder;
+    use tracing::Span;
+    use ziggurat_core_utils::err_constants::{
+        ERR_KMD_BUILD, ERR_KMD_STOP, ERR_NODE_BUILD, ERR_NODE_STOP, ERR_TEMPDIR_NEW,
+    };
+
+    use super::*;
+    use crate::{
+        protocol::codecs::{
+            msgpack::{
+                canonical_encode, Ed25519PublicKey, HashDigest, KeyRegistration, Payment, Round,
+                SignedTransaction, StateProofCommitment, TransactionType, VrfPublicKey,
+            },
+            payload::Payload,
+            tagmsg::{TagMsgCodec, TAG_LEN},
+        },
+        setup::node::Node,
+        tools::{
+            constants::ERR_NODE_START,
+            helpers::{get_named_wallet_token, get_pub_key_addr, get_wallet_token, start_kmd},
+        },
+    };
+
+    async fn start_kmd_without_node(target: &TempDir) -> Kmd {
+        // The kmd instance uses the data directory of the node, but the node isn't started.
+        let _node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+
+        start_kmd(target.path()).await
+    }
+
+    #[tokio::test]
+    async fn build_with_an_explicit_token_and_data_dir() {
+        // The data directory is empty, so reading the token from it would time out.
+        let data_dir = TempDir::new().expect(ERR_TEMPDIR_NEW);
+
+        let kmd = Kmd::builder()
+            .token("token".into())
+            .data_dir(data_dir.path().to_path_buf())
+            .build(Path::new("/nonexistent"))
+            .await
+            .expect(ERR_KMD_BUILD);
+
+        assert_eq!(kmd.conf.token, "token");
+        assert_eq!(kmd.conf.path, data_dir.path());
+    }
+
+    fn payment_txn(sender: Address, receiver: Address) -> Transaction {
+        // Signing doesn't require the node, so the parameters don't have to be valid on the network.
+        let params = TransactionParams {
+            fee: 0,
+            min_fee: 1000,
+            genesis_id: "ziggurat".into(),
+            genesis_hash: HashDigest([0; 32]),
+            last_round: Round(1),
+            consensus_version: String::new(),
+        };
+
+        params
+            .payment(sender, receiver, 1000)
+            .expect("the last valid round overflows")
+    }
+
+    #[tokio::test]
+    async fn create_wallet() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut kmd = start_kmd_without_node(&target).await;
+
+        let wallet_id = kmd
+            .create_wallet("ziggurat".into(), "password".into(), "sqlite".into())
+            .await
+            .expect("couldn't create the wallet");
+
+        let wallets = kmd.get_wallets().await.expect("couldn't get the wallets");
+        assert!(wallets
+            .wallets
+            .iter()
+            .any(|wallet| wallet.id == wallet_id && wallet.name == "ziggurat"));
+
+        kmd.stop().expect(ERR_KMD_STOP);
+    }
+
+    #[tokio::test]
+    async fn renew_and_release_wallet_handle() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut kmd = start_kmd_without_node(&target).await;
+
+        let wallet_token = get_wallet_token(&mut kmd).await;
+
+        let rsp = kmd
+            .renew_wallet_handle(wallet_token.clone())
+            .await
+            .expect("couldn't renew the wallet handle");
+        assert_eq!(rsp.wallet_handle.wallet.name, "unencrypted-default-wallet");
+        assert!(rsp.wallet_handle.expires_seconds > 0);
+
+        kmd.release_wallet_handle(wallet_token.clone())
+            .await
+            .expect("couldn't release the wallet handle");
+
+        // The released token is no longer valid.
+        assert!(kmd.get_keys(wallet_token).await.is_err());
+
+        kmd.stop().expect(ERR_KMD_STOP);
+    }
+
+    #[tokio::test]
+    async fn generate_keys_and_sign_a_payment() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut kmd = start_kmd_without_node(&target).await;
+
+        kmd.create_wallet("ziggurat".into(), "password".into(), "sqlite".into())
+            .await
+            .expect("couldn't create the wallet");
+        let wallet_token = get_named_wallet_token(&mut kmd, "ziggurat", "password").await;
+
+        let mut addrs = Vec::new();
+        for _ in 0..2 {
+            let addr = kmd
+                .generate_key(wallet_token.clone())
+                .await
+                .expect("couldn't generate a key");
+            addrs.push(Address::from_string(&addr).expect("invalid address"));
+        }
+        assert_ne!(addrs[0], addrs[1]);
+
+        let txn = payment_txn(addrs[0], addrs[1]);
+        let signed_txn = kmd
+            .sign_transaction(wallet_token, "password".into(), &txn)
+            .await
+            .expect("couldn't sign the transaction")
+            .signed_transaction;
+
+        let signed_txn: SignedTransaction =
+            rmp_serde::from_slice(&signed_txn).expect("couldn't decode the signed transaction");
+        assert!(signed_txn.sig.is_some());
+        assert_eq!(signed_txn.transaction, txn);
+
+        kmd.stop().expect(ERR_KMD_STOP);
+    }
+
+    #[tokio::test]
+    async fn canonical_encoding_matches_kmd() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut kmd = start_kmd_without_node(&target).await;
+
+        let wallet_token = get_wallet_token(&mut kmd).await;
+        let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+        // Zero values are omitted from the canonical encoding, so avoid them here.
+        let mut txn = payment_txn(addr, addr)
+            .with_note(b"ziggurat".to_vec())
+            .expect("invalid note");
+        txn.genesis_hash = HashDigest([1; 32]);
+
+        let signed_txn = kmd
+            .sign_transaction(wallet_token, "".into(), &txn)
+            .await
+            .expect("couldn't sign the transaction")
+            .signed_transaction;
+
+        // kmd returns the signed transaction in the canonical encoding.
+        let decoded: SignedTransaction =
+            rmp_serde::from_slice(&signed_txn).expect("couldn't decode the signed transaction");
+        assert_eq!(
+            canonical_encode(&decoded).expect("couldn't encode the signed transaction"),
+            signed_txn
+        );
+
+        kmd.stop().expect(ERR_KMD_STOP);
+    }
+
+    #[tokio::test]
+    async fn keyreg_canonical_encoding_matches_kmd() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut kmd = start_kmd_without_node(&target).await;
+
+        let wallet_token = get_wallet_token(&mut kmd).await;
+        let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+        // The key registration fields of the KEYREG_TXN sample in the msgpack tests.
+        let mut txn = payment_txn(addr, addr);
+        txn.txn_type = TransactionType::KeyRegistration(KeyRegistration {
+            vote_pk: Some(Ed25519PublicKey([5; 32])),
+            selection_pk: Some(VrfPublicKey([2; 32])),
+            state_proof_pk: Some(StateProofCommitment([4; 64])),
+            vote_first: Round(10),
+            vote_last: Round(1_000_000),
+            vote_key_dilution: 10_000,
+            nonparticipation: false,
+        });
+
+        let signed_txn = kmd
+            .sign_transaction(wallet_token, "".into(), &txn)
+            .await
+            .expect("couldn't sign the transaction")
+            .signed_transaction;
+
+        let decoded: SignedTransaction =
+            rmp_serde::from_slice(&signed_txn).expect("couldn't decode the signed transaction");
+        assert_eq!(decoded.transaction, txn);
+        assert_eq!(
+            canonical_encode(&decoded).expect("couldn't encode the signed transaction"),
+            signed_txn
+        );
+
+        kmd.stop().expect(ERR_KMD_STOP);
+    }
+
+    #[tokio::test]
+    async fn decode_a_transaction_signed_by_kmd() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut kmd = start_kmd_without_node(&target).await;
+
+        let wallet_token = get_wallet_token(&mut kmd).await;
+        let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+        let txn = payment_txn(addr, addr);
+        let tagged_txn = kmd
+            .sign_tagged_transaction(wallet_token, "".into(), &txn)
+            .await
+            .expect("couldn't sign the transaction");
+
+        // The message is decoded the same way as the transactions gossiped by the node.
+        let payload = TagMsgCodec::new(Span::none())
+            .decode(&mut BytesMut::from(&tagged_txn[..]))
+            .expect("couldn't decode the signed transaction")
+            .expect("missing payload");
+        match payload {
+            Payload::Transaction(stxn) => {
+                assert!(stxn.sig.is_some());
+                assert_eq!(stxn.transaction, txn);
+            }
+            payload => panic!("unexpected payload: {payload:?}"),
+        }
+
+        kmd.stop().expect(ERR_KMD_STOP);
+    }
+
+    #[tokio::test]
+    async fn make_a_signed_payment_accepted_by_the_node() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+        node.start().await.expect(ERR_NODE_START);
+
+        let mut kmd = start_kmd(target.path()).await;
+
+        let wallet_token = get_wallet_token(&mut kmd).await;
+        let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+        let rest_client = node.rest_client().expect("couldn't get the REST client");
+        let params = rest_client
+            .get_transaction_params()
+            .await
+            .expect("couldn't get the transaction parameters");
+
+        let tagged_txn = kmd
+            .make_signed_payment(wallet_token, "".into(), addr, addr, 1000, &params)
+            .await
+            .expect("couldn't make a signed payment");
+        let (tag, signed_txn) = tagged_txn.split_at(TAG_LEN);
+        assert_eq!(tag, Tag::Txn.get_tag_str().as_bytes());
+
+        let tx_id = rest_client
+            .send_raw_transaction(signed_txn)
+            .await
+            .expect("the node didn't accept the payment");
+        assert!(!tx_id.is_empty());
+
+        kmd.stop().expect(ERR_KMD_STOP);
+        node.stop().await.expect(ERR_NODE_STOP);
+    }
+
+    #[tokio::test]
+    async fn sign_a_batch_of_payments() {
+        const TXN_COUNT: u64 = 50;
+
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut kmd = start_kmd_without_node(&target).await;
+
+        let wallet_token = get_wallet_token(&mut kmd).await;
+        let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+        // Distinct amounts make each transaction unique.
+        let txns = (0..TXN_COUNT)
+            .map(|i| {
+                let mut txn = payment_txn(addr, addr);
+                txn.txn_type = TransactionType::Payment(Payment {
+                    receiver: addr,
+                    amount: 1000 + i,
+                    close_remainder_to: None,
+                });
+                txn
+            })
+            .collect::<Vec<_>>();
+
+        let tagged_txns = kmd
+            .sign_transactions(wallet_token, "".into(), &txns)
+            .await
+            .expect("couldn't sign the transactions");
+        assert_eq!(tagged_txns.len(), txns.len());
+
+        for (tagged_txn, txn) in tagged_txns.iter().zip(&txns) {
+            let (tag, signed_txn) = tagged_txn.split_at(TAG_LEN);
+            assert_eq!(tag, Tag::Txn.get_tag_str().as_bytes());
+
+            let signed_txn: SignedTransaction =
+                rmp_serde::from_slice(signed_txn).expect("couldn't decode the signed transaction");
+            assert!(signed_txn.sig.is_some());
+            assert_eq!(&signed_txn.transaction, txn);
+        }
+
+        kmd.stop().expect(ERR_KMD_STOP);
+    }
+
+    #[tokio::test]
+    async fn export_and_import_key() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut kmd = start_kmd_without_node(&target).await;
+
+        let default_token = get_wallet_token(&mut kmd).await;
+        let addr = get_pub_key_addr(&mut kmd, default_token.clone()).await;
+
+        let private_key = kmd
+            .export_key(default_token.clone(), "".into(), addr.to_string())
+            .await
+            .expect("couldn't export the key");
+
+        kmd.create_wallet("ziggurat".into(), "password".into(), "sqlite".into())
+            .await
+            .expect("couldn't create the wallet");
+        let wallet_token = get_named_wallet_token(&mut kmd, "ziggurat", "password").await;
+
+        let imported_addr = kmd
+            .import_key(wallet_token.clone(), private_key)
+            .await
+            .expect("couldn't import the key");
+        assert_eq!(addr.to_string(), imported_addr);
+
+        // Ed25519 signatures are deterministic, so both wallets must produce the same signature.
+        let txn = payment_txn(addr, addr);
+        let default_sig = kmd
+            .sign_transaction(default_token, "".into(), &txn)
+            .await
+            .expect("couldn't sign the transaction")
+            .signed_transaction;
+        let imported_sig = kmd
+            .sign_transaction(wallet_token, "password".into(), &txn)
+            .await
+            .expect("couldn't sign the transaction")
+            .signed_transaction;
+        assert_eq!(default_sig, imported_sig);
+
+        kmd.stop().expect(ERR_KMD_STOP);
+    }
+}
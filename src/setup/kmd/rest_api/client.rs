@@ -3,9 +3,15 @@
 //! The kmd daemons provide their API specifications here:
 //! https://developer.algorand.org/docs/rest-apis/kmd/
 
+use ed25519_dalek::SigningKey;
+
 use crate::{
-    protocol::codecs::msgpack::Transaction,
+    protocol::codecs::msgpack::{
+        canonical_encode, Ed25519PublicKey, MultisigSignature, Transaction,
+    },
     setup::kmd::rest_api::message::{
+        CreateWalletRequest, CreateWalletResponse, ExportKeyRequest, ExportKeyResponse,
+        GenerateKeyRequest, GenerateKeyResponse, ImportKeyRequest, ImportKeyResponse,
         InitWalletHandleRequest, InitWalletHandleResponse, ListKeysRequest, ListKeysResponse,
         ListWalletsResponse, SignTransactionRequest, SignTransactionResponse,
     },
@@ -47,6 +53,40 @@ impl ClientV1 {
             .map_err(|e| anyhow::anyhow!("couldn't get the wallets: {e}"))
     }
 
+    /// Create a new wallet and return its ID.
+    pub async fn create_wallet(
+        &self,
+        wallet_name: String,
+        wallet_password: String,
+        wallet_driver_name: String,
+    ) -> anyhow::Result<String> {
+        let req = CreateWalletRequest {
+            wallet_name,
+            wallet_driver_name,
+            wallet_password,
+        };
+
+        let rsp: CreateWalletResponse = self
+            .http_client
+            .post(&format!("http://{}/v1/wallet", self.address))
+            .header(API_HEADER_TOKEN, &self.token)
+            .header(reqwest::header::ACCEPT, API_HEADER_ACCEPT_JSON)
+            .json(&req)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "couldn't create the wallet (name: {}: {e})",
+                    req.wallet_name
+                )
+            })?;
+
+        Ok(rsp.wallet.id)
+    }
+
     /// Unlock the wallet and return a wallet handle token that can be used for subsequent operations.
     ///
     /// These tokens expire periodically and must be renewed. You can POST the token to
@@ -80,6 +120,47 @@ impl ClientV1 {
             })
     }
 
+    /// Renew the wallet handle token and reset its expiration timer.
+    pub async fn renew_wallet_handle(
+        &self,
+        wallet_handle_token: String,
+    ) -> anyhow::Result<RenewWalletHandleResponse> {
+        let req = WalletHandleRequest {
+            wallet_handle_token,
+        };
+
+        self.http_client
+            .post(&format!("http://{}/v1/wallet/renew", self.address))
+            .header(API_HEADER_TOKEN, &self.token)
+            .header(reqwest::header::ACCEPT, API_HEADER_ACCEPT_JSON)
+            .json(&req)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("couldn't renew the wallet handle: {e}"))
+    }
+
+    /// Release the wallet handle token so it can't be used anymore.
+    pub async fn release_wallet_handle(&self, wallet_handle_token: String) -> anyhow::Result<()> {
+        let req = WalletHandleRequest {
+            wallet_handle_token,
+        };
+
+        self.http_client
+            .post(&format!("http://{}/v1/wallet/release", self.address))
+            .header(API_HEADER_TOKEN, &self.token)
+            .header(reqwest::header::ACCEPT, API_HEADER_ACCEPT_JSON)
+            .json(&req)
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(|e| anyhow::anyhow!("couldn't release the wallet handle: {e}"))?;
+
+        Ok(())
+    }
+
     /// Get the list of public keys in the wallet.
     pub async fn get_keys(&self, wallet_handle_token: String) -> anyhow::Result<ListKeysResponse> {
         let req = ListKeysRequest {
@@ -99,17 +180,110 @@ impl ClientV1 {
             .map_err(|e| anyhow::anyhow!("couldn't get the keys: {e}"))
     }
 
+    /// Generate a new key in the wallet and return its address.
+    pub async fn generate_key(&self, wallet_handle_token: String) -> anyhow::Result<String> {
+        let req = GenerateKeyRequest {
+            wallet_handle_token,
+            display_mnemonic: false,
+        };
+
+        let rsp: GenerateKeyResponse = self
+            .http_client
+            .post(&format!("http://{}/v1/key", self.address))
+            .header(API_HEADER_TOKEN, &self.token)
+            .header(reqwest::header::ACCEPT, API_HEADER_ACCEPT_JSON)
+            .json(&req)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("couldn't generate a key: {e}"))?;
+
+        Ok(rsp.address)
+    }
+
+    /// Export the private key of the address and return its 32-byte secret seed.
+    pub async fn export_key(
+        &self,
+        wallet_handle_token: String,
+        wallet_password: String,
+        address: String,
+    ) -> anyhow::Result<[u8; 32]> {
+        let req = ExportKeyRequest {
+            wallet_handle_token,
+            address,
+            wallet_password,
+        };
+
+        let rsp: ExportKeyResponse = self
+            .http_client
+            .post(&format!("http://{}/v1/key/export", self.address))
+            .header(API_HEADER_TOKEN, &self.token)
+            .header(reqwest::header::ACCEPT, API_HEADER_ACCEPT_JSON)
+            .json(&req)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!("couldn't export the key (address: {}: {e})", req.address)
+            })?;
+
+        // The seed is followed by the public key.
+        rsp.private_key
+            .get(..32)
+            .and_then(|seed| seed.try_into().ok())
+            .ok_or_else(|| anyhow::anyhow!("invalid private key length: {}", rsp.private_key.len()))
+    }
+
+    /// Import the private key from its 32-byte secret seed and return the address.
+    pub async fn import_key(
+        &self,
+        wallet_handle_token: String,
+        private_key: [u8; 32],
+    ) -> anyhow::Result<String> {
+        // The kmd expects the seed to be followed by the public key.
+        let req = ImportKeyRequest {
+            wallet_handle_token,
+            private_key: SigningKey::from_bytes(&private_key)
+                .to_keypair_bytes()
+                .to_vec(),
+        };
+
+        let rsp: ImportKeyResponse = self
+            .http_client
+            .post(&format!("http://{}/v1/key/import", self.address))
+            .header(API_HEADER_TOKEN, &self.token)
+            .header(reqwest::header::ACCEPT, API_HEADER_ACCEPT_JSON)
+            .json(&req)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("couldn't import the key: {e}"))?;
+
+        Ok(rsp.address)
+    }
+
     /// Sign a transaction.
+    ///
+    /// The transaction is signed with the sender's key unless another public key is provided.
     pub async fn sign_transaction(
         &self,
         wallet_handle_token: String,
         wallet_password: String,
+        public_key: Option<Ed25519PublicKey>,
         transaction: &Transaction,
     ) -> anyhow::Result<SignTransactionResponse> {
-        let transaction_bytes = rmp_serde::to_vec_named(transaction)?;
+        // Send the transaction in the same canonical form go-algorand uses.
+        let transaction_bytes = canonical_encode(transaction)?;
         let req = SignTransactionRequest {
             wallet_handle_token,
             transaction: transaction_bytes,
+            public_key,
             wallet_password,
         };
 
@@ -125,4 +299,41 @@ impl ClientV1 {
             .await
             .map_err(|e| anyhow::anyhow!("couldn't sign the transaction: {e}"))
     }
+
+    /// Sign a multisig transaction with the key and return the updated multisig signature.
+    ///
+    /// The partial multisig signature must contain all the public keys of the multisig account.
+    pub async fn sign_multisig_transaction(
+        &self,
+        wallet_handle_token: String,
+        wallet_password: String,
+        public_key: Ed25519PublicKey,
+        partial_multisig: &MultisigSignature,
+        transaction: &Transaction,
+    ) -> anyhow::Result<MultisigSignature> {
+        let transaction_bytes = canonical_encode(transaction)?;
+        let req = SignMultisigRequest {
+            wallet_handle_token,
+            transaction: transaction_bytes,
+            public_key,
+            partial_multisig: partial_multisig.into(),
+            wallet_password,
+        };
+
+        let rsp: SignMultisigResponse = self
+            .http_client
+            .post(&format!("http://{}/v1/multisig/sign", self.address))
+            .header(API_HEADER_TOKEN, &self.token)
+            .header(reqwest::header::ACCEPT, API_HEADER_ACCEPT_JSON)
+            .json(&req)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("couldn't sign the multisig transaction: {e}"))?;
+
+        rmp_serde::from_slice(&rsp.multisig)
+            .map_err(|e| anyhow::anyhow!("couldn't decode the multisig signature: {e}"))
+    }
 }
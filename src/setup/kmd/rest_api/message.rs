@@ -6,6 +6,8 @@
 use data_encoding::BASE64;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::protocol::codecs::msgpack::{Ed25519PublicKey, MultisigSignature};
+
 /// APIV1Wallet is the API's representation of a wallet.
 #[derive(Debug, Deserialize)]
 pub struct ApiV1Wallet {
@@ -24,6 +26,20 @@ pub struct ListWalletsResponse {
     pub wallets: Vec<ApiV1Wallet>,
 }
 
+/// CreateWalletRequest is the request for `POST /v1/wallet`.
+#[derive(Serialize)]
+pub(super) struct CreateWalletRequest {
+    pub wallet_name: String,
+    pub wallet_driver_name: String,
+    pub wallet_password: String,
+}
+
+/// CreateWalletResponse is the response to `POST /v1/wallet`.
+#[derive(Debug, Deserialize)]
+pub struct CreateWalletResponse {
+    pub wallet: ApiV1Wallet,
+}
+
 /// InitWalletHandleRequest is the request for `POST /v1/wallet/init`.
 #[derive(Serialize)]
 pub(super) struct InitWalletHandleRequest {
@@ -37,6 +53,26 @@ pub struct InitWalletHandleResponse {
     pub wallet_handle_token: String,
 }
 
+/// WalletHandleRequest is the request for `POST /v1/wallet/renew` and `POST /v1/wallet/release`.
+#[derive(Serialize)]
+pub struct WalletHandleRequest {
+    pub wallet_handle_token: String,
+}
+
+/// ApiV1WalletHandle includes the wallet the handle corresponds to and the number of seconds
+/// until the handle expires.
+#[derive(Debug, Deserialize)]
+pub struct ApiV1WalletHandle {
+    pub wallet: ApiV1Wallet,
+    pub expires_seconds: i64,
+}
+
+/// RenewWalletHandleResponse is the response to `POST /v1/wallet/renew`.
+#[derive(Debug, Deserialize)]
+pub struct RenewWalletHandleResponse {
+    pub wallet_handle: ApiV1WalletHandle,
+}
+
 /// ListKeysRequest is the request for `POST /v1/key/list`.
 #[derive(Serialize)]
 pub struct ListKeysRequest {
@@ -50,12 +86,62 @@ pub struct ListKeysResponse {
     pub addresses: Vec<String>,
 }
 
+/// GenerateKeyRequest is the request for `POST /v1/key`.
+#[derive(Serialize)]
+pub struct GenerateKeyRequest {
+    pub wallet_handle_token: String,
+    pub display_mnemonic: bool,
+}
+
+/// GenerateKeyResponse is the response to `POST /v1/key`.
+#[derive(Debug, Deserialize)]
+pub struct GenerateKeyResponse {
+    pub address: String,
+}
+
+/// ExportKeyRequest is the request for `POST /v1/key/export`.
+#[derive(Serialize)]
+pub struct ExportKeyRequest {
+    pub wallet_handle_token: String,
+    pub address: String,
+    pub wallet_password: String,
+}
+
+/// ExportKeyResponse is the response to `POST /v1/key/export`.
+#[derive(Debug, Deserialize)]
+pub struct ExportKeyResponse {
+    /// The 64-byte ed25519 private key - the secret seed followed by the public key.
+    #[serde(deserialize_with = "deserialize_bytes")]
+    pub private_key: Vec<u8>,
+}
+
+/// ImportKeyRequest is the request for `POST /v1/key/import`.
+#[derive(Serialize)]
+pub struct ImportKeyRequest {
+    pub wallet_handle_token: String,
+    /// The 64-byte ed25519 private key - the secret seed followed by the public key.
+    #[serde(serialize_with = "serialize_bytes")]
+    pub private_key: Vec<u8>,
+}
+
+/// ImportKeyResponse is the response to `POST /v1/key/import`.
+#[derive(Debug, Deserialize)]
+pub struct ImportKeyResponse {
+    pub address: String,
+}
+
 /// SignTransactionRequest is the request for `POST /v1/transaction/sign`.
 #[derive(Serialize)]
 pub struct SignTransactionRequest {
     pub wallet_handle_token: String,
     #[serde(serialize_with = "serialize_bytes")]
     pub transaction: Vec<u8>,
+    /// The key to sign with, the sender's key is used if not set.
+    #[serde(
+        serialize_with = "serialize_optional_public_key",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub public_key: Option<Ed25519PublicKey>,
     pub wallet_password: String,
 }
 
@@ -66,6 +152,83 @@ pub struct SignTransactionResponse {
     pub signed_transaction: Vec<u8>,
 }
 
+/// SignMultisigRequest is the request for `POST /v1/multisig/sign`.
+#[derive(Serialize)]
+pub struct SignMultisigRequest {
+    pub wallet_handle_token: String,
+    #[serde(serialize_with = "serialize_bytes")]
+    pub transaction: Vec<u8>,
+    #[serde(serialize_with = "serialize_public_key")]
+    pub public_key: Ed25519PublicKey,
+    pub partial_multisig: ApiV1MultisigSig,
+    pub wallet_password: String,
+}
+
+/// SignMultisigResponse is the response to `POST /v1/multisig/sign`.
+#[derive(Debug, Deserialize)]
+pub struct SignMultisigResponse {
+    /// The msgpack encoded multisig signature.
+    #[serde(deserialize_with = "deserialize_bytes")]
+    pub multisig: Vec<u8>,
+}
+
+/// The JSON representation of a [MultisigSignature] used by the kmd.
+#[derive(Serialize)]
+pub struct ApiV1MultisigSig {
+    #[serde(rename = "Subsigs")]
+    pub subsigs: Vec<ApiV1MultisigSubsig>,
+    #[serde(rename = "Threshold")]
+    pub threshold: u8,
+    #[serde(rename = "Version")]
+    pub version: u8,
+}
+
+/// The JSON representation of a multisig subsignature used by the kmd.
+#[derive(Serialize)]
+pub struct ApiV1MultisigSubsig {
+    #[serde(rename = "Key", serialize_with = "serialize_bytes")]
+    pub key: Vec<u8>,
+    #[serde(rename = "Sig", skip_serializing_if = "Option::is_none")]
+    pub sig: Option<String>,
+}
+
+impl From<&MultisigSignature> for ApiV1MultisigSig {
+    fn from(msig: &MultisigSignature) -> Self {
+        Self {
+            subsigs: msig
+                .subsigs
+                .iter()
+                .map(|subsig| ApiV1MultisigSubsig {
+                    key: subsig.key.0.to_vec(),
+                    sig: subsig.sig.map(|sig| BASE64.encode(&sig.0)),
+                })
+                .collect(),
+            threshold: msig.threshold,
+            version: msig.version,
+        }
+    }
+}
+
+fn serialize_public_key<S>(key: &Ed25519PublicKey, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_bytes(&key.0, serializer)
+}
+
+fn serialize_optional_public_key<S>(
+    key: &Option<Ed25519PublicKey>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match key {
+        Some(key) => serialize_public_key(key, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 fn deserialize_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
 where
     D: Deserializer<'de>,
@@ -1,22 +1,39 @@
 //! Utilities for node configuration.
 
-use std::{collections::HashSet, net::SocketAddr, path::PathBuf, str::FromStr};
+use std::{
+    collections::HashSet,
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use tokio::time::timeout;
+use anyhow::Context;
+use tokio::time::{timeout, Duration};
 
-use crate::setup::{
-    self,
-    constants::LOAD_FILE_TIMEOUT_SECS,
-    node::constants::{AUTH_TOKEN_FILE, NET_ADDR_FILE, REST_ADDR_FILE},
+use crate::{
+    protocol::handshake::X_AG_ALGORAND_GENESIS,
+    setup::{
+        self,
+        constants::LOAD_FILE_TIMEOUT_SECS,
+        node::constants::{
+            AUTH_TOKEN_FILE, CONNECTION_TIMEOUT, NET_ADDR_FILE, NODE_CONFIG_FILE, RELAY_NET_ADDR,
+            REST_ADDR_FILE, STOP_GRACE_PERIOD,
+        },
+    },
 };
 
 /// Startup configuration for the node.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct NodeConfig {
     /// Setting this option to true will enable node logging to stdout.
     pub log_to_stdout: bool,
+    /// Setting this option to true will capture the node's stdout and stderr in memory.
+    pub capture_output: bool,
     /// The path of the cache directory of the node.
     pub path: PathBuf,
+    /// Relay nodes listen for incoming network connections.
+    pub is_relay: bool,
     /// The network socket address of the node.
     pub net_addr: Option<SocketAddr>,
     /// The REST API socket address of the node.
@@ -25,41 +42,142 @@ pub struct NodeConfig {
     pub rest_api_auth_token: String,
     /// The initial peer set of the node.
     pub initial_peers: HashSet<SocketAddr>,
+    /// Genesis ID of the network the node belongs to.
+    pub genesis_id: String,
+    /// How long to wait for the node to start accepting connections.
+    pub start_timeout: Duration,
+    /// How long to wait for the node to write its address and token files.
+    pub load_file_timeout: Duration,
+    /// How long to wait for the node to exit cleanly before it is killed.
+    pub stop_grace_period: Duration,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        Self {
+            log_to_stdout: false,
+            capture_output: false,
+            path: Default::default(),
+            is_relay: false,
+            net_addr: None,
+            rest_api_addr: None,
+            rest_api_auth_token: Default::default(),
+            initial_peers: Default::default(),
+            genesis_id: X_AG_ALGORAND_GENESIS.into(),
+            start_timeout: CONNECTION_TIMEOUT,
+            load_file_timeout: LOAD_FILE_TIMEOUT_SECS,
+            stop_grace_period: STOP_GRACE_PERIOD,
+        }
+    }
 }
 
 impl NodeConfig {
+    /// Reads from the node's configuration file whether the node is a relay node.
+    pub fn read_is_relay(path: &Path) -> anyhow::Result<bool> {
+        let node_cfg = fs::read_to_string(path.join(NODE_CONFIG_FILE))?;
+        let node_cfg: serde_json::Value = serde_json::from_str(&node_cfg)?;
+
+        Ok(node_cfg
+            .get("NetAddress")
+            .and_then(|addr| addr.as_str())
+            .map_or(false, |addr| !addr.is_empty()))
+    }
+
+    /// Writes the network address option to the node's configuration file, which makes the node a
+    /// relay node when the address is set.
+    pub fn write_is_relay(path: &Path, is_relay: bool) -> anyhow::Result<()> {
+        let cfg_path = path.join(NODE_CONFIG_FILE);
+        let node_cfg = fs::read_to_string(&cfg_path)?;
+        let mut node_cfg: serde_json::Value = serde_json::from_str(&node_cfg)?;
+
+        // The port is chosen by the node and published in the net address file.
+        let net_addr = if is_relay { RELAY_NET_ADDR } else { "" };
+        node_cfg
+            .as_object_mut()
+            .ok_or_else(|| anyhow::anyhow!("invalid node configuration file"))?
+            .insert("NetAddress".into(), net_addr.into());
+
+        fs::write(cfg_path, serde_json::to_string_pretty(&node_cfg)?)?;
+        Ok(())
+    }
+
     /// Fetches the node's runtime configuration - addresses and authorization tokens.
+    ///
+    /// Fails if the node doesn't write the files within the configured timeout.
     pub async fn load_runtime_cfg(&mut self) -> anyhow::Result<()> {
         let mut net_addr = String::new();
         let mut rest_addr = String::new();
+        let load_timeout = self.load_file_timeout;
 
-        timeout(LOAD_FILE_TIMEOUT_SECS, async {
+        timeout(load_timeout, async {
             let net_addr_path = self.path.join(NET_ADDR_FILE);
             let rest_addr_path = self.path.join(REST_ADDR_FILE);
             let auth_token_path = self.path.join(AUTH_TOKEN_FILE);
 
-            net_addr = setup::try_read_to_string(&net_addr_path).await;
+            // Non-relay nodes do not listen for incoming network connections.
+            if self.is_relay {
+                net_addr = setup::try_read_to_string(&net_addr_path).await;
+            }
             rest_addr = setup::try_read_to_string(&rest_addr_path).await;
 
             self.rest_api_auth_token = setup::try_read_to_string(&auth_token_path).await;
         })
         .await
-        .expect("couldn't fetch node's addresses");
-
-        self.net_addr = Some(
-            SocketAddr::from_str(
-                net_addr
-                    .trim()
-                    .strip_prefix("http://")
-                    .expect("the http prefix is missing"),
-            )
-            .expect("couldn't create the network socket address"),
-        );
-        self.rest_api_addr = Some(
-            SocketAddr::from_str(rest_addr.trim())
-                .expect("couldn't create the REST API socket address"),
-        );
+        .with_context(|| format!("couldn't fetch the node's addresses in {load_timeout:?}"))?;
+
+        if self.is_relay {
+            let net_addr = net_addr
+                .trim()
+                .strip_prefix("http://")
+                .ok_or_else(|| anyhow::anyhow!("the http prefix is missing"))?;
+            self.net_addr = Some(SocketAddr::from_str(net_addr)?);
+        }
+        self.rest_api_addr = Some(SocketAddr::from_str(rest_addr.trim())?);
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use tempfile::TempDir;
+    use tokio::time::error::Elapsed;
+    use ziggurat_core_utils::err_constants::ERR_TEMPDIR_NEW;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn load_runtime_cfg_times_out_cleanly() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut cfg = NodeConfig {
+            path: target.path().to_path_buf(),
+            is_relay: true,
+            load_file_timeout: Duration::from_millis(300),
+            ..Default::default()
+        };
+
+        // No node writes its files to an empty directory.
+        let err = cfg.load_runtime_cfg().await.unwrap_err();
+        assert!(err.downcast_ref::<Elapsed>().is_some());
+        assert!(cfg.net_addr.is_none());
+        assert!(cfg.rest_api_addr.is_none());
+    }
+
+    #[tokio::test]
+    async fn load_runtime_cfg_rejects_an_invalid_address() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        fs::write(target.path().join(REST_ADDR_FILE), "not an address").unwrap();
+        fs::write(target.path().join(AUTH_TOKEN_FILE), "token").unwrap();
+        let mut cfg = NodeConfig {
+            path: target.path().to_path_buf(),
+            is_relay: false,
+            load_file_timeout: Duration::from_millis(300),
+            ..Default::default()
+        };
+
+        // The files are loaded in time, so it's not reported as a timeout.
+        let err = cfg.load_runtime_cfg().await.unwrap_err();
+        assert!(err.downcast_ref::<Elapsed>().is_none());
+        assert!(cfg.rest_api_addr.is_none());
+    }
+}
@@ -12,14 +12,26 @@ pub const NODE_DIR: &str = "Node";
 /// documentation](https://developer.algorand.org/docs/run-a-node/reference/config/).
 pub const NET_ADDR_FILE: &str = "algod-listen.net";
 
+/// The `NetAddress` configuration option value used for relay nodes.
+pub const RELAY_NET_ADDR: &str = "127.0.0.1:0";
+
 /// The address on which the node listens for REST API calls.
 ///
 /// The address is named `EndpointAddress` in the [official Algorand
 /// documentation](https://developer.algorand.org/docs/run-a-node/reference/config/).
 pub const REST_ADDR_FILE: &str = "algod.net";
 
+/// Node's configuration file, created when setting up the private network.
+///
+/// Described in the [official Algorand
+/// documentation](https://developer.algorand.org/docs/run-a-node/reference/config/).
+pub const NODE_CONFIG_FILE: &str = "config.json";
+
 /// Authentication token file which stores the token needed for some REST API calls.
 pub const AUTH_TOKEN_FILE: &str = "algod.token";
 
 /// Timeout when waiting for [Node](crate::setup::node::Node)'s start.
 pub const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long to wait for the [Node](crate::setup::node::Node) to exit cleanly before killing it.
+pub const STOP_GRACE_PERIOD: Duration = Duration::from_secs(5);
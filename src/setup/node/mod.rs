@@ -2,14 +2,20 @@
 
 mod config;
 mod constants;
+pub mod private_network;
 pub mod rest_api;
 
 use std::{
     collections::HashSet,
-    fs, io,
+    ffi::OsString,
+    fs,
+    io::{self, Read},
     net::SocketAddr,
-    path::{Path, PathBuf},
+    path::Path,
     process::{Child, Command, Stdio},
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+    time::Instant,
 };
 
 use anyhow::Result;
@@ -17,31 +23,98 @@ use fs_extra::dir;
 use tokio::{
     io::AsyncWriteExt,
     net::TcpStream,
-    time::{sleep, Duration},
+    time::{error::Elapsed, sleep, Duration},
 };
 
-use crate::setup::{
-    constants::{ALGORAND_SETUP_DIR, PRIVATE_NETWORK_DIR},
-    get_algorand_work_path,
-    node::{
-        config::NodeConfig,
-        constants::{CONNECTION_TIMEOUT, NET_ADDR_FILE, NODE_DIR, REST_ADDR_FILE},
-        rest_api::client::RestClient,
+use crate::{
+    protocol::codecs::msgpack::Round,
+    setup::{
+        constants::ALGORAND_SETUP_DIR,
+        get_algorand_work_path,
+        node::{
+            config::NodeConfig,
+            constants::{NET_ADDR_FILE, REST_ADDR_FILE},
+            private_network::PrivateNetwork,
+            rest_api::{client::RestClient, message::NodeStatus},
+        },
+        node_meta_data::NodeMetaData,
     },
-    node_meta_data::NodeMetaData,
 };
 
+/// How often to check whether the node has exited while waiting for it to stop.
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How often to check the node's status while waiting for it to sync.
+const SYNC_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 #[derive(Debug, PartialEq)]
 pub enum ChildExitCode {
     Success,
     ErrorCode(Option<i32>),
 }
 
+/// Output of the node's process captured in memory.
+#[derive(Debug, Clone, Default)]
+pub struct CapturedOutput {
+    /// Everything the node wrote to stdout, which includes the logs if they are written there.
+    pub stdout: String,
+    /// Everything the node wrote to stderr.
+    pub stderr: String,
+}
+
+/// Buffers the node's output is captured into, kept across the restarts of the node.
+#[derive(Default)]
+struct OutputCapture {
+    stdout: Arc<Mutex<Vec<u8>>>,
+    stderr: Arc<Mutex<Vec<u8>>>,
+    /// Threads reading the output of the running process.
+    readers: Vec<JoinHandle<()>>,
+}
+
+impl OutputCapture {
+    /// Starts reading the `source` into the `buffer` until the source is closed.
+    fn read_into(&mut self, mut source: impl Read + Send + 'static, buffer: Arc<Mutex<Vec<u8>>>) {
+        self.readers.push(std::thread::spawn(move || {
+            let mut chunk = [0; 4096];
+            while let Ok(len @ 1..) = source.read(&mut chunk) {
+                buffer
+                    .lock()
+                    .expect("poisoned lock")
+                    .extend_from_slice(&chunk[..len]);
+            }
+        }));
+    }
+
+    /// Waits until the output of the exited process is fully read.
+    fn wait_for_readers(&mut self) {
+        for reader in self.readers.drain(..) {
+            if reader.join().is_err() {
+                eprintln!("Failed to capture the node's output");
+            }
+        }
+    }
+
+    fn output(&self) -> CapturedOutput {
+        let read = |buffer: &Mutex<Vec<u8>>| {
+            String::from_utf8_lossy(&buffer.lock().expect("poisoned lock")).into_owned()
+        };
+
+        CapturedOutput {
+            stdout: read(&self.stdout),
+            stderr: read(&self.stderr),
+        }
+    }
+}
+
 pub struct NodeBuilder {
     /// Node's startup configuration.
     conf: NodeConfig,
     /// Node's process metadata read from Ziggurat configuration files.
     meta: NodeMetaData,
+    /// Index of the node directory in the private network.
+    node_idx: usize,
+    /// Overrides the relay configuration of the private network node when set.
+    relay: Option<bool>,
 }
 
 impl NodeBuilder {
@@ -52,30 +125,48 @@ impl NodeBuilder {
         let conf = NodeConfig::default();
         let meta = NodeMetaData::new(&setup_path)?;
 
-        Ok(Self { conf, meta })
+        Ok(Self {
+            conf,
+            meta,
+            node_idx: 0,
+            relay: None,
+        })
     }
 
     /// Creates a [Node] according to configuration.
+    ///
+    /// Fails if the private network has no node with the configured index.
     pub fn build(&self, target: &Path) -> Result<Node> {
+        let node_count = PrivateNetwork::node_count()?;
+        if self.node_idx >= node_count {
+            anyhow::bail!(
+                "the private network has {node_count} nodes, node index {} is out of range",
+                self.node_idx
+            );
+        }
+
         if !target.exists() {
             fs::create_dir_all(target)?;
         }
 
-        // Currently we can start only the first node.
-        let source = Node::get_path(0)?;
+        let source = PrivateNetwork::node_path(self.node_idx)?;
 
         let mut copy_options = dir::CopyOptions::new();
         copy_options.content_only = true;
         copy_options.overwrite = true;
         dir::copy(source, target, &copy_options)?;
 
-        // Note: we would implement dynamic node configuration here if the need occurs.
+        if let Some(relay) = self.relay {
+            NodeConfig::write_is_relay(target, relay)?;
+        }
 
         let mut conf = self.conf.clone();
         conf.path = target.to_path_buf();
+        conf.is_relay = NodeConfig::read_is_relay(target)?;
 
         Ok(Node {
             child: None,
+            output: conf.capture_output.then(OutputCapture::default),
             conf,
             meta: self.meta.clone(),
             rest_client: None,
@@ -88,6 +179,58 @@ impl NodeBuilder {
         self
     }
 
+    /// Captures the node's stdout and stderr in memory, see [Node::captured_output].
+    ///
+    /// The captured output is no longer logged to Ziggurat's output stream. The node writes its
+    /// logs to stdout only if [NodeBuilder::log_to_stdout] is enabled as well.
+    pub fn capture_output(mut self) -> Self {
+        self.conf.capture_output = true;
+        self
+    }
+
+    /// Sets which node from the private network should be used.
+    ///
+    /// The first node (index 0) is the relay node and it is used by default. The index must be
+    /// lower than [PrivateNetwork::node_count].
+    pub fn node_index(mut self, idx: usize) -> Self {
+        self.node_idx = idx;
+        self
+    }
+
+    /// Sets whether the node is a relay node which listens for incoming network connections.
+    ///
+    /// By default, the configuration of the private network node is used.
+    pub fn relay(mut self, relay: bool) -> Self {
+        self.relay = Some(relay);
+        self
+    }
+
+    /// Sets the genesis ID of the node's network.
+    pub fn genesis_id(mut self, genesis_id: &str) -> Self {
+        self.conf.genesis_id = genesis_id.into();
+        self
+    }
+
+    /// Sets how long to wait for the node to start accepting connections.
+    pub fn start_timeout(mut self, timeout: Duration) -> Self {
+        self.conf.start_timeout = timeout;
+        self
+    }
+
+    /// Sets how long to wait for the node to write its address and token files after starting.
+    pub fn load_file_timeout(mut self, timeout: Duration) -> Self {
+        self.conf.load_file_timeout = timeout;
+        self
+    }
+
+    /// Sets how long to wait for the node to exit cleanly when it's stopped, before killing it.
+    ///
+    /// A node which is dropped without being stopped is killed right away instead.
+    pub fn stop_grace_period(mut self, grace_period: Duration) -> Self {
+        self.conf.stop_grace_period = grace_period;
+        self
+    }
+
     /// Sets initial peers for the node.
     pub fn initial_peers<I: IntoIterator<Item = SocketAddr>>(mut self, addrs: I) -> Self {
         self.conf.initial_peers = addrs.into_iter().collect::<HashSet<SocketAddr>>();
@@ -98,6 +241,8 @@ impl NodeBuilder {
 pub struct Node {
     /// Node's process.
     child: Option<Child>,
+    /// Node's output captured since the node was built, if enabled.
+    output: Option<OutputCapture>,
     /// Node's startup configuration.
     conf: NodeConfig,
     /// Node's process metadata read from Ziggurat configuration files.
@@ -115,8 +260,8 @@ impl Node {
     }
 
     /// Waits the node to start responding.
-    async fn wait_for_start(addr: SocketAddr) {
-        tokio::time::timeout(CONNECTION_TIMEOUT, async {
+    async fn wait_for_start(addr: SocketAddr, start_timeout: Duration) -> io::Result<()> {
+        tokio::time::timeout(start_timeout, async {
             const SLEEP: Duration = Duration::from_millis(100);
 
             loop {
@@ -129,29 +274,33 @@ impl Node {
             }
         })
         .await
-        .unwrap();
+        .map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("the node didn't start responding on {addr} in {start_timeout:?}"),
+            )
+        })
     }
 
-    /// Starts the node instance.
-    pub async fn start(&mut self) {
-        let (stdout, stderr) = match self.conf.log_to_stdout {
-            true => (Stdio::inherit(), Stdio::inherit()),
-            false => (Stdio::null(), Stdio::null()),
-        };
+    /// Builds the full argument list for the start command.
+    ///
+    /// The list is built anew on each call so restarting the node doesn't accumulate arguments.
+    fn start_args(&self) -> Vec<OsString> {
+        let mut args = self.meta.start_args.clone();
 
         // Specify node's data path location with the `-d` option.
-        self.meta.start_args.push("-d".into());
-        self.meta.start_args.push(self.conf.path.clone().into());
+        args.push("-d".into());
+        args.push(self.conf.path.clone().into());
 
         if self.conf.log_to_stdout {
             // Write to stdout instead of node.log using the option '-o'.
-            self.meta.start_args.push("-o".into());
+            args.push("-o".into());
         }
 
         if !self.conf.initial_peers.is_empty() {
             // Override phonebook with peer ip:port (or semicolon separated list: ip:port;ip:port;...)
             // with the option '-p'
-            self.meta.start_args.push("-p".into());
+            args.push("-p".into());
 
             let mut ip_list = String::new();
             for ip in self.conf.initial_peers.iter() {
@@ -159,43 +308,90 @@ impl Node {
             }
             ip_list.pop().unwrap(); // Remove a trailing ';'
 
-            self.meta.start_args.push(ip_list.into());
+            args.push(ip_list.into());
         }
 
+        args
+    }
+
+    /// Starts the node instance.
+    ///
+    /// Fails with [io::ErrorKind::TimedOut] if the node doesn't write its address files or start
+    /// responding within the configured timeouts, and with [io::ErrorKind::InvalidData] if the
+    /// address files are invalid.
+    pub async fn start(&mut self) -> io::Result<()> {
+        let (stdout, stderr) = match (self.output.is_some(), self.conf.log_to_stdout) {
+            (true, _) => (Stdio::piped(), Stdio::piped()),
+            (false, true) => (Stdio::inherit(), Stdio::inherit()),
+            (false, false) => (Stdio::null(), Stdio::null()),
+        };
+
         let full_path = fs::canonicalize(self.meta.path.join(&self.meta.start_command)).unwrap();
-        let child = Command::new(full_path)
+        let mut child = Command::new(full_path)
             .current_dir(&self.meta.path)
-            .args(&self.meta.start_args)
+            .args(self.start_args())
             .stdin(Stdio::null())
             .stdout(stdout)
             .stderr(stderr)
-            .spawn()
-            .expect("node failed to start");
+            .spawn()?;
+        if let Some(ref mut output) = self.output {
+            if let Some(stdout) = child.stdout.take() {
+                output.read_into(stdout, output.stdout.clone());
+            }
+            if let Some(stderr) = child.stderr.take() {
+                output.read_into(stderr, output.stderr.clone());
+            }
+        }
         self.child = Some(child);
 
         // Once the node is started, fetch its addresses.
-        self.conf
-            .load_runtime_cfg()
-            .await
-            .expect("couldn't load the node's addresses");
+        self.conf.load_runtime_cfg().await.map_err(|e| {
+            // Only a timeout is reported as such, the other errors come from the loaded files.
+            let kind = if e.downcast_ref::<Elapsed>().is_some() {
+                io::ErrorKind::TimedOut
+            } else if let Some(e) = e.downcast_ref::<io::Error>() {
+                e.kind()
+            } else {
+                io::ErrorKind::InvalidData
+            };
+            io::Error::new(kind, format!("couldn't load the node's addresses: {e}"))
+        })?;
 
         // Get the addresses - unwrap will always work here (ensured by the block above).
         let rest_api_addr = self.conf.rest_api_addr.unwrap();
-        let net_addr = self.conf.net_addr.unwrap();
+        // Non-relay nodes serve everything through the REST API address.
+        let net_addr = self.conf.net_addr.unwrap_or(rest_api_addr);
 
-        Node::wait_for_start(net_addr).await;
+        Node::wait_for_start(net_addr, self.conf.start_timeout).await?;
 
         self.rest_client = Some(RestClient::new(
             net_addr.to_string(),
             rest_api_addr.to_string(),
             self.conf.rest_api_auth_token.clone(),
+            self.conf.genesis_id.clone(),
         ));
+
+        Ok(())
     }
 
     /// Stops the node instance.
-    pub fn stop(&mut self) -> io::Result<ChildExitCode> {
+    ///
+    /// The node is asked to terminate first so it can flush its logs and exit cleanly. It is
+    /// killed if it is still running once the configured grace period expires.
+    pub async fn stop(&mut self) -> io::Result<ChildExitCode> {
         // Cannot use 'mut self' due to the Drop impl.
+        let grace_period = self.conf.stop_grace_period;
+        if let Some(ref mut child) = self.child {
+            if child.try_wait()?.is_none() {
+                Self::terminate(child, grace_period).await?;
+            }
+        }
 
+        self.kill()
+    }
+
+    /// Kills the node instance if it is still running and waits for it to exit.
+    fn kill(&mut self) -> io::Result<ChildExitCode> {
         // Remove address files since addresses may change if the node is restarted.
         let remove_file = |file_name| match fs::remove_file(self.conf.path.join(file_name)) {
             Err(e) if e.kind() != io::ErrorKind::NotFound => panic!("unexpected error: {e:?}"),
@@ -211,11 +407,15 @@ impl Node {
             None => return Ok(ChildExitCode::Success),
         };
 
-        match child.try_wait()? {
-            None => child.kill()?,
-            Some(code) => return Ok(ChildExitCode::ErrorCode(code.code())),
-        }
-        let exit = child.wait()?;
+        let exit = match child.try_wait()? {
+            // The node has already exited, on its own or once terminated.
+            Some(exit) => exit,
+            None => {
+                child.kill()?;
+                child.wait()?
+            }
+        };
+        self.wait_for_output();
 
         match exit.code() {
             None => Ok(ChildExitCode::Success),
@@ -224,28 +424,103 @@ impl Node {
         }
     }
 
+    /// Waits until the output of the exited node is fully captured, if the capture is enabled.
+    fn wait_for_output(&mut self) {
+        if let Some(ref mut output) = self.output {
+            output.wait_for_readers();
+        }
+    }
+
+    /// Sends the SIGTERM signal to the child and waits for it to exit within the grace period.
+    #[cfg(unix)]
+    async fn terminate(child: &mut Child, grace_period: Duration) -> io::Result<()> {
+        use nix::{
+            sys::signal::{kill, Signal},
+            unistd::Pid,
+        };
+
+        kill(Pid::from_raw(child.id() as i32), Signal::SIGTERM)?;
+
+        let start = Instant::now();
+        while start.elapsed() < grace_period {
+            if child.try_wait()?.is_some() {
+                break;
+            }
+            sleep(STOP_POLL_INTERVAL).await;
+        }
+
+        Ok(())
+    }
+
+    /// There is no portable graceful termination on other platforms, so the child is killed.
+    #[cfg(not(unix))]
+    async fn terminate(_child: &mut Child, _grace_period: Duration) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Restarts the node instance with the same configuration.
+    pub async fn restart(&mut self) -> io::Result<()> {
+        self.stop().await?;
+        self.start().await
+    }
+
     /// Returns the listening network address of the node.
-    /// Non-relay nodes do not have this address configured.
+    ///
+    /// Non-relay nodes do not listen for incoming network connections, so `None` is expected
+    /// for them. Their gossip messages are served through the REST API address instead.
     pub fn net_addr(&self) -> Option<SocketAddr> {
         self.conf.net_addr
     }
 
+    /// Returns the REST API address of the node.
+    pub fn rest_api_addr(&self) -> Option<SocketAddr> {
+        self.conf.rest_api_addr
+    }
+
+    /// Returns the output captured since the node was built.
+    ///
+    /// Only available if the node was built with [NodeBuilder::capture_output]. The output is
+    /// complete once the node is stopped.
+    pub fn captured_output(&self) -> Option<CapturedOutput> {
+        self.output.as_ref().map(OutputCapture::output)
+    }
+
     /// Returns the REST API client handle.
     pub fn rest_client(&self) -> Option<&RestClient> {
         self.rest_client.as_ref()
     }
 
-    fn get_path(node_dir_idx: usize) -> io::Result<PathBuf> {
-        Ok(get_algorand_work_path()?
-            .join(PRIVATE_NETWORK_DIR)
-            .join(format!("{NODE_DIR}{node_dir_idx}")))
+    /// Waits until the node has caught up with the network and returns its status.
+    ///
+    /// The node is synced once it's no longer in the catchup mode and has seen at least one round.
+    pub async fn wait_until_synced(&self, timeout: Duration) -> Result<NodeStatus> {
+        let rest_client = self
+            .rest_client()
+            .ok_or_else(|| anyhow::anyhow!("the node isn't started"))?;
+
+        tokio::time::timeout(timeout, async {
+            loop {
+                match rest_client.get_status().await {
+                    Ok(status) if status.catchup_time == 0 && status.last_round > Round(0) => {
+                        return status
+                    }
+                    Ok(status) => tracing::trace!("the node is catching up: {status:?}"),
+                    Err(e) => tracing::trace!("{e}"),
+                }
+
+                sleep(SYNC_POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .map_err(|_| anyhow::anyhow!("the node didn't sync in {timeout:?}"))
     }
 }
 
 impl Drop for Node {
     fn drop(&mut self) {
+        // Dropping can't wait for the grace period, so a node which wasn't stopped is killed.
         // We should avoid a panic.
-        if let Err(e) = self.stop() {
+        if let Err(e) = self.kill() {
             eprintln!("Failed to stop the node: {e}");
         }
     }
@@ -257,6 +532,7 @@ mod test {
     use ziggurat_core_utils::err_constants::{ERR_NODE_BUILD, ERR_TEMPDIR_NEW};
 
     use super::*;
+    use crate::tools::constants::ERR_NODE_START;
 
     const SLEEP: Duration = Duration::from_millis(500);
 
@@ -274,20 +550,362 @@ mod test {
         assert!(node.rest_client().is_none());
         assert!(node.net_addr().is_none());
 
-        node.start().await;
+        node.start().await.expect(ERR_NODE_START);
         // Addresses are available once the node is started.
         assert!(node.rest_client().is_some());
         assert!(node.net_addr().is_some());
 
         sleep(SLEEP).await;
 
-        assert!(node.stop().is_ok());
+        assert!(node.stop().await.is_ok());
         // Addresses are deleted after the node is stopped.
         assert!(node.net_addr().is_none());
 
         // Restart the node.
-        node.start().await;
+        node.start().await.expect(ERR_NODE_START);
         sleep(SLEEP).await;
         // The node will be stopped via the Drop impl.
     }
+
+    #[tokio::test]
+    async fn start_multiple_nodes() {
+        let target0 = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let target1 = TempDir::new().expect(ERR_TEMPDIR_NEW);
+
+        let mut node0 = Node::builder()
+            .node_index(0)
+            .build(target0.path())
+            .expect(ERR_NODE_BUILD);
+        node0.start().await.expect(ERR_NODE_START);
+        let node0_addr = node0
+            .net_addr()
+            .expect("the relay node should have an address");
+
+        let mut node1 = Node::builder()
+            .node_index(1)
+            .initial_peers([node0_addr])
+            .build(target1.path())
+            .expect(ERR_NODE_BUILD);
+        node1.start().await.expect(ERR_NODE_START);
+
+        // Both nodes are up and running, but only the relay node listens for connections.
+        assert!(node0.rest_client().is_some());
+        assert!(node1.rest_client().is_some());
+        assert!(node1.net_addr().is_none());
+
+        sleep(SLEEP).await;
+
+        assert!(node1.stop().await.is_ok());
+        assert!(node0.stop().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn start_non_relay_node() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder()
+            .relay(false)
+            .build(target.path())
+            .expect(ERR_NODE_BUILD);
+
+        node.start().await.expect(ERR_NODE_START);
+        assert!(node.rest_client().is_some());
+        assert!(node.net_addr().is_none());
+
+        assert!(node.stop().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn start_relay_node() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder()
+            .node_index(1)
+            .relay(true)
+            .build(target.path())
+            .expect(ERR_NODE_BUILD);
+
+        node.start().await.expect(ERR_NODE_START);
+        assert!(node.net_addr().is_some());
+
+        assert!(node.stop().await.is_ok());
+    }
+
+    /// Returns the arguments the running node was spawned with, as reported by the kernel.
+    #[cfg(target_os = "linux")]
+    fn spawned_args(node: &Node) -> Vec<OsString> {
+        use std::os::unix::ffi::OsStringExt;
+
+        let pid = node.child.as_ref().expect("the node isn't running").id();
+        let cmdline = fs::read(format!("/proc/{pid}/cmdline")).expect("couldn't read the cmdline");
+
+        // Each argument is terminated with a NUL byte, the first one is the program.
+        cmdline
+            .split(|&b| b == 0)
+            .skip(1)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| OsString::from_vec(arg.to_vec()))
+            .collect()
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn start_args_do_not_accumulate() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder()
+            .log_to_stdout(true)
+            .initial_peers(["127.0.0.1:4161".parse().unwrap()])
+            .build(target.path())
+            .expect(ERR_NODE_BUILD);
+
+        let mut expected_args = node.meta.start_args.clone();
+        expected_args.extend([
+            "-d".into(),
+            node.conf.path.clone().into(),
+            "-o".into(),
+            "-p".into(),
+            "127.0.0.1:4161".into(),
+        ]);
+
+        // Check the arguments of each spawned process, not just the ones computed for it.
+        for _ in 0..2 {
+            node.start().await.expect(ERR_NODE_START);
+            assert_eq!(spawned_args(&node), expected_args);
+            assert!(node.stop().await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn node_status_advances() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+        node.start().await.expect(ERR_NODE_START);
+
+        let rest_client = node.rest_client().expect("couldn't get the rest client");
+        let first_round = rest_client
+            .get_status()
+            .await
+            .expect("couldn't get the node status")
+            .last_round;
+
+        // On average, new blocks are generated every 4 seconds.
+        sleep(Duration::from_secs(10)).await;
+
+        let status = rest_client
+            .get_status()
+            .await
+            .expect("couldn't get the node status");
+        assert!(status.last_round > first_round);
+        assert!(!status.last_version.is_empty());
+
+        assert!(node.stop().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn wait_for_round() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+        node.start().await.expect(ERR_NODE_START);
+
+        let rest_client = node.rest_client().expect("couldn't get the rest client");
+        let status = rest_client
+            .wait_for_round(Round(2))
+            .await
+            .expect("the node didn't reach the round");
+        assert!(status.last_round >= Round(2));
+
+        assert!(node.stop().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn fetched_block_keeps_state_proof_tracking() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+        node.start().await.expect(ERR_NODE_START);
+
+        let rest_client = node.rest_client().expect("couldn't get the rest client");
+        let block_cert = rest_client
+            .wait_for_block(Round(1))
+            .await
+            .expect("couldn't get a block");
+
+        // The genesis protocol has state proofs enabled, so every block tracks the next state
+        // proof round of the basic state proof type.
+        let tracking = block_cert
+            .block
+            .state_proof_tracking
+            .get(&0)
+            .expect("the block doesn't track state proofs");
+        assert!(tracking.next_round > Round(0));
+
+        assert!(node.stop().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn wait_for_block_with_a_short_timeout() {
+        const TIMEOUT: Duration = Duration::from_secs(2);
+
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+        node.start().await.expect(ERR_NODE_START);
+
+        let rest_client = node
+            .rest_client()
+            .expect("couldn't get the rest client")
+            .clone()
+            .with_timeout(TIMEOUT);
+
+        // The network won't reach this round any time soon, so the wait ends with the timeout.
+        let start = Instant::now();
+        let _: Elapsed = rest_client
+            .wait_for_block(Round(1_000_000))
+            .await
+            .expect_err("the block shouldn't be available");
+        let elapsed = start.elapsed();
+        assert!(elapsed >= TIMEOUT && elapsed < TIMEOUT * 2);
+
+        assert!(node.stop().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn genesis_matches_transaction_params() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+        node.start().await.expect(ERR_NODE_START);
+
+        let rest_client = node.rest_client().expect("couldn't get the rest client");
+        let genesis = rest_client
+            .genesis()
+            .await
+            .expect("couldn't get the genesis");
+        let params = rest_client
+            .get_transaction_params()
+            .await
+            .expect("couldn't get the transaction parameters");
+
+        assert_eq!(genesis.hash, params.genesis_hash);
+        assert_eq!(genesis.id(), params.genesis_id);
+        assert!(!genesis.allocation.is_empty());
+
+        assert!(node.stop().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn start_with_a_generous_timeout() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder()
+            .start_timeout(Duration::from_secs(60))
+            .build(target.path())
+            .expect(ERR_NODE_BUILD);
+
+        assert!(node.start().await.is_ok());
+        assert!(node.rest_client().is_some());
+
+        assert!(node.stop().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn restart_the_node() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder()
+            .log_to_stdout(false)
+            .build(target.path())
+            .expect(ERR_NODE_BUILD);
+        node.start().await.expect(ERR_NODE_START);
+
+        for _ in 0..3 {
+            assert!(node.restart().await.is_ok());
+
+            // The node should be reachable after each restart.
+            let net_addr = node.net_addr().expect("the address should be available");
+            assert!(TcpStream::connect(net_addr).await.is_ok());
+        }
+
+        assert!(node.stop().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn stop_the_node_gracefully() {
+        const GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder()
+            .stop_grace_period(GRACE_PERIOD)
+            .build(target.path())
+            .expect(ERR_NODE_BUILD);
+        node.start().await.expect(ERR_NODE_START);
+
+        sleep(SLEEP).await;
+
+        let start = Instant::now();
+        assert_eq!(node.stop().await.unwrap(), ChildExitCode::Success);
+        // The node exited on its own rather than being killed once the grace period expired.
+        assert!(start.elapsed() < GRACE_PERIOD);
+    }
+
+    #[tokio::test]
+    async fn capture_the_output_of_a_crashed_node() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder()
+            .capture_output()
+            .load_file_timeout(Duration::from_secs(1))
+            .build(target.path())
+            .expect(ERR_NODE_BUILD);
+        // The node refuses to start with an unknown flag.
+        node.meta.start_args.push("-ziggurat-unknown-flag".into());
+
+        assert!(node.start().await.is_err());
+        assert!(matches!(
+            node.stop().await.unwrap(),
+            ChildExitCode::ErrorCode(Some(code)) if code != 0
+        ));
+
+        let output = node
+            .captured_output()
+            .expect("the output should be captured");
+        assert!(!output.stderr.is_empty(), "the node's stderr is empty");
+    }
+
+    #[test]
+    fn node_count_matches_the_private_network() {
+        let node_count = PrivateNetwork::node_count().expect("couldn't count the nodes");
+        assert!(node_count > 0, "the private network has no nodes");
+
+        for idx in 0..node_count {
+            let path = PrivateNetwork::node_path(idx).unwrap();
+            assert!(path.is_dir(), "{path:?} is not a directory");
+        }
+        assert!(!PrivateNetwork::node_path(node_count).unwrap().exists());
+    }
+
+    #[test]
+    fn build_a_node_out_of_range() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let node_count = PrivateNetwork::node_count().expect("couldn't count the nodes");
+
+        let result = Node::builder().node_index(node_count).build(target.path());
+        let err = result.err().expect("the node index should be out of range");
+        assert!(err.to_string().contains("out of range"));
+        // Nothing is copied to the target directory.
+        assert_eq!(fs::read_dir(target.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn wait_until_synced() {
+        const TIMEOUT: Duration = Duration::from_secs(30);
+
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+
+        // The node can't be synced before it's started.
+        assert!(node.wait_until_synced(TIMEOUT).await.is_err());
+
+        node.start().await.expect(ERR_NODE_START);
+        let status = node
+            .wait_until_synced(TIMEOUT)
+            .await
+            .expect("the node didn't sync");
+        assert_eq!(status.catchup_time, 0);
+        assert!(status.last_round > Round(0));
+
+        assert!(node.stop().await.is_ok());
+    }
 }
@@ -0,0 +1,94 @@
+//! The preloaded private network the nodes are copied from.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::setup::{
+    constants::PRIVATE_NETWORK_DIR, get_algorand_work_path, node::constants::NODE_DIR,
+};
+
+/// The private network template created by the setup script.
+pub struct PrivateNetwork;
+
+impl PrivateNetwork {
+    /// Returns the number of node directories in the private network.
+    ///
+    /// Fails unless the node directories are numbered from zero without gaps.
+    pub fn node_count() -> io::Result<usize> {
+        Self::count_node_dirs(&Self::path()?)
+    }
+
+    /// Returns the path of the private network directory.
+    pub fn path() -> io::Result<PathBuf> {
+        Ok(get_algorand_work_path()?.join(PRIVATE_NETWORK_DIR))
+    }
+
+    /// Returns the path of the node directory with the given index.
+    pub fn node_path(node_dir_idx: usize) -> io::Result<PathBuf> {
+        Ok(Self::path()?.join(format!("{NODE_DIR}{node_dir_idx}")))
+    }
+
+    /// Counts the node directories in the `path` and checks that each index below the count has one.
+    fn count_node_dirs(path: &Path) -> io::Result<usize> {
+        let mut count = 0;
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir()
+                && Self::is_node_dir(&entry.file_name().to_string_lossy())
+            {
+                count += 1;
+            }
+        }
+
+        for idx in 0..count {
+            if !path.join(format!("{NODE_DIR}{idx}")).is_dir() {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{count} node directories, but {NODE_DIR}{idx} is missing"),
+                ));
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Checks whether the name is a node directory name, e.g. "Node0".
+    fn is_node_dir(name: &str) -> bool {
+        name.strip_prefix(NODE_DIR)
+            .is_some_and(|idx| !idx.is_empty() && idx.bytes().all(|b| b.is_ascii_digit()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::TempDir;
+    use ziggurat_core_utils::err_constants::ERR_TEMPDIR_NEW;
+
+    use super::*;
+
+    #[test]
+    fn count_contiguous_node_dirs() {
+        let network = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        for name in ["Node0", "Node1", "Node2", "NodeX", "Primary"] {
+            fs::create_dir(network.path().join(name)).unwrap();
+        }
+        // Files don't count as nodes.
+        fs::write(network.path().join("Node3"), "").unwrap();
+
+        let count = PrivateNetwork::count_node_dirs(network.path()).expect("invalid node dirs");
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn fail_on_a_gap_in_node_dirs() {
+        let network = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        for name in ["Node0", "Node2"] {
+            fs::create_dir(network.path().join(name)).unwrap();
+        }
+
+        let err = PrivateNetwork::count_node_dirs(network.path()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -6,61 +6,94 @@
 
 use std::time::Duration;
 
-use reqwest::{header, Client};
+use reqwest::{header, Client, StatusCode};
 use tokio::time::{error::Elapsed, sleep};
 
 use crate::{
-    protocol::constants::USER_AGENT,
-    setup::node::rest_api::message::{EncodedBlockCert, TransactionParams},
+    protocol::{codecs::msgpack::Round, constants::USER_AGENT},
+    setup::node::rest_api::message::{
+        AccountInformation, EncodedBlockCert, Genesis, NodeStatus, PendingTransactionResponse,
+        PostTransactionsResponse, TransactionParams,
+    },
 };
 
 const API_HEADER_TOKEN: &str = "X-Algo-API-Token";
 
-/// Timeout time for REST requests.
+/// Default timeout time for REST requests.
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// [RestClient] supports all required REST API handling.
-#[derive(Default)]
+#[derive(Clone)]
 pub struct RestClient {
     net_addr: String,
     rest_addr: String,
     token: String,
+    genesis_id: String,
     http_client: Client,
+    /// Timeout time for requests and for waiting on the node.
+    timeout: Duration,
+}
+
+impl Default for RestClient {
+    fn default() -> Self {
+        Self {
+            net_addr: Default::default(),
+            rest_addr: Default::default(),
+            token: Default::default(),
+            genesis_id: Default::default(),
+            http_client: Default::default(),
+            timeout: REQUEST_TIMEOUT,
+        }
+    }
 }
 
 impl RestClient {
     // Restriction: only the node module can create new clients.
     /// Creates a new [RestClient].
-    pub(in super::super) fn new(net_addr: String, rest_addr: String, token: String) -> Self {
+    pub(in super::super) fn new(
+        net_addr: String,
+        rest_addr: String,
+        token: String,
+        genesis_id: String,
+    ) -> Self {
         Self {
             net_addr,
             rest_addr,
             token,
+            genesis_id,
             http_client: reqwest::Client::new(),
+            timeout: REQUEST_TIMEOUT,
         }
     }
 
+    /// Sets the timeout for requests and for waiting on the node, 10 seconds by default.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
     async fn get_block(&self, round: &str) -> anyhow::Result<reqwest::Response, reqwest::Error> {
         // Replica of the HTTP request our synth node receives from the node.
         self.http_client
             .get(format!(
-                "http://{}/v1/private-v1/block/{}",
-                self.net_addr, round
+                "http://{}/v1/{}/block/{}",
+                self.net_addr, self.genesis_id, round
             ))
             .header(header::HOST, self.net_addr.clone())
             .header(header::USER_AGENT, USER_AGENT)
             .header(header::ACCEPT_ENCODING, "gzip")
+            .timeout(self.timeout)
             .send()
             .await
     }
 
     /// Returns a block for a provided round.
-    pub async fn wait_for_block(&self, round: u64) -> Result<EncodedBlockCert, Elapsed> {
+    pub async fn wait_for_block(&self, round: Round) -> Result<EncodedBlockCert, Elapsed> {
         // Algod V1 documentation states that the round format is 'integer (int64)',
         // but it's actually an int64 integer encoded in base36.
-        let round = radix_fmt::radix_36(round).to_string();
+        let round = radix_fmt::radix_36(round.0).to_string();
 
-        tokio::time::timeout(REQUEST_TIMEOUT, async move {
+        tokio::time::timeout(self.timeout, async move {
             loop {
                 if let Ok(rsp) = self.get_block(&round).await {
                     if rsp.error_for_status_ref().is_err() {
@@ -86,6 +119,7 @@ impl RestClient {
         self.http_client
             .get(&format!("http://{}/v2/transactions/params", self.rest_addr))
             .header(API_HEADER_TOKEN, &self.token)
+            .timeout(self.timeout)
             .send()
             .await?
             .error_for_status()?
@@ -93,4 +127,116 @@ impl RestClient {
             .await
             .map_err(|e| anyhow::anyhow!("couldn't get the transaction parameters: {e}"))
     }
+
+    /// Waits until the node reaches the provided round and returns the node status.
+    pub async fn wait_for_round(&self, round: Round) -> anyhow::Result<NodeStatus> {
+        tokio::time::timeout(self.timeout, async move {
+            loop {
+                match self.get_status().await {
+                    Ok(status) if status.last_round >= round => return status,
+                    Ok(status) => tracing::trace!("the node is at round {}", status.last_round),
+                    Err(e) => tracing::trace!("{e}"),
+                }
+
+                sleep(Duration::from_millis(500)).await;
+            }
+        })
+        .await
+        .map_err(|_| anyhow::anyhow!("the node didn't reach round {round} in time"))
+    }
+
+    /// Gets the genesis of the node's network, along with its hash.
+    pub async fn genesis(&self) -> anyhow::Result<Genesis> {
+        let mut genesis: Genesis = self
+            .http_client
+            .get(&format!("http://{}/genesis", self.rest_addr))
+            .header(API_HEADER_TOKEN, &self.token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("couldn't get the genesis: {e}"))?;
+        genesis.hash = genesis.compute_hash();
+
+        Ok(genesis)
+    }
+
+    /// Gets the current node status.
+    pub async fn get_status(&self) -> anyhow::Result<NodeStatus> {
+        self.http_client
+            .get(&format!("http://{}/v2/status", self.rest_addr))
+            .header(API_HEADER_TOKEN, &self.token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("couldn't get the node status: {e}"))
+    }
+
+    /// Submits a raw msgpack encoded signed transaction and returns its ID.
+    pub async fn send_raw_transaction(&self, bytes: &[u8]) -> anyhow::Result<String> {
+        let rsp: PostTransactionsResponse = self
+            .http_client
+            .post(&format!("http://{}/v2/transactions", self.rest_addr))
+            .header(API_HEADER_TOKEN, &self.token)
+            .header(header::CONTENT_TYPE, "application/x-binary")
+            .body(bytes.to_vec())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("couldn't submit the transaction: {e}"))?;
+
+        Ok(rsp.tx_id)
+    }
+
+    /// Gets the status of a submitted transaction.
+    ///
+    /// Returns `None` if the node doesn't know about the transaction.
+    pub async fn pending_transaction_information(
+        &self,
+        tx_id: &str,
+    ) -> anyhow::Result<Option<PendingTransactionResponse>> {
+        let rsp = self
+            .http_client
+            .get(&format!(
+                "http://{}/v2/transactions/pending/{tx_id}",
+                self.rest_addr
+            ))
+            .header(API_HEADER_TOKEN, &self.token)
+            .send()
+            .await?;
+
+        if rsp.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        rsp.error_for_status()?
+            .json()
+            .await
+            .map(Some)
+            .map_err(|e| anyhow::anyhow!("couldn't get the pending transaction information: {e}"))
+    }
+
+    /// Gets the account information for a base32 encoded address.
+    pub async fn account_information(&self, address: &str) -> anyhow::Result<AccountInformation> {
+        let rsp = self
+            .http_client
+            .get(&format!("http://{}/v2/accounts/{address}", self.rest_addr))
+            .header(API_HEADER_TOKEN, &self.token)
+            .send()
+            .await?;
+
+        if let Err(e) = rsp.error_for_status_ref() {
+            let msg = rsp.text().await.unwrap_or_default();
+            anyhow::bail!("couldn't get the account information for {address}: {e}: {msg}");
+        }
+
+        rsp.json()
+            .await
+            .map_err(|e| anyhow::anyhow!("couldn't parse the account information: {e}"))
+    }
 }
@@ -7,137 +7,293 @@
 use data_encoding::BASE64;
 use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::protocol::codecs::msgpack::{Ed25519Seed, HashDigest, Round};
-
-/// [EncodedBlockCert] defines how get-block response encodes a block and its certificate.
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct EncodedBlockCert {
-    /// Block header data.
-    pub block: BlockHeaderMsgPack,
-    /// Certificate.
-    pub cert: Certificate,
+// The block types are shared with the block responses received over the network.
+use crate::protocol::codecs::msgpack::{
+    Address, CanonicalValue, HashDigest, Payment, Round, Transaction, TransactionType,
+};
+pub use crate::protocol::codecs::msgpack::{
+    BlockHeaderMsgPack, Certificate, CertificateProposal, EncodedBlockCert, SignedTxnInBlock,
+};
+
+/// Domain separation prefix for the genesis hash.
+const HASH_ID_GENESIS: &[u8] = b"GE";
+
+/// TransactionParams contains the parameters that help a client construct a new transaction.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransactionParams {
+    /// Transaction fee in units of micro-Algos per byte.
+    pub fee: u64,
+
+    /// Minimum fee.
+    #[serde(rename = "min-fee")]
+    pub min_fee: u64,
+
+    /// Genesis ID.
+    #[serde(rename = "genesis-id")]
+    pub genesis_id: String,
+
+    /// Genesis hash.
+    #[serde(
+        rename = "genesis-hash",
+        deserialize_with = "deserialize_hash_in_base64"
+    )]
+    pub genesis_hash: HashDigest,
+
+    /// The last round seen.
+    #[serde(rename = "last-round")]
+    pub last_round: Round,
+
+    /// The consensus protocol version as of last round.
+    #[serde(rename = "consensus-version")]
+    pub consensus_version: String,
 }
 
-/// A Certificate contains a cryptographic proof that agreement was reached on a
-/// given block in a given round.
-///
-/// When a client first joins the network or has fallen behind and needs to catch
-/// up, certificates allow the client to verify that a block someone gives them
-/// is the real one.
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Certificate {
-    /// Proposal value.
-    #[serde(default, rename = "prop")]
-    pub proposal: Option<CertificateProposal>,
+impl TransactionParams {
+    /// Creates a payment of `amount` micro-Algos from the sender to the receiver, valid for the
+    /// next 1000 rounds.
+    ///
+    /// Returns `None` if the last valid round overflows.
+    pub fn payment(&self, sender: Address, receiver: Address, amount: u64) -> Option<Transaction> {
+        Some(Transaction {
+            sender,
+            fee: self.min_fee,
+            first_valid: self.last_round,
+            last_valid: self.last_round.checked_add(1000)?,
+            note: Vec::new(),
+            genesis_id: self.genesis_id.clone(),
+            genesis_hash: self.genesis_hash,
+            group: None,
+            lease: None,
+            txn_type: TransactionType::Payment(Payment {
+                receiver,
+                amount,
+                close_remainder_to: None,
+            }),
+            rekey_to: None,
+        })
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct CertificateProposal {
-    /// Block header's hash.
-    #[serde(rename = "dig")]
-    pub block_digest: HashDigest,
+/// PostTransactionsResponse contains the ID of a submitted transaction.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostTransactionsResponse {
+    /// Encoding of the transaction hash.
+    #[serde(rename = "txId")]
+    pub tx_id: String,
 }
 
-/// BlockHeader
-/// Deserialized from MessagePack format.
-///
-/// See [block.go](https://github.com/algorand/go-algorand/blob/master/data/bookkeeping/block.go) for more details.
-// Comments below are simply copied from the go-algorand repo.
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct BlockHeaderMsgPack {
-    /// RewardsLevel specifies how many rewards, in MicroAlgos, have been distributed
-    /// to each config.Protocol.RewardUnit of MicroAlgos since genesis.
-    #[serde(default)]
-    pub earn: u64,
+/// PendingTransactionResponse contains the status of a submitted transaction.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PendingTransactionResponse {
+    /// The round where this transaction was confirmed, if present.
+    #[serde(rename = "confirmed-round", default)]
+    pub confirmed_round: Option<Round>,
 
-    /// The FeeSink accepts transaction fees. It can only spend to the incentive pool.
-    #[serde(default, rename = "fees")]
-    pub fee_sink: Option<HashDigest>,
+    /// Indicates that the transaction was kicked out of this node's transaction pool (and
+    /// specifies why that happened). An empty string indicates the transaction wasn't kicked out.
+    #[serde(rename = "pool-error", default)]
+    pub pool_error: String,
+}
 
-    /// The number of leftover MicroAlgos after the distribution of RewardsRate/rewardUnits
-    /// MicroAlgos for every reward unit in the next round.
-    #[serde(default, rename = "frac")]
-    pub leftover_fraction: u64,
+/// AccountInformation contains the state of an account.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountInformation {
+    /// The account's address.
+    pub address: String,
 
-    /// Genesis ID to which this block belongs.
-    #[serde(default, rename = "gen")]
-    pub genensis_id: String,
+    /// Total number of micro-Algos in the account.
+    pub amount: u64,
 
-    /// Genesis hash to which this block belongs.
-    #[serde(default, rename = "gh")]
-    pub genesis_id_hash: Option<HashDigest>,
+    /// Specifies the amount of micro-Algos in the account, without the pending rewards.
+    #[serde(rename = "amount-without-pending-rewards")]
+    pub amount_without_pending_rewards: u64,
 
-    /// The hash of the previous block.
-    #[serde(default, rename = "prev")]
-    pub prevous_block_hash: Option<HashDigest>,
+    /// The round for which this information is relevant.
+    pub round: Round,
+}
 
-    /// Current protocol.
-    #[serde(default, rename = "proto")]
-    pub protocol_current: String,
+/// NodeStatus contains the information about the node's status.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NodeStatus {
+    /// The last round seen.
+    #[serde(rename = "last-round")]
+    pub last_round: Round,
+
+    /// The current protocol version.
+    #[serde(rename = "last-version")]
+    pub last_version: String,
+
+    /// The next protocol version.
+    #[serde(rename = "next-version")]
+    pub next_version: String,
+
+    /// Nanoseconds since the last round was seen.
+    #[serde(rename = "time-since-last-round")]
+    pub time_since_last_round: u64,
+
+    /// Nanoseconds spent in the catchup mode, zero if the node is not catching up.
+    #[serde(rename = "catchup-time")]
+    pub catchup_time: u64,
+}
 
-    /// The number of new MicroAlgos added to the participation stake from rewards at the next round.
-    #[serde(default, rename = "rate")]
-    pub rewards_rate: u64,
+/// Genesis contains the initial state of the network, as defined in
+/// go-algorand/data/bookkeeping/genesis.go.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Genesis {
+    /// The initial accounts and their balances.
+    #[serde(rename = "alloc", default)]
+    pub allocation: Vec<GenesisAllocation>,
+
+    /// A free form comment.
+    #[serde(default)]
+    pub comment: String,
 
-    /// Round represents a protocol round index.
-    #[serde(default, rename = "rnd")]
-    pub round: u64,
+    /// Whether the network runs in the developer mode.
+    #[serde(rename = "devmode", default)]
+    pub dev_mode: bool,
 
-    /// The round at which the RewardsRate will be recalculated.
-    #[serde(default, rename = "rwcalr")]
-    pub rewards_rate_recalc_round: u64,
+    /// The address of the fee sink account.
+    #[serde(rename = "fees")]
+    pub fee_sink: String,
 
-    /// The RewardsPool accepts periodic injections from the FeeSink and continually
-    /// redistributes them to addresses as rewards.
-    #[serde(default, rename = "rwd")]
-    pub rewards_pool: Option<HashDigest>,
+    /// The schema ID, usually `v1`.
+    #[serde(rename = "id")]
+    pub schema_id: String,
 
-    /// Sortition seed.
-    #[serde(rename = "seed", default)]
-    pub sortition_seed: Option<Ed25519Seed>,
+    /// The network name.
+    pub network: String,
 
-    /// TimeStamp in seconds since epoch.
-    #[serde(default, rename = "ts")]
+    /// The initial consensus protocol version.
+    pub proto: String,
+
+    /// The address of the rewards pool account.
+    #[serde(rename = "rwd")]
+    pub rewards_pool: String,
+
+    /// The network creation timestamp.
+    #[serde(default)]
     pub timestamp: i64,
 
-    /// Root of transaction merkle tree using SHA512_256 hash function.
-    /// This commitment is computed based on the PaysetCommit type specified in the block's consensus protocol.
-    #[serde(default, rename = "txn")]
-    pub tx_merke_root_hash: Option<HashDigest>,
+    /// The hash of the genesis, filled in by [Genesis::compute_hash] once fetched.
+    #[serde(skip)]
+    pub hash: HashDigest,
+}
+
+impl Genesis {
+    /// Returns the genesis ID, which transactions use together with the genesis hash.
+    pub fn id(&self) -> String {
+        format!("{}-{}", self.network, self.schema_id)
+    }
+
+    /// Computes the genesis hash - the hash of the canonically encoded genesis.
+    pub fn compute_hash(&self) -> HashDigest {
+        let mut genesis = CanonicalValue::from_obj(self).expect("couldn't encode the genesis");
+
+        // The canonical encoding omits the empty fields of the allocations, but go-algorand
+        // always encodes them, so the allocations are encoded in full and put back.
+        if !self.allocation.is_empty() {
+            let allocation = self
+                .allocation
+                .iter()
+                .map(GenesisAllocation::to_canonical_value)
+                .collect();
+            genesis
+                .insert("alloc", CanonicalValue::Array(allocation))
+                .expect("the genesis isn't encoded as a map");
+        }
 
-    /// Root of transaction vector commitment merkle tree using SHA256 hash function.
-    #[serde(default, rename = "txn256")]
-    pub tx_merke_root_hash256: Option<HashDigest>,
+        genesis.hash(HASH_ID_GENESIS)
+    }
 }
 
-/// TransactionParams contains the parameters that help a client construct a new transaction.
+/// GenesisAllocation is an account present in the genesis.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct TransactionParams {
-    /// Transaction fee in units of micro-Algos per byte.
-    pub fee: u64,
+pub struct GenesisAllocation {
+    /// The account's address.
+    #[serde(rename = "addr")]
+    pub address: String,
 
-    /// Minimum fee.
-    #[serde(rename = "min-fee")]
-    pub min_fee: u64,
+    /// A free form comment, usually the name of the account.
+    #[serde(default)]
+    pub comment: String,
 
-    /// Genesis ID.
-    #[serde(rename = "genesis-id")]
-    pub genesis_id: String,
+    /// The initial account state.
+    pub state: GenesisAccountData,
+}
 
-    /// Genesis hash.
+impl GenesisAllocation {
+    /// Returns the canonical form of the allocation, which keeps all of its fields, even the empty
+    /// ones, as go-algorand encodes them.
+    fn to_canonical_value(&self) -> CanonicalValue {
+        CanonicalValue::Map(vec![
+            ("addr".into(), CanonicalValue::Str(self.address.clone())),
+            ("comment".into(), CanonicalValue::Str(self.comment.clone())),
+            (
+                "state".into(),
+                CanonicalValue::from_obj(&self.state).expect("couldn't encode the account state"),
+            ),
+        ])
+    }
+}
+
+/// GenesisAccountData is the initial state of a genesis account.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GenesisAccountData {
+    /// Total number of micro-Algos in the account.
+    #[serde(rename = "algo", default)]
+    pub amount: u64,
+
+    /// The participation status of the account.
+    #[serde(rename = "onl", default)]
+    pub status: u64,
+
+    /// The selection public key.
     #[serde(
-        rename = "genesis-hash",
-        deserialize_with = "deserialize_hash_in_base64"
+        rename = "sel",
+        default,
+        serialize_with = "serde_bytes::serialize",
+        deserialize_with = "deserialize_bytes_in_base64"
     )]
-    pub genesis_hash: HashDigest,
+    pub selection_pk: Vec<u8>,
 
-    /// The last round seen.
-    #[serde(rename = "last-round")]
-    pub last_round: Round,
+    /// The state proof public key commitment.
+    #[serde(
+        rename = "stprf",
+        default,
+        serialize_with = "serde_bytes::serialize",
+        deserialize_with = "deserialize_bytes_in_base64"
+    )]
+    pub state_proof_pk: Vec<u8>,
 
-    /// The consensus protocol version as of last round.
-    #[serde(rename = "consensus-version")]
-    pub consensus_version: String,
+    /// The root participation public key.
+    #[serde(
+        rename = "vote",
+        default,
+        serialize_with = "serde_bytes::serialize",
+        deserialize_with = "deserialize_bytes_in_base64"
+    )]
+    pub vote_pk: Vec<u8>,
+
+    /// The first round that the participation key is valid.
+    #[serde(rename = "voteFst", default)]
+    pub vote_first: Round,
+
+    /// The dilution for the 2-level participation key.
+    #[serde(rename = "voteKD", default)]
+    pub vote_key_dilution: u64,
+
+    /// The last round that the participation key is valid.
+    #[serde(rename = "voteLst", default)]
+    pub vote_last: Round,
+}
+
+fn deserialize_bytes_in_base64<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    BASE64
+        .decode(String::deserialize(deserializer)?.as_bytes())
+        .map_err(serde::de::Error::custom)
 }
 
 fn deserialize_hash_in_base64<'de, D>(deserializer: D) -> Result<HashDigest, D::Error>
@@ -152,3 +308,63 @@ where
     );
     Ok(HashDigest(hash))
 }
+
+#[cfg(test)]
+mod test {
+    use sha2::Digest;
+
+    use super::*;
+
+    #[test]
+    fn genesis_hash_keeps_empty_allocation_fields() {
+        let genesis: Genesis = serde_json::from_str(
+            r#"{
+                "alloc": [
+                    {"addr": "A", "comment": "pool", "state": {"algo": 5}},
+                    {"addr": "B", "state": {}}
+                ],
+                "fees": "F",
+                "id": "v1",
+                "network": "net",
+                "proto": "p",
+                "rwd": "R"
+            }"#,
+        )
+        .expect("invalid genesis");
+
+        // The empty genesis fields are omitted, but the uncommented allocation keeps its empty
+        // comment and state.
+        let bytes = data_encoding::HEXLOWER
+            .decode(
+                concat!(
+                    // A map with 6 entries.
+                    "86",
+                    // alloc: an array with 2 entries
+                    "a5616c6c6f6392",
+                    // {addr: "A", comment: "pool", state: {algo: 5}}
+                    "83a461646472a141a7636f6d6d656e74a4706f6f6ca5737461746581a4616c676f05",
+                    // {addr: "B", comment: "", state: {}}
+                    "83a461646472a142a7636f6d6d656e74a0a5737461746580",
+                    // fees: "F"
+                    "a466656573a146",
+                    // id: "v1"
+                    "a26964a27631",
+                    // network: "net"
+                    "a76e6574776f726ba36e6574",
+                    // proto: "p"
+                    "a570726f746fa170",
+                    // rwd: "R"
+                    "a3727764a152",
+                )
+                .as_bytes(),
+            )
+            .expect("invalid hex sample");
+
+        let mut data = HASH_ID_GENESIS.to_vec();
+        data.extend(bytes);
+        let mut hash = [0; 32];
+        hash.copy_from_slice(&sha2::Sha512_256::digest(data));
+
+        assert_eq!(genesis.compute_hash(), HashDigest(hash));
+    }
+}
@@ -8,9 +8,15 @@ use ziggurat_core_utils::err_constants::{
 };
 
 use crate::{
-    protocol::codecs::payload::Payload,
+    protocol::{
+        codecs::payload::Payload,
+        handshake::{HandshakeCfg, X_AG_ALGORAND_GENESIS, X_AG_ALGORAND_VERSION},
+    },
     setup::node::Node,
-    tools::{constants::CONNECTION_TIMEOUT, synthetic_node::SyntheticNodeBuilder},
+    tools::{
+        constants::{CONNECTION_TIMEOUT, ERR_NODE_START},
+        synthetic_node::SyntheticNodeBuilder,
+    },
 };
 
 #[tokio::test]
@@ -20,7 +26,7 @@ async fn c001_handshake_when_node_receives_connection() {
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and enable handshaking.
     let synthetic_node = SyntheticNodeBuilder::default()
@@ -42,9 +48,60 @@ async fn c001_handshake_when_node_receives_connection() {
         "synthetic node is not connected to the node"
     );
 
+    // The node should advertise the chain and the protocol version we asked for.
+    let rsp = synthetic_node
+        .handshake_response(net_addr)
+        .expect("missing the handshake response");
+    assert_eq!(rsp.ar_genesis.as_deref(), Some(X_AG_ALGORAND_GENESIS));
+    assert_eq!(rsp.ar_version.as_deref(), Some(X_AG_ALGORAND_VERSION));
+
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+async fn c001_t2_handshake_falls_back_to_a_supported_version() {
+    // ZG-CONFORMANCE-001
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    // Create a synthetic node which tries an unsupported version first.
+    let cfg = HandshakeCfg {
+        version_candidates: vec!["9.9".into(), X_AG_ALGORAND_VERSION.into()],
+        ..Default::default()
+    };
+    let synthetic_node = SyntheticNodeBuilder::default()
+        .with_handshake_configuration(cfg)
+        .build()
+        .await
+        .expect(ERR_SYNTH_BUILD);
+
+    let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
+
+    // Connect to the node and initiate the handshake.
+    synthetic_node
+        .connect(net_addr)
+        .await
+        .expect(ERR_SYNTH_CONNECT);
+
+    assert!(
+        synthetic_node.is_connected(net_addr),
+        "synthetic node is not connected to the node"
+    );
+
+    // The node should agree to the supported candidate.
+    let rsp = synthetic_node
+        .handshake_response(net_addr)
+        .expect("missing the handshake response");
+    assert_eq!(rsp.ar_version.as_deref(), Some(X_AG_ALGORAND_VERSION));
+
+    // Gracefully shut down the nodes.
+    synthetic_node.shut_down().await;
+    node.stop().await.expect(ERR_NODE_STOP);
 }
 
 #[tokio::test]
@@ -68,7 +125,7 @@ async fn c002_handshake_when_node_initiates_connection() {
         .initial_peers([listening_addr])
         .build(target.path())
         .expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     let node_addr = timeout(CONNECTION_TIMEOUT, synthetic_node.wait_for_connection())
         .await
@@ -87,7 +144,54 @@ async fn c002_handshake_when_node_initiates_connection() {
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+async fn c002_t2_handshake_when_node_initiates_multiple_connections() {
+    // ZG-CONFORMANCE-002
+
+    // Create two synthetic nodes listening for the node's connections.
+    let mut synthetic_nodes = Vec::new();
+    let mut listening_addrs = Vec::new();
+    for _ in 0..2 {
+        let synthetic_node = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+
+        let listening_addr = synthetic_node
+            .start_listening()
+            .await
+            .expect("a synthetic node couldn't start listening");
+
+        synthetic_nodes.push(synthetic_node);
+        listening_addrs.push(listening_addr);
+    }
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder()
+        .initial_peers(listening_addrs)
+        .build(target.path())
+        .expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    // The node should connect to all of its initial peers.
+    for synthetic_node in &synthetic_nodes {
+        assert!(
+            synthetic_node
+                .wait_for_connections(1, CONNECTION_TIMEOUT)
+                .await,
+            "the node didn't connect to all of its initial peers"
+        );
+    }
+
+    // Gracefully shut down the nodes.
+    for synthetic_node in &synthetic_nodes {
+        synthetic_node.shut_down().await;
+    }
+    node.stop().await.expect(ERR_NODE_STOP);
 }
 
 const NO_MSG_TIMEOUT: Option<Duration> = Some(Duration::from_secs(5));
@@ -102,7 +206,7 @@ async fn c003_t1_expect_no_messages_before_handshake() {
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and enable handshaking.
     let mut synthetic_node = SyntheticNodeBuilder::default()
@@ -128,7 +232,7 @@ async fn c003_t1_expect_no_messages_before_handshake() {
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
 
 // NOTE: Maybe this test makes no sense because we do get bombarded with the GET_BLOCK requests,
@@ -159,7 +263,7 @@ async fn c003_t2_expect_no_messages_before_handshake() {
         .initial_peers([listening_addr])
         .build(target.path())
         .expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     let expect_any_msg = |_: &Payload| true;
     assert!(
@@ -170,5 +274,5 @@ async fn c003_t2_expect_no_messages_before_handshake() {
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
@@ -1,12 +1,15 @@
 use tempfile::TempDir;
+use tokio::time::Duration;
+use websocket_codec::Opcode;
 use ziggurat_core_utils::err_constants::{
     ERR_NODE_ADDR, ERR_NODE_BUILD, ERR_NODE_STOP, ERR_SYNTH_BUILD, ERR_SYNTH_CONNECT,
     ERR_TEMPDIR_NEW,
 };
 
 use crate::{
-    protocol::codecs::payload::Payload, setup::node::Node,
-    tools::synthetic_node::SyntheticNodeBuilder,
+    protocol::codecs::payload::Payload,
+    setup::node::Node,
+    tools::{constants::ERR_NODE_START, synthetic_node::SyntheticNodeBuilder},
 };
 
 #[tokio::test]
@@ -17,7 +20,7 @@ async fn c008_AGREEMENT_VOTE_expect_after_connect() {
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and enable handshaking.
     let mut synthetic_node = SyntheticNodeBuilder::default()
@@ -42,5 +45,97 @@ async fn c008_AGREEMENT_VOTE_expect_after_connect() {
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c008_t2_AGREEMENT_VOTE_expect_binary_frames() {
+    // ZG-CONFORMANCE-008
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    // Create a synthetic node and enable handshaking.
+    let mut synthetic_node = SyntheticNodeBuilder::default()
+        .build()
+        .await
+        .expect(ERR_SYNTH_BUILD);
+
+    let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
+
+    // Connect to the node and initiate the handshake.
+    synthetic_node
+        .connect(net_addr)
+        .await
+        .expect(ERR_SYNTH_CONNECT);
+
+    // All gossip messages should be delivered in binary frames.
+    let mut votes = 0;
+    while votes < 2 {
+        let (_, msg) = synthetic_node
+            .recv_message_timeout(Duration::from_secs(10))
+            .await
+            .expect("the node stopped gossiping");
+        if matches!(msg.payload, Payload::WsPing(_)) {
+            continue;
+        }
+
+        assert_eq!(msg.opcode, Opcode::Binary, "unexpected frame: {msg:?}");
+        if matches!(msg.payload, Payload::AgreementVote(..)) {
+            votes += 1;
+        }
+    }
+
+    // Gracefully shut down the nodes.
+    synthetic_node.shut_down().await;
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c008_t3_AGREEMENT_VOTE_round_advances() {
+    // ZG-CONFORMANCE-008
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    // Create a synthetic node and enable handshaking.
+    let mut synthetic_node = SyntheticNodeBuilder::default()
+        .build()
+        .await
+        .expect(ERR_SYNTH_BUILD);
+
+    let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
+
+    // Connect to the node and initiate the handshake.
+    synthetic_node
+        .connect(net_addr)
+        .await
+        .expect(ERR_SYNTH_CONNECT);
+
+    let mut first_round = None;
+    let check = |m: &Payload| {
+        if let Payload::AgreementVote(vote) = m {
+            let first_round = *first_round.get_or_insert(vote.round());
+            return vote.round() > first_round;
+        }
+        false
+    };
+
+    // The private network agrees on a new block every few seconds.
+    assert!(
+        synthetic_node
+            .expect_message(check, Some(Duration::from_secs(30)))
+            .await,
+        "the node didn't vote in a later round than {first_round:?}"
+    );
+
+    // Gracefully shut down the nodes.
+    synthetic_node.shut_down().await;
+    node.stop().await.expect(ERR_NODE_STOP);
 }
@@ -5,8 +5,9 @@ use ziggurat_core_utils::err_constants::{
 };
 
 use crate::{
-    protocol::codecs::payload::Payload, setup::node::Node,
-    tools::synthetic_node::SyntheticNodeBuilder,
+    protocol::codecs::payload::Payload,
+    setup::node::Node,
+    tools::{constants::ERR_NODE_START, synthetic_node::SyntheticNodeBuilder},
 };
 
 #[tokio::test]
@@ -17,7 +18,7 @@ async fn c007_PROPOSAL_PAYLOAD_expect_after_connect() {
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and enable handshaking.
     let mut synthetic_node = SyntheticNodeBuilder::default()
@@ -42,5 +43,5 @@ async fn c007_PROPOSAL_PAYLOAD_expect_after_connect() {
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
@@ -7,11 +7,11 @@ use ziggurat_core_utils::err_constants::{
 use crate::{
     protocol::codecs::{algomsg::AlgoMsg, msgpack::HashDigest, payload::Payload},
     setup::node::Node,
-    tests::{
-        conformance::post_handshake::cmd::get_handshaked_synth_node,
-        resistance::post_handshake::enormous_message::get_huge_proposal_payload,
+    tests::resistance::post_handshake::enormous_message::get_huge_proposal_payload,
+    tools::{
+        constants::{ERR_NODE_START, EXPECT_MSG_TIMEOUT},
+        helpers::get_handshaked_synth_node,
     },
-    tools::constants::EXPECT_MSG_TIMEOUT,
 };
 
 #[tokio::test]
@@ -29,7 +29,7 @@ async fn c013_t1_MSG_DIGEST_SKIP_receive_a_msg() {
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create synthetic nodes.
     let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
@@ -64,5 +64,5 @@ async fn c013_t1_MSG_DIGEST_SKIP_receive_a_msg() {
     // Gracefully shut down the nodes.
     synthetic_node_rx.shut_down().await;
     synthetic_node_tx.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
@@ -1,19 +1,29 @@
-use std::time::Duration;
+use std::{collections::HashSet, time::Duration};
 
 use tempfile::TempDir;
 use ziggurat_core_utils::err_constants::{
-    ERR_KMD_BUILD, ERR_KMD_STOP, ERR_NODE_ADDR, ERR_NODE_BUILD, ERR_NODE_STOP, ERR_TEMPDIR_NEW,
+    ERR_KMD_STOP, ERR_NODE_ADDR, ERR_NODE_BUILD, ERR_NODE_STOP, ERR_SYNTH_BUILD, ERR_SYNTH_CONNECT,
+    ERR_TEMPDIR_NEW,
 };
 
 use crate::{
     protocol::codecs::{
-        msgpack::{Payment, Transaction, TransactionType},
+        msgpack::{
+            Address, ApplicationCall, MultisigSignature, SignedTransaction, TransactionType,
+        },
         payload::Payload,
+        tagmsg::{Tag, TAG_LEN},
     },
-    setup::{kmd::Kmd, node::Node},
-    tests::conformance::post_handshake::cmd::{
-        get_handshaked_synth_node, get_pub_key_addr, get_signed_tagged_txn, get_txn_params,
-        get_wallet_token,
+    setup::node::Node,
+    tools::{
+        constants::ERR_NODE_START,
+        helpers::{
+            concat_signed_tagged_txns, get_close_out_txn, get_handshaked_synth_node,
+            get_payment_txn, get_pub_key_addr, get_self_payment_group, get_self_payment_txn,
+            get_signed_tagged_txn, get_signed_txn, get_txn_params, get_wallet_token,
+            rekey_to_new_key, start_kmd, wait_for_group_confirmation, wait_for_txn_confirmation,
+        },
+        synthetic_node::SyntheticNodeBuilder,
     },
 };
 
@@ -25,41 +35,307 @@ async fn c012_TXN_submit_txn_and_expect_to_receive_it() {
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
-    let mut kmd = Kmd::builder()
-        .build(target.path())
-        .await
-        .expect(ERR_KMD_BUILD);
-    kmd.start().await;
+    let mut kmd = start_kmd(target.path()).await;
 
     let wallet_token = get_wallet_token(&mut kmd).await;
 
     // Just send payment to the same address - good enough for the test.
-    let rx_addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
-    let tx_addr = rx_addr;
+    let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+    let txn_params = get_txn_params(&mut node).await;
 
-    let txn_type = TransactionType::Payment(Payment {
-        receiver: rx_addr,
-        amount: 1000,
-        close_remainder_to: None,
-    });
+    let signed_tagged_txn = kmd
+        .make_signed_payment(wallet_token, "".into(), addr, addr, 1000, &txn_params)
+        .await
+        .expect("couldn't make a signed payment");
 
-    let txn_params = get_txn_params(&mut node).await;
+    let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
+
+    // Create synthetic nodes.
+    let synthetic_node_tx = get_handshaked_synth_node(net_addr).await;
+    let mut synthetic_node_rx = get_handshaked_synth_node(net_addr).await;
+
+    // Send a signed transaction.
+    let signed_tagged_txn = Payload::RawBytes(signed_tagged_txn);
+    assert!(synthetic_node_tx
+        .unicast(net_addr, signed_tagged_txn)
+        .is_ok());
+
+    let check = |m: &Payload| matches!(&m, Payload::Transaction(_));
+    assert!(
+        synthetic_node_rx
+            .expect_message(&check, Some(Duration::from_secs(3)))
+            .await,
+        "a broadcasted transaction is missing"
+    );
+
+    // Gracefully shut down the nodes.
+    synthetic_node_rx.shut_down().await;
+    synthetic_node_tx.shut_down().await;
+    kmd.stop().expect(ERR_KMD_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c012_t2_TXN_submit_txn_via_rest_api() {
+    // ZG-CONFORMANCE-012
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    let mut kmd = start_kmd(target.path()).await;
+
+    let wallet_token = get_wallet_token(&mut kmd).await;
+    let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+    let txn = get_self_payment_txn(&mut node, addr, 1000).await;
+    let signed_txn = get_signed_txn(&mut kmd, wallet_token, &txn).await;
+
+    let tx_id = node
+        .rest_client()
+        .expect("couldn't get the REST client")
+        .send_raw_transaction(&signed_txn)
+        .await
+        .expect("couldn't submit the transaction");
+    assert!(!tx_id.is_empty());
+
+    // Gracefully shut down the nodes.
+    kmd.stop().expect(ERR_KMD_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c012_t3_TXN_submitted_txn_gets_confirmed() {
+    // ZG-CONFORMANCE-012
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    let mut kmd = start_kmd(target.path()).await;
+
+    let wallet_token = get_wallet_token(&mut kmd).await;
+    let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+    let txn = get_self_payment_txn(&mut node, addr, 1000).await;
+    let signed_txn = get_signed_txn(&mut kmd, wallet_token, &txn).await;
+
+    let rest_client = node.rest_client().expect("couldn't get the REST client");
+    let tx_id = rest_client
+        .send_raw_transaction(&signed_txn)
+        .await
+        .expect("couldn't submit the transaction");
+
+    // An unknown transaction shouldn't be an error.
+    assert!(rest_client
+        .pending_transaction_information("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA")
+        .await
+        .expect("couldn't get the pending transaction information")
+        .is_none());
+
+    wait_for_txn_confirmation(rest_client, &tx_id, false).await;
+
+    // Gracefully shut down the nodes.
+    kmd.stop().expect(ERR_KMD_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c012_t4_TXN_fee_is_deducted_from_the_balance() {
+    // ZG-CONFORMANCE-012
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    let mut kmd = start_kmd(target.path()).await;
+
+    let wallet_token = get_wallet_token(&mut kmd).await;
+    let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+    let txn = get_self_payment_txn(&mut node, addr, 1000).await;
+    let fee = txn.fee;
+    let signed_txn = get_signed_txn(&mut kmd, wallet_token, &txn).await;
 
-    let txn = Transaction {
-        sender: tx_addr,
-        fee: txn_params.min_fee,
-        first_valid: txn_params.last_round,
-        last_valid: txn_params.last_round + 1000,
-        note: Vec::new(),
-        genesis_id: txn_params.genesis_id,
-        genesis_hash: txn_params.genesis_hash,
-        group: None,
-        lease: None,
-        txn_type,
-        rekey_to: None,
+    let rest_client = node.rest_client().expect("couldn't get the REST client");
+    let balance_before = rest_client
+        .account_information(&addr.encode_string())
+        .await
+        .expect("couldn't get the account information")
+        .amount_without_pending_rewards;
+
+    let tx_id = rest_client
+        .send_raw_transaction(&signed_txn)
+        .await
+        .expect("couldn't submit the transaction");
+    wait_for_txn_confirmation(rest_client, &tx_id, false).await;
+
+    // A self-payment only costs the fee.
+    let balance_after = rest_client
+        .account_information(&addr.encode_string())
+        .await
+        .expect("couldn't get the account information")
+        .amount_without_pending_rewards;
+    assert_eq!(balance_before - fee, balance_after);
+
+    // Gracefully shut down the nodes.
+    kmd.stop().expect(ERR_KMD_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c012_t5_TXN_submit_multisig_txn() {
+    // ZG-CONFORMANCE-012
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    let mut kmd = start_kmd(target.path()).await;
+
+    let wallet_token = get_wallet_token(&mut kmd).await;
+    let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+    // Create a 2-of-3 multisig account.
+    let mut keys = Vec::new();
+    for _ in 0..3 {
+        let key_addr = kmd
+            .generate_key(wallet_token.clone())
+            .await
+            .expect("couldn't generate a key");
+        keys.push(
+            Address::from_string(&key_addr)
+                .expect("invalid address")
+                .public_key(),
+        );
+    }
+    let mut msig = MultisigSignature::new(1, 2, &keys);
+    let msig_addr = msig.address();
+
+    // Fund the multisig account so it can pay the fee.
+    let funding_txn = get_payment_txn(&mut node, addr, msig_addr, 1_000_000).await;
+    let signed_funding_txn = get_signed_txn(&mut kmd, wallet_token.clone(), &funding_txn).await;
+
+    let rest_client = node
+        .rest_client()
+        .expect("couldn't get the REST client")
+        .clone();
+    let tx_id = rest_client
+        .send_raw_transaction(&signed_funding_txn)
+        .await
+        .expect("couldn't submit the funding transaction");
+    wait_for_txn_confirmation(&rest_client, &tx_id, false).await;
+
+    // Sign the payment with two out of three keys.
+    let txn = get_payment_txn(&mut node, msig_addr, addr, 1000).await;
+    for key in &keys[..2] {
+        msig = kmd
+            .sign_multisig_transaction(wallet_token.clone(), "".to_string(), *key, &msig, &txn)
+            .await
+            .expect("couldn't sign the multisig transaction");
+    }
+    assert_eq!(msig.subsigs.iter().filter(|s| s.sig.is_some()).count(), 2);
+
+    let signed_txn = SignedTransaction {
+        sig: None,
+        multisig: Some(msig),
+        auth_addr: None,
+        transaction: txn,
     };
+    let mut signed_tagged_txn = Tag::Txn.get_tag_str().as_bytes().to_vec();
+    signed_tagged_txn.extend(
+        rmp_serde::to_vec_named(&signed_txn).expect("couldn't encode the signed transaction"),
+    );
+
+    // Broadcast the multisig transaction over the gossip connection.
+    let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
+    let synthetic_node = get_handshaked_synth_node(net_addr).await;
+    assert!(synthetic_node
+        .unicast(net_addr, Payload::RawBytes(signed_tagged_txn))
+        .is_ok());
+
+    let tx_id = format!("{:?}", signed_txn.transaction.id());
+    wait_for_txn_confirmation(&rest_client, &tx_id, true).await;
+
+    // Gracefully shut down the nodes.
+    synthetic_node.shut_down().await;
+    kmd.stop().expect(ERR_KMD_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c012_t6_TXN_submit_atomic_txn_group() {
+    // ZG-CONFORMANCE-012
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    let mut kmd = start_kmd(target.path()).await;
+
+    let wallet_token = get_wallet_token(&mut kmd).await;
+    let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+    let txns = get_self_payment_group(&mut node, addr, &[1000, 2000]).await;
+
+    // A group is submitted as concatenated signed transactions.
+    let mut signed_group = Vec::new();
+    for txn in &txns {
+        signed_group.extend(get_signed_txn(&mut kmd, wallet_token.clone(), txn).await);
+    }
+
+    let rest_client = node.rest_client().expect("couldn't get the REST client");
+    let tx_id = rest_client
+        .send_raw_transaction(&signed_group)
+        .await
+        .expect("couldn't submit the transaction group");
+    assert_eq!(tx_id, format!("{:?}", txns[0].id()));
+
+    // Both transactions are confirmed in the same round or neither is.
+    wait_for_group_confirmation(rest_client, &txns, false).await;
+
+    // Gracefully shut down the nodes.
+    kmd.stop().expect(ERR_KMD_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c012_t7_TXN_submit_app_create_txn_and_expect_to_receive_it() {
+    // ZG-CONFORMANCE-012
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    let mut kmd = start_kmd(target.path()).await;
+
+    let wallet_token = get_wallet_token(&mut kmd).await;
+    let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+    // An approval program which always succeeds: `#pragma version 2; int 1`.
+    let program = vec![0x02, 0x20, 0x01, 0x01, 0x22];
+
+    // Reuse the payment template and turn it into an application create transaction.
+    let mut txn = get_self_payment_txn(&mut node, addr, 0).await;
+    txn.txn_type = TransactionType::ApplicationCall(ApplicationCall {
+        approval_program: program.clone(),
+        clear_state_program: program,
+        ..Default::default()
+    });
 
     let signed_tagged_txn = get_signed_tagged_txn(&mut kmd, wallet_token, &txn).await;
 
@@ -70,9 +346,115 @@ async fn c012_TXN_submit_txn_and_expect_to_receive_it() {
     let mut synthetic_node_rx = get_handshaked_synth_node(net_addr).await;
 
     // Send a signed transaction.
-    let signed_tagged_txn = Payload::RawBytes(signed_tagged_txn);
     assert!(synthetic_node_tx
-        .unicast(net_addr, signed_tagged_txn)
+        .unicast(net_addr, Payload::RawBytes(signed_tagged_txn))
+        .is_ok());
+
+    let check = |m: &Payload| matches!(&m, Payload::Transaction(stxn) if stxn.transaction == txn);
+    assert!(
+        synthetic_node_rx
+            .expect_message(&check, Some(Duration::from_secs(3)))
+            .await,
+        "a broadcasted application call transaction is missing"
+    );
+
+    // Gracefully shut down the nodes.
+    synthetic_node_rx.shut_down().await;
+    synthetic_node_tx.shut_down().await;
+    kmd.stop().expect(ERR_KMD_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c012_t8_TXN_broadcasted_txn_retains_the_signature() {
+    // ZG-CONFORMANCE-012
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    let mut kmd = start_kmd(target.path()).await;
+
+    let wallet_token = get_wallet_token(&mut kmd).await;
+    let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+    let txn = get_self_payment_txn(&mut node, addr, 1000).await;
+    let signed_tagged_txn = get_signed_tagged_txn(&mut kmd, wallet_token, &txn).await;
+
+    let signed_txn: SignedTransaction = rmp_serde::from_slice(&signed_tagged_txn[TAG_LEN..])
+        .expect("couldn't decode the signed transaction");
+    let sig = signed_txn.sig.expect("the transaction isn't signed");
+
+    let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
+
+    // Create synthetic nodes.
+    let synthetic_node_tx = get_handshaked_synth_node(net_addr).await;
+    let mut synthetic_node_rx = get_handshaked_synth_node(net_addr).await;
+
+    // Send a signed transaction.
+    assert!(synthetic_node_tx
+        .unicast(net_addr, Payload::RawBytes(signed_tagged_txn))
+        .is_ok());
+
+    // The rebroadcasted transaction should carry the sender's signature.
+    let check = |m: &Payload| {
+        matches!(&m, Payload::Transaction(stxn)
+            if stxn.transaction == txn && stxn.sig == Some(sig) && stxn.multisig.is_none())
+    };
+    assert!(
+        synthetic_node_rx
+            .expect_message(&check, Some(Duration::from_secs(3)))
+            .await,
+        "a broadcasted signed transaction is missing"
+    );
+
+    // Gracefully shut down the nodes.
+    synthetic_node_rx.shut_down().await;
+    synthetic_node_tx.shut_down().await;
+    kmd.stop().expect(ERR_KMD_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c012_t9_TXN_receive_only_subscribed_txns() {
+    // ZG-CONFORMANCE-012
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    let mut kmd = start_kmd(target.path()).await;
+
+    let wallet_token = get_wallet_token(&mut kmd).await;
+    let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+    let txn_params = get_txn_params(&mut node).await;
+
+    let signed_tagged_txn = kmd
+        .make_signed_payment(wallet_token, "".into(), addr, addr, 1000, &txn_params)
+        .await
+        .expect("couldn't make a signed payment");
+
+    let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
+
+    // Create synthetic nodes, the receiving one is only subscribed to transactions.
+    let synthetic_node_tx = get_handshaked_synth_node(net_addr).await;
+    let mut synthetic_node_rx = SyntheticNodeBuilder::default()
+        .subscribe_tags(HashSet::from([Tag::Txn]))
+        .build()
+        .await
+        .expect(ERR_SYNTH_BUILD);
+    synthetic_node_rx
+        .connect(net_addr)
+        .await
+        .expect(ERR_SYNTH_CONNECT);
+
+    // Send a signed transaction.
+    assert!(synthetic_node_tx
+        .unicast(net_addr, Payload::RawBytes(signed_tagged_txn))
         .is_ok());
 
     let check = |m: &Payload| matches!(&m, Payload::Transaction(_));
@@ -83,9 +465,210 @@ async fn c012_TXN_submit_txn_and_expect_to_receive_it() {
         "a broadcasted transaction is missing"
     );
 
+    // Proposals are broadcast every round, so they would arrive within the timeout if not filtered.
+    let check = |m: &Payload| matches!(&m, Payload::ProposalPayload(_));
+    assert!(
+        !synthetic_node_rx
+            .expect_message(&check, Some(Duration::from_secs(5)))
+            .await,
+        "received a proposal payload without subscribing to it"
+    );
+
     // Gracefully shut down the nodes.
     synthetic_node_rx.shut_down().await;
     synthetic_node_tx.shut_down().await;
     kmd.stop().expect(ERR_KMD_STOP);
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c012_t10_TXN_confirmed_txn_is_in_the_block() {
+    // ZG-CONFORMANCE-012
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    let mut kmd = start_kmd(target.path()).await;
+
+    let wallet_token = get_wallet_token(&mut kmd).await;
+    let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+    let txn = get_self_payment_txn(&mut node, addr, 1000).await;
+    let signed_txn = get_signed_txn(&mut kmd, wallet_token, &txn).await;
+
+    let rest_client = node.rest_client().expect("couldn't get the REST client");
+    let tx_id = rest_client
+        .send_raw_transaction(&signed_txn)
+        .await
+        .expect("couldn't submit the transaction");
+    let round = wait_for_txn_confirmation(rest_client, &tx_id, false).await;
+
+    let block = rest_client
+        .wait_for_block(round)
+        .await
+        .expect("couldn't get the block")
+        .block;
+    assert_eq!(block.round, round);
+
+    // The genesis fields are restored, so the ID matches the submitted transaction.
+    assert!(
+        block
+            .transactions()
+            .iter()
+            .any(|stxn| format!("{:?}", stxn.transaction.id()) == tx_id),
+        "the confirmed transaction is missing from the block"
+    );
+
+    // Gracefully shut down the nodes.
+    kmd.stop().expect(ERR_KMD_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c012_t11_TXN_close_out_an_account() {
+    // ZG-CONFORMANCE-012
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    let mut kmd = start_kmd(target.path()).await;
+
+    let wallet_token = get_wallet_token(&mut kmd).await;
+    let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+    // Create a throwaway account and fund it from the genesis account.
+    let throwaway_addr = kmd
+        .generate_key(wallet_token.clone())
+        .await
+        .expect("couldn't generate a key");
+    let throwaway_addr = Address::from_string(&throwaway_addr).expect("invalid address");
+
+    let funding_txn = get_payment_txn(&mut node, addr, throwaway_addr, 1_000_000).await;
+    let signed_funding_txn = get_signed_txn(&mut kmd, wallet_token.clone(), &funding_txn).await;
+
+    let rest_client = node
+        .rest_client()
+        .expect("couldn't get the REST client")
+        .clone();
+    let tx_id = rest_client
+        .send_raw_transaction(&signed_funding_txn)
+        .await
+        .expect("couldn't submit the funding transaction");
+    wait_for_txn_confirmation(&rest_client, &tx_id, false).await;
+
+    // Close the throwaway account out to the genesis account.
+    let txn = get_close_out_txn(&mut node, throwaway_addr, addr).await;
+    let signed_txn = get_signed_txn(&mut kmd, wallet_token, &txn).await;
+
+    let tx_id = rest_client
+        .send_raw_transaction(&signed_txn)
+        .await
+        .expect("couldn't submit the close out transaction");
+    wait_for_txn_confirmation(&rest_client, &tx_id, false).await;
+
+    let balance = rest_client
+        .account_information(&throwaway_addr.encode_string())
+        .await
+        .expect("couldn't get the account information")
+        .amount;
+    assert_eq!(balance, 0, "the closed account isn't empty");
+
+    // Gracefully shut down the nodes.
+    kmd.stop().expect(ERR_KMD_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c012_t12_TXN_rekeyed_account_requires_the_auth_key() {
+    // ZG-CONFORMANCE-012
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    let mut kmd = start_kmd(target.path()).await;
+
+    let wallet_token = get_wallet_token(&mut kmd).await;
+    let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+    let auth_addr = rekey_to_new_key(&mut node, &mut kmd, wallet_token.clone(), addr).await;
+
+    let txn = get_self_payment_txn(&mut node, addr, 1000).await;
+    let rest_client = node.rest_client().expect("couldn't get the REST client");
+
+    // The old key no longer authorizes the account's transactions.
+    let old_key_txn = get_signed_txn(&mut kmd, wallet_token.clone(), &txn).await;
+    assert!(
+        rest_client
+            .send_raw_transaction(&old_key_txn)
+            .await
+            .is_err(),
+        "the node accepted a transaction signed by the old key"
+    );
+
+    let new_key_txn = kmd
+        .sign_transaction_with_key(wallet_token, "".into(), auth_addr.public_key(), &txn)
+        .await
+        .expect("couldn't sign the transaction")
+        .signed_transaction;
+    let decoded: SignedTransaction =
+        rmp_serde::from_slice(&new_key_txn).expect("couldn't decode the signed transaction");
+    assert_eq!(decoded.auth_addr, Some(auth_addr));
+
+    let tx_id = rest_client
+        .send_raw_transaction(&new_key_txn)
+        .await
+        .expect("the node rejected a transaction signed by the auth key");
+    wait_for_txn_confirmation(rest_client, &tx_id, false).await;
+
+    // Gracefully shut down the nodes.
+    kmd.stop().expect(ERR_KMD_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c012_t13_TXN_submit_atomic_txn_group_over_gossip() {
+    // ZG-CONFORMANCE-012
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    let mut kmd = start_kmd(target.path()).await;
+
+    let wallet_token = get_wallet_token(&mut kmd).await;
+    let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+
+    let txns = get_self_payment_group(&mut node, addr, &[1000, 2000]).await;
+
+    let mut signed_tagged_txns = Vec::new();
+    for txn in &txns {
+        signed_tagged_txns.push(get_signed_tagged_txn(&mut kmd, wallet_token.clone(), txn).await);
+    }
+
+    // The whole group is sent as a single message.
+    let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
+    let synthetic_node = get_handshaked_synth_node(net_addr).await;
+    assert!(synthetic_node
+        .unicast(net_addr, concat_signed_tagged_txns(&signed_tagged_txns))
+        .is_ok());
+
+    // Both transactions are confirmed in the same round.
+    let rest_client = node.rest_client().expect("couldn't get the REST client");
+    wait_for_group_confirmation(rest_client, &txns, true).await;
+
+    // Gracefully shut down the nodes.
+    synthetic_node.shut_down().await;
+    kmd.stop().expect(ERR_KMD_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
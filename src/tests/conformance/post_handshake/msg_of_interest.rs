@@ -10,7 +10,7 @@ use ziggurat_core_utils::err_constants::{
 use crate::{
     protocol::codecs::{payload::Payload, tagmsg::Tag, topic::MsgOfInterest},
     setup::node::Node,
-    tools::synthetic_node::SyntheticNodeBuilder,
+    tools::{constants::ERR_NODE_START, synthetic_node::SyntheticNodeBuilder},
 };
 
 // All MsgOfInterest messages should be received immediately after the connetion is established.
@@ -24,7 +24,7 @@ async fn c005_t1_MSG_OF_INTEREST_expect_after_connect() {
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and enable handshaking.
     let mut synthetic_node = SyntheticNodeBuilder::default()
@@ -45,7 +45,7 @@ async fn c005_t1_MSG_OF_INTEREST_expect_after_connect() {
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
 
 #[tokio::test]
@@ -56,7 +56,7 @@ async fn c005_t2_MSG_OF_INTEREST_send_after_connect() {
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and enable handshaking.
     let mut synthetic_node = SyntheticNodeBuilder::default()
@@ -93,11 +93,9 @@ async fn c005_t2_MSG_OF_INTEREST_send_after_connect() {
 
     // Clear any remaining received messages in the inbound queue
     // before the node processes our MsgOfInterest message.
-    while synthetic_node
-        .recv_message_timeout(Duration::from_millis(10))
-        .await
-        .is_ok()
-    {}
+    synthetic_node
+        .drain_inbound(Duration::from_millis(10))
+        .await;
 
     // Wait for any message from the 'tags' list above.
     let expect_any_msg = |_: &Payload| true;
@@ -105,7 +103,7 @@ async fn c005_t2_MSG_OF_INTEREST_send_after_connect() {
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
 
 #[tokio::test]
@@ -116,7 +114,7 @@ async fn c006_MSG_OF_INTEREST_expect_no_messages_after_sending_empty_tag_list()
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and enable handshaking.
     let mut synthetic_node = SyntheticNodeBuilder::default()
@@ -136,17 +134,15 @@ async fn c006_MSG_OF_INTEREST_expect_no_messages_after_sending_empty_tag_list()
     assert!(synthetic_node.expect_message(&check, MSG_TIMEOUT).await);
 
     // Send a MsgOfInterest message with no tags enabled.
-    let no_tags = HashSet::new();
-    let message = Payload::MsgOfInterest(MsgOfInterest { tags: no_tags });
+    let no_tags = MsgOfInterest::new([]).expect("couldn't create the MsgOfInterest message");
+    let message = Payload::MsgOfInterest(no_tags);
     assert!(synthetic_node.unicast(net_addr, message).is_ok());
 
     // Clear any remaining received messages in the inbound queue
     // before the node processes our MsgOfInterest message.
-    while synthetic_node
-        .recv_message_timeout(Duration::from_millis(10))
-        .await
-        .is_ok()
-    {}
+    synthetic_node
+        .drain_inbound(Duration::from_millis(10))
+        .await;
 
     // Verify the node won't send us any messages afterwards.
     let expect_any_msg = |_: &Payload| true;
@@ -159,5 +155,5 @@ async fn c006_MSG_OF_INTEREST_expect_no_messages_after_sending_empty_tag_list()
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
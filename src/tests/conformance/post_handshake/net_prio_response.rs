@@ -1,20 +1,22 @@
 use data_encoding::BASE64;
 use tempfile::TempDir;
-use tokio::time::Duration;
+use tokio::time::{sleep, Duration};
 use ziggurat_core_utils::err_constants::{
-    ERR_NODE_BUILD, ERR_NODE_STOP, ERR_SYNTH_BUILD, ERR_SYNTH_START_LISTENING, ERR_TEMPDIR_NEW,
+    ERR_NODE_ADDR, ERR_NODE_BUILD, ERR_NODE_STOP, ERR_SYNTH_BUILD, ERR_SYNTH_CONNECT,
+    ERR_SYNTH_START_LISTENING, ERR_TEMPDIR_NEW,
 };
 
 use crate::{
     protocol::{
         codecs::{
-            msgpack::{NetPrioResponse, Response},
+            msgpack::{Address, NetPrioResponse, Response, Round},
             payload::Payload,
         },
         handshake::HandshakeCfg,
+        net_prio::{NetPrioSigner, OneTimeSigner, DEFAULT_KEY_DILUTION},
     },
     setup::node::Node,
-    tools::synthetic_node::SyntheticNodeBuilder,
+    tools::{constants::ERR_NODE_START, synthetic_node::SyntheticNodeBuilder},
 };
 
 const MSG_TIMEOUT: Option<Duration> = Some(Duration::from_secs(3));
@@ -50,7 +52,7 @@ async fn c011_t1_NET_PRIO_RESPONSE_expect_rsp_from_the_node() {
         .initial_peers([listening_addr])
         .build(target.path())
         .expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     let check = |m: &Payload| {
         matches!(&m, Payload::NetPrioResponse(NetPrioResponse{response: Response { nonce }, ..})
@@ -60,7 +62,7 @@ async fn c011_t1_NET_PRIO_RESPONSE_expect_rsp_from_the_node() {
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
 
 #[tokio::test]
@@ -88,12 +90,62 @@ async fn c011_t2_NET_PRIO_RESPONSE_no_rsp_if_challenge_not_sent() {
         .initial_peers([listening_addr])
         .build(target.path())
         .expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     let check = |m: &Payload| matches!(&m, Payload::NetPrioResponse(..));
     assert!(!synthetic_node.expect_message(&check, MSG_TIMEOUT).await);
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c011_t3_NET_PRIO_RESPONSE_connection_survives_signed_rsp() {
+    // ZG-CONFORMANCE-011
+    //
+    // The node sends a challenge when answering our handshake request.
+    // The synthetic node answers it with a signed NetPrioResponse message.
+    //
+    // The node doesn't expose whether the response was accepted: a failed verification is only
+    // logged and the peer is kept either way. This only checks that the node keeps the
+    // connection after receiving the message.
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    let cfg = HandshakeCfg {
+        prio_signer: Some(NetPrioSigner {
+            signer: OneTimeSigner::generate(DEFAULT_KEY_DILUTION),
+            sender: Address::new([1u8; 32]),
+            round: Round(1),
+        }),
+        ..Default::default()
+    };
+
+    // Create a synthetic node and enable handshaking.
+    let synthetic_node = SyntheticNodeBuilder::default()
+        .with_handshake_configuration(cfg)
+        .build()
+        .await
+        .expect(ERR_SYNTH_BUILD);
+
+    let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
+
+    // Connect to the node and initiate the handshake.
+    synthetic_node
+        .connect(net_addr)
+        .await
+        .expect(ERR_SYNTH_CONNECT);
+
+    // The account isn't registered, so the response gives no priority either way.
+    sleep(MSG_TIMEOUT.unwrap()).await;
+    assert!(synthetic_node.is_connected(net_addr));
+
+    // Gracefully shut down the nodes.
+    synthetic_node.shut_down().await;
+    node.stop().await.expect(ERR_NODE_STOP);
 }
@@ -6,13 +6,67 @@ use ziggurat_core_utils::err_constants::{
 
 use crate::{
     protocol::codecs::{
+        msgpack::Round,
         payload::Payload,
-        topic::{TopicMsgResp, UniEnsBlockReq, UniEnsBlockReqType},
+        topic::{TopicMsgResp, UniCatchupReq, UniEnsBlockReq, UniEnsBlockReqType},
     },
     setup::node::Node,
-    tools::synthetic_node::SyntheticNodeBuilder,
+    tools::{constants::ERR_NODE_START, synthetic_node::SyntheticNodeBuilder},
 };
 
+/// Requests the data type for the first rounds and asserts that the response to each request
+/// passes the `check` for its round.
+async fn request_block_data_type(
+    data_type: UniEnsBlockReqType,
+    check: impl Fn(Round, &Payload) -> bool,
+) {
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    // Create a synthetic node and enable handshaking.
+    let mut synthetic_node = SyntheticNodeBuilder::default()
+        .build()
+        .await
+        .expect(ERR_SYNTH_BUILD);
+
+    let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
+
+    // Connect to the node and initiate the handshake.
+    synthetic_node
+        .connect(net_addr)
+        .await
+        .expect(ERR_SYNTH_CONNECT);
+
+    for round in (0..4).map(Round) {
+        let message = Payload::UniEnsBlockReq(UniEnsBlockReq {
+            data_type,
+            round_key: round,
+            nonce: round.0,
+            max_blocks: None,
+        });
+        assert!(synthetic_node.unicast(net_addr, message).is_ok());
+
+        assert!(
+            synthetic_node
+                .expect_message(|m| check(round, m), None)
+                .await,
+            "the UniEnsBlockRsp response is missing"
+        );
+    }
+
+    // Gracefully shut down the nodes.
+    synthetic_node.shut_down().await;
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+/// Checks for the error response to the data types the node doesn't support.
+fn is_unsupported_data_type_rsp(_round: Round, m: &Payload) -> bool {
+    matches!(&m, Payload::TopicMsgResp(TopicMsgResp::ErrorRsp(rsp))
+             if rsp.error.as_str() == "requested data type is unsupported")
+}
+
 #[tokio::test]
 #[allow(non_snake_case)]
 async fn c004_V1_BLOCK_ROUND_get_block() {
@@ -21,7 +75,7 @@ async fn c004_V1_BLOCK_ROUND_get_block() {
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and enable handshaking.
     let synthetic_node = SyntheticNodeBuilder::default()
@@ -39,7 +93,7 @@ async fn c004_V1_BLOCK_ROUND_get_block() {
 
     let rest_client = node.rest_client().expect("couldn't get the rest client");
 
-    for round in 0..4 {
+    for round in (0..4).map(Round) {
         let block_cert = rest_client
             .wait_for_block(round)
             .await
@@ -52,7 +106,7 @@ async fn c004_V1_BLOCK_ROUND_get_block() {
             "genesis hash not found"
         );
 
-        if round == 0 {
+        if round == Round(0) {
             assert!(
                 block_cert.block.prevous_block_hash.is_none(),
                 "previous block hash shouldn't be found for the first round"
@@ -67,7 +121,7 @@ async fn c004_V1_BLOCK_ROUND_get_block() {
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
 
 #[tokio::test]
@@ -78,7 +132,7 @@ async fn c010_t1_UNI_ENS_BLOCK_REQ_get_block_and_cert() {
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and enable handshaking.
     let mut synthetic_node = SyntheticNodeBuilder::default()
@@ -94,18 +148,19 @@ async fn c010_t1_UNI_ENS_BLOCK_REQ_get_block_and_cert() {
         .await
         .expect(ERR_SYNTH_CONNECT);
 
-    for round in 0..4 {
+    for round in (0..4).map(Round) {
         let message = Payload::UniEnsBlockReq(UniEnsBlockReq {
             data_type: UniEnsBlockReqType::BlockAndCert,
             round_key: round,
-            nonce: round,
+            nonce: round.0,
+            max_blocks: None,
         });
         assert!(synthetic_node.unicast(net_addr, message).is_ok());
 
         // Expect a UniEnsBlockRsp response with a block with the same round and also a certificate.
         let check = |m: &Payload| {
             matches!(&m, Payload::TopicMsgResp(TopicMsgResp::UniEnsBlockRsp(rsp))
-                     if rsp.block.is_some() && rsp.block.as_ref().unwrap().round == round && rsp.cert.is_some())
+                     if rsp.block_round() == Some(round) && rsp.has_cert())
         };
         assert!(
             synthetic_node.expect_message(&check, None).await,
@@ -115,18 +170,44 @@ async fn c010_t1_UNI_ENS_BLOCK_REQ_get_block_and_cert() {
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
 
+#[cfg_attr(
+    feature = "block_data_types",
+    ignore = "the 'block_data_types' feature expects the node to support the data type"
+)]
 #[tokio::test]
 #[allow(non_snake_case)]
 async fn c010_t2_UNI_ENS_BLOCK_REQ_get_block_only() {
     // ZG-CONFORMANCE-010
 
+    // The data type is still unsupported by the node, see c010_t8 for the expected response.
+    request_block_data_type(UniEnsBlockReqType::Block, is_unsupported_data_type_rsp).await;
+}
+
+#[cfg_attr(
+    feature = "block_data_types",
+    ignore = "the 'block_data_types' feature expects the node to support the data type"
+)]
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c010_t3_UNI_ENS_BLOCK_REQ_get_cert_only() {
+    // ZG-CONFORMANCE-010
+
+    // The data type is still unsupported by the node, see c010_t9 for the expected response.
+    request_block_data_type(UniEnsBlockReqType::Cert, is_unsupported_data_type_rsp).await;
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c010_t4_UNI_ENS_BLOCK_REQ_cannot_get_non_existent_block() {
+    // ZG-CONFORMANCE-010
+
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and enable handshaking.
     let mut synthetic_node = SyntheticNodeBuilder::default()
@@ -142,46 +223,37 @@ async fn c010_t2_UNI_ENS_BLOCK_REQ_get_block_only() {
         .await
         .expect(ERR_SYNTH_CONNECT);
 
-    for round in 0..4 {
-        let message = Payload::UniEnsBlockReq(UniEnsBlockReq {
-            data_type: UniEnsBlockReqType::Block,
-            round_key: round,
-            nonce: round,
-        });
-        assert!(synthetic_node.unicast(net_addr, message).is_ok());
-
-        // TODO: Still unsupported, check with the Algorand team.
-        //// Expect a UniEnsBlockRsp response with only a block with the same round, no certificate.
-        //let check = |m: &Payload| {
-        //    matches!(&m, Payload::TopicMsgResp(TopicMsgResp::UniEnsBlockRsp(rsp))
-        //             if rsp.block.is_some() && rsp.block.as_ref().unwrap().round == round && rsp.cert.is_none())
-        //};
+    let message = Payload::UniEnsBlockReq(UniEnsBlockReq {
+        data_type: UniEnsBlockReqType::BlockAndCert,
+        round_key: Round(9999),
+        nonce: 0,
+        max_blocks: None,
+    });
+    assert!(synthetic_node.unicast(net_addr, message).is_ok());
 
-        // Alternative check to ensure it's unsupported :-)
-        let check = |m: &Payload| {
-            matches!(&m, Payload::TopicMsgResp(TopicMsgResp::ErrorRsp(rsp))
-                     if rsp.error.as_str() == "requested data type is unsupported")
-        };
-        assert!(
-            synthetic_node.expect_message(&check, None).await,
-            "the UniEnsBlockRsp response is missing"
-        );
-    }
+    let check = |m: &Payload| {
+        matches!(&m, Payload::TopicMsgResp(TopicMsgResp::ErrorRsp(rsp))
+                 if rsp.error.as_str() == "requested block is not available")
+    };
+    assert!(
+        synthetic_node.expect_message(&check, None).await,
+        "the UniEnsBlockRsp response is missing"
+    );
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
 
 #[tokio::test]
 #[allow(non_snake_case)]
-async fn c010_t3_UNI_ENS_BLOCK_REQ_get_cert_only() {
+async fn c010_t5_UNI_CATCHUP_REQ_get_block_and_cert() {
     // ZG-CONFORMANCE-010
 
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and enable handshaking.
     let mut synthetic_node = SyntheticNodeBuilder::default()
@@ -197,46 +269,38 @@ async fn c010_t3_UNI_ENS_BLOCK_REQ_get_cert_only() {
         .await
         .expect(ERR_SYNTH_CONNECT);
 
-    for round in 0..4 {
-        let message = Payload::UniEnsBlockReq(UniEnsBlockReq {
-            data_type: UniEnsBlockReqType::Cert,
+    for round in (0..4).map(Round) {
+        let message = Payload::UniCatchupReq(UniCatchupReq {
             round_key: round,
-            nonce: round,
+            nonce: round.0,
         });
         assert!(synthetic_node.unicast(net_addr, message).is_ok());
 
-        // TODO: Still unsupported, check with the Algorand team.
-        //// Expect a UniEnsBlockRsp response with only a certificate, no block.
-        //let check = |m: &Payload| {
-        //    matches!(&m, Payload::TopicMsgResp(TopicMsgResp::UniEnsBlockRsp(rsp))
-        //             if rsp.block.is_none() && rsp.cert.is_some())
-        //};
-
-        // Alternative check to ensure it's unsupported :-)
+        // The catchup response has the same format as the UniEnsBlockRsp response.
         let check = |m: &Payload| {
-            matches!(&m, Payload::TopicMsgResp(TopicMsgResp::ErrorRsp(rsp))
-                     if rsp.error.as_str() == "requested data type is unsupported")
+            matches!(&m, Payload::TopicMsgResp(TopicMsgResp::UniEnsBlockRsp(rsp))
+                     if rsp.block_round() == Some(round) && rsp.has_cert())
         };
         assert!(
             synthetic_node.expect_message(&check, None).await,
-            "the UniEnsBlockRsp response is missing"
+            "the UniCatchupReq response is missing"
         );
     }
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
 
 #[tokio::test]
 #[allow(non_snake_case)]
-async fn c010_t4_UNI_ENS_BLOCK_REQ_cannot_get_non_existent_block() {
+async fn c010_t6_UNI_ENS_BLOCK_REQ_ignore_unknown_max_blocks_topic() {
     // ZG-CONFORMANCE-010
 
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and enable handshaking.
     let mut synthetic_node = SyntheticNodeBuilder::default()
@@ -254,21 +318,133 @@ async fn c010_t4_UNI_ENS_BLOCK_REQ_cannot_get_non_existent_block() {
 
     let message = Payload::UniEnsBlockReq(UniEnsBlockReq {
         data_type: UniEnsBlockReqType::BlockAndCert,
-        round_key: 9999,
+        round_key: Round(0),
         nonce: 0,
+        max_blocks: Some(4),
     });
     assert!(synthetic_node.unicast(net_addr, message).is_ok());
 
+    // The node doesn't recognise the key, so it ignores it and returns only the requested block.
+    let mut rsp = None;
     let check = |m: &Payload| {
-        matches!(&m, Payload::TopicMsgResp(TopicMsgResp::ErrorRsp(rsp))
-                 if rsp.error.as_str() == "requested block is not available")
+        if let Payload::TopicMsgResp(topic_rsp) = m {
+            rsp = Some(topic_rsp.clone());
+        }
+        rsp.is_some()
     };
     assert!(
-        synthetic_node.expect_message(&check, None).await,
-        "the UniEnsBlockRsp response is missing"
+        synthetic_node.expect_message(check, None).await,
+        "the response to the ranged request is missing"
     );
 
+    match rsp.expect("the response is missing") {
+        TopicMsgResp::UniEnsBlockRsp(rsp) => {
+            assert_eq!(rsp.block_round(), Some(Round(0)), "invalid round");
+            assert!(rsp.has_cert(), "the certificate is missing");
+        }
+        TopicMsgResp::ErrorRsp(rsp) => {
+            panic!("the node rejected the unknown topic: {}", rsp.error)
+        }
+    }
+
+    // The node shouldn't drop the connection.
+    assert!(synthetic_node.is_connected(net_addr));
+
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c010_t7_UNI_ENS_BLOCK_REQ_request_a_block() {
+    // ZG-CONFORMANCE-010
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    // Create a synthetic node and enable handshaking.
+    let mut synthetic_node = SyntheticNodeBuilder::default()
+        .build()
+        .await
+        .expect(ERR_SYNTH_BUILD);
+
+    let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
+
+    // Connect to the node and initiate the handshake.
+    synthetic_node
+        .connect(net_addr)
+        .await
+        .expect(ERR_SYNTH_CONNECT);
+
+    for round in (0..4).map(Round) {
+        let message = Payload::UniEnsBlockReq(UniEnsBlockReq {
+            data_type: UniEnsBlockReqType::BlockAndCert,
+            round_key: round,
+            nonce: round.0,
+            max_blocks: None,
+        });
+        let is_block_rsp = |m: &Payload| {
+            matches!(m, Payload::TopicMsgResp(TopicMsgResp::UniEnsBlockRsp(rsp))
+                     if rsp.block.is_some())
+        };
+
+        let rsp = synthetic_node
+            .request(net_addr, message, is_block_rsp, None)
+            .await
+            .expect("the UniEnsBlockRsp response is missing");
+
+        match rsp.payload {
+            Payload::TopicMsgResp(TopicMsgResp::UniEnsBlockRsp(rsp)) => {
+                assert_eq!(rsp.block_round(), Some(round), "invalid round")
+            }
+            _ => unreachable!("the response is checked to be a UniEnsBlockRsp"),
+        }
+    }
+
+    // Gracefully shut down the nodes.
+    synthetic_node.shut_down().await;
+    node.stop().await.expect(ERR_NODE_STOP);
+}
+
+// The node doesn't support the block-only requests yet. Once it does, enable the 'block_data_types'
+// feature, which runs this test instead of c010_t2:
+//    cargo test --features block_data_types
+#[cfg_attr(
+    not(feature = "block_data_types"),
+    ignore = "run this test with the 'block_data_types' feature enabled"
+)]
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c010_t8_UNI_ENS_BLOCK_REQ_get_block_only_when_supported() {
+    // ZG-CONFORMANCE-010
+
+    // Expect a UniEnsBlockRsp response with only a block with the same round, no certificate.
+    request_block_data_type(UniEnsBlockReqType::Block, |round, m| {
+        matches!(&m, Payload::TopicMsgResp(TopicMsgResp::UniEnsBlockRsp(rsp))
+                 if rsp.block_round() == Some(round) && !rsp.has_cert())
+    })
+    .await;
+}
+
+// The node doesn't support the certificate-only requests yet. Once it does, enable the 'block_data_types'
+// feature, which runs this test instead of c010_t3:
+//    cargo test --features block_data_types
+#[cfg_attr(
+    not(feature = "block_data_types"),
+    ignore = "run this test with the 'block_data_types' feature enabled"
+)]
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn c010_t9_UNI_ENS_BLOCK_REQ_get_cert_only_when_supported() {
+    // ZG-CONFORMANCE-010
+
+    // Expect a UniEnsBlockRsp response with only a certificate, no block.
+    request_block_data_type(UniEnsBlockReqType::Cert, |_round, m| {
+        matches!(&m, Payload::TopicMsgResp(TopicMsgResp::UniEnsBlockRsp(rsp))
+                 if rsp.block_round().is_none() && rsp.has_cert())
+    })
+    .await;
 }
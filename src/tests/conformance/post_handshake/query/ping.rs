@@ -6,12 +6,9 @@ use ziggurat_core_utils::err_constants::{
 };
 
 use crate::{
-    protocol::codecs::{
-        algomsg::AlgoMsg,
-        payload::{Payload, PingData},
-    },
+    protocol::codecs::{algomsg::AlgoMsg, payload::Payload},
     setup::node::Node,
-    tools::synthetic_node::SyntheticNodeBuilder,
+    tools::{constants::ERR_NODE_START, synthetic_node::SyntheticNodeBuilder},
 };
 
 #[tokio::test]
@@ -22,7 +19,7 @@ async fn c009_t1_PING_PING_REPLY_send_req_expect_reply() {
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and enable handshaking.
     let mut synthetic_node = SyntheticNodeBuilder::default()
@@ -38,23 +35,15 @@ async fn c009_t1_PING_PING_REPLY_send_req_expect_reply() {
         .await
         .expect(ERR_SYNTH_CONNECT);
 
-    // Send a Ping with rand_bytes data.
-    let nonce: [u8; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
-    let message = Payload::Ping(PingData { nonce });
-    assert!(synthetic_node.unicast(net_addr, message).is_ok());
-
-    // Expect a PingReply response with the same data.
-    let check =
-        |m: &Payload| matches!(&m, Payload::PingReply(PingData{nonce: data}) if *data == nonce);
-    let duration = Some(Duration::from_secs(3));
+    // Send a Ping with a random nonce and expect a PingReply response with the same nonce.
     assert!(
-        synthetic_node.expect_message(&check, duration).await,
+        synthetic_node.ping(net_addr).await.is_ok(),
         "the PingReply response is missing"
     );
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
 
 #[tokio::test]
@@ -70,7 +59,7 @@ async fn c009_t2_PING_PING_REPLY_wait_for_a_ping_req() {
         .log_to_stdout(true)
         .build(target.path())
         .expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and enable handshaking.
     let mut synthetic_node = SyntheticNodeBuilder::default()
@@ -118,5 +107,5 @@ async fn c009_t2_PING_PING_REPLY_wait_for_a_ping_req() {
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
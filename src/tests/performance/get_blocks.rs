@@ -1,6 +1,5 @@
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    str::FromStr,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -28,7 +27,12 @@ use crate::{
         payload_factory::PayloadFactory,
     },
     setup::node::Node,
-    tools::{ips::ips, synthetic_node::SyntheticNodeBuilder},
+    tests::performance::msg_sizes::{add_msg_size_rows, record_msg_size, MsgSizeTable},
+    tools::{
+        constants::ERR_NODE_START,
+        ips::{bind_socket, ips},
+        synthetic_node::SyntheticNodeBuilder,
+    },
 };
 
 const METRIC_LATENCY: &str = "block_test_latency";
@@ -58,11 +62,12 @@ async fn p001_GET_BLOCKS_latency() {
     let synth_counts = vec![1, 50, 100, 200, 300, 400, 500, 600, 700, 800];
 
     let mut table = LatencyRequestsTable::default();
+    let mut size_table = MsgSizeTable::default();
 
     for synth_count in synth_counts {
         let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
         let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-        node.start().await;
+        node.start().await.expect(ERR_NODE_START);
 
         let node_addr = node.net_addr().expect(ERR_NODE_ADDR);
 
@@ -72,16 +77,9 @@ async fn p001_GET_BLOCKS_latency() {
         for _ in 0..synth_count {
             // If there is address for our thread in the pool we can use it.
             // Otherwise we'll not set bound_addr and use local IP addr (127.0.0.1).
-            let ip = ips.pop().unwrap_or("127.0.0.1".to_string());
-
-            let ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::from_str(&ip).unwrap()), 0);
-            let socket = TcpSocket::new_v4().unwrap();
-
-            // Make sure we can reuse the address and port
-            socket.set_reuseaddr(true).unwrap();
-            socket.set_reuseport(true).unwrap();
+            let ip = ips.pop().unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
 
-            socket.bind(ip).expect(ERR_SOCKET_BIND);
+            let socket = bind_socket(ip).expect(ERR_SOCKET_BIND);
             synth_sockets.push(socket);
         }
 
@@ -117,15 +115,17 @@ async fn p001_GET_BLOCKS_latency() {
                 ));
             }
         }
+        add_msg_size_rows(&mut size_table, &snapshot, synth_count as u16);
 
-        node.stop().expect(ERR_NODE_STOP);
+        node.stop().await.expect(ERR_NODE_STOP);
     }
 
     // Display results table
     println!("\r\n{}", table);
+    println!("\r\n{}", size_table);
 }
 
-const ROUND_KEY: Round = 1;
+const ROUND_KEY: Round = Round(1);
 #[allow(unused_must_use)] // just for result of the timeout
 async fn simulate_peer(node_addr: SocketAddr, socket: TcpSocket, start_barrier: Arc<Barrier>) {
     let mut synth_node = SyntheticNodeBuilder::default()
@@ -144,6 +144,7 @@ async fn simulate_peer(node_addr: SocketAddr, socket: TcpSocket, start_barrier:
             data_type: UniEnsBlockReqType::BlockAndCert,
             round_key: ROUND_KEY,
             nonce: 1,
+            max_blocks: None,
         }),
         None,
     );
@@ -171,8 +172,9 @@ async fn simulate_peer(node_addr: SocketAddr, socket: TcpSocket, start_barrier:
         timeout(RESPONSE_TIMEOUT, async {
             loop {
                 let m = synth_node.recv_message().await;
+                record_msg_size(&m.1);
                 if matches!(&m.1, AlgoMsg { payload: Payload::TopicMsgResp(TopicMsgResp::UniEnsBlockRsp(rsp)), .. }
-                     if rsp.block.is_some() && rsp.block.as_ref().unwrap().round == ROUND_KEY && rsp.cert.is_some()) {
+                     if rsp.block_round() == Some(ROUND_KEY) && rsp.has_cert()) {
                     metrics::histogram!(METRIC_LATENCY, duration_as_ms(now.elapsed()));
                     break;
                 }
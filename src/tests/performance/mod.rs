@@ -1,2 +1,4 @@
 mod get_blocks;
+mod msg_sizes;
 mod prio_test;
+mod txn_flood;
@@ -0,0 +1,123 @@
+use std::fmt;
+
+use websocket_codec::Opcode;
+use ziggurat_core_metrics::recorder::{TestMetrics, TestMetricsSnapshot};
+
+use crate::protocol::codecs::{
+    algomsg::AlgoMsg,
+    payload::Payload,
+    tagmsg::{Tag, TAG_LEN},
+};
+
+/// The prefix of the per-tag histograms of the received message sizes.
+const METRIC_MSG_SIZE: &str = "received_msg_size";
+
+/// Returns the name of the histogram of the received message sizes for the tag.
+pub fn msg_size_metric(tag: Tag) -> String {
+    format!("{METRIC_MSG_SIZE}_{tag:?}")
+}
+
+/// Records the size of the received message in the histogram of its tag.
+pub fn record_msg_size(msg: &AlgoMsg) {
+    metrics::histogram!(msg_size_metric(msg.tag()), msg.raw.len() as f64);
+}
+
+/// Sizes of the messages with the same tag received in a single test run.
+pub struct MsgSizeStats {
+    /// Number of the synthetic nodes in the run.
+    pub peers: u16,
+    /// Tag of the messages.
+    pub tag: Tag,
+    /// Number of the received messages.
+    pub messages: u64,
+    /// Size of the smallest message in bytes.
+    pub min: u64,
+    /// Median message size in bytes.
+    pub median: u64,
+    /// Size of the largest message in bytes.
+    pub max: u64,
+}
+
+/// A table of the received message sizes, with a row for each tag of each test run.
+#[derive(Default)]
+pub struct MsgSizeTable {
+    rows: Vec<MsgSizeStats>,
+}
+
+impl MsgSizeTable {
+    pub fn add_row(&mut self, row: MsgSizeStats) {
+        self.rows.push(row);
+    }
+}
+
+impl fmt::Display for MsgSizeTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "| {:>7} | {:>5} | {:>10} | {:>9} | {:>9} | {:>9} |",
+            "peers", "tag", "messages", "min (B)", "50% (B)", "max (B)"
+        )?;
+        for row in &self.rows {
+            writeln!(
+                f,
+                "| {:>7} | {:>5} | {:>10} | {:>9} | {:>9} | {:>9} |",
+                row.peers,
+                row.tag.get_tag_str(),
+                row.messages,
+                row.min,
+                row.median,
+                row.max
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Adds a row to the table for each tag of the messages received in the test run.
+pub fn add_msg_size_rows(table: &mut MsgSizeTable, snapshot: &TestMetricsSnapshot, peers: u16) {
+    for tag in Tag::iter() {
+        let sizes = match snapshot.construct_histogram(&msg_size_metric(tag)) {
+            Some(sizes) if sizes.entries() >= 1 => sizes,
+            _ => continue,
+        };
+
+        table.add_row(MsgSizeStats {
+            peers,
+            tag,
+            messages: sizes.entries(),
+            min: sizes.minimum().unwrap_or_default(),
+            median: sizes.percentile(50.0).unwrap_or_default(),
+            max: sizes.maximum().unwrap_or_default(),
+        });
+    }
+}
+
+#[test]
+fn record_sizes_per_tag() {
+    let test_metrics = TestMetrics::default();
+
+    for len in [10, 20, 30] {
+        let mut raw = Tag::StateProofSig.get_tag_str().as_bytes().to_vec();
+        raw.resize(len, 0);
+        record_msg_size(&AlgoMsg {
+            payload: Payload::Unknown {
+                tag: Tag::StateProofSig,
+                raw: raw[TAG_LEN..].to_vec(),
+            },
+            raw,
+            opcode: Opcode::Binary,
+        });
+    }
+
+    let snapshot = test_metrics.take_snapshot();
+    let mut table = MsgSizeTable::default();
+    add_msg_size_rows(&mut table, &snapshot, 1);
+
+    assert_eq!(table.rows.len(), 1);
+    let row = &table.rows[0];
+    assert_eq!(row.tag, Tag::StateProofSig);
+    assert_eq!(row.messages, 3);
+    assert_eq!((row.min, row.max), (10, 30));
+    assert!(table.to_string().contains("SP"));
+}
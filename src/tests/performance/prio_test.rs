@@ -1,7 +1,6 @@
 use std::{
     collections::HashSet,
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    str::FromStr,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -35,14 +34,19 @@ use crate::{
         payload_factory::PayloadFactory,
     },
     setup::node::Node,
-    tools::{ips::ips, synthetic_node::SyntheticNodeBuilder},
+    tests::performance::msg_sizes::{add_msg_size_rows, record_msg_size, MsgSizeTable},
+    tools::{
+        constants::ERR_NODE_START,
+        ips::{bind_socket, ips},
+        synthetic_node::SyntheticNodeBuilder,
+    },
 };
 
 const METRIC_LATENCY: &str = "traffic_test_latency";
 // number of requests to send per peer
 const REQUESTS: u16 = 300;
 const RESPONSE_TIMEOUT: Duration = Duration::from_secs(3);
-const ROUND_KEY: Round = 1;
+const ROUND_KEY: Round = Round(1);
 
 // ZG-PERFORMANCE-002, Getting messages of one kind while other nodes send some other traffic
 //
@@ -90,6 +94,7 @@ async fn p002_t1_TRAFFIC_HIGH_LOW_latency() {
             data_type: UniEnsBlockReqType::BlockAndCert,
             round_key: ROUND_KEY,
             nonce: 123,
+            max_blocks: None,
         }),
         None,
     );
@@ -108,8 +113,9 @@ async fn p002_t2_TRAFFIC_SAME_PRIO_latency() {
     let high_traffic_factory = PayloadFactory::new(
         Payload::UniEnsBlockReq(UniEnsBlockReq {
             data_type: UniEnsBlockReqType::BlockAndCert,
-            round_key: 3,
+            round_key: Round(3),
             nonce: 1,
+            max_blocks: None,
         }),
         None,
     );
@@ -118,6 +124,7 @@ async fn p002_t2_TRAFFIC_SAME_PRIO_latency() {
             data_type: UniEnsBlockReqType::BlockAndCert,
             round_key: ROUND_KEY,
             nonce: 123,
+            max_blocks: None,
         }),
         None,
     );
@@ -141,6 +148,7 @@ async fn p002_t3_COMB_MSG_DIGEST_latency() {
             data_type: UniEnsBlockReqType::BlockAndCert,
             round_key: ROUND_KEY,
             nonce: 123,
+            max_blocks: None,
         }),
         None,
     );
@@ -178,6 +186,7 @@ async fn p002_t4_NET_PRIO_latency() {
             data_type: UniEnsBlockReqType::BlockAndCert,
             round_key: ROUND_KEY,
             nonce: 123,
+            max_blocks: None,
         }),
         None,
     );
@@ -222,6 +231,7 @@ async fn p002_t5_PROP_PAYLOAD_latency() {
             data_type: UniEnsBlockReqType::BlockAndCert,
             round_key: ROUND_KEY,
             nonce: 123,
+            max_blocks: None,
         }),
         None,
     );
@@ -263,6 +273,7 @@ async fn p002_t6_AGREEMENT_latency() {
             data_type: UniEnsBlockReqType::BlockAndCert,
             round_key: ROUND_KEY,
             nonce: 123,
+            max_blocks: None,
         }),
         None,
     );
@@ -277,6 +288,7 @@ async fn run_traffic_test(
     let n_traffic_peers = 1;
 
     let mut table = TrafficRequestsTable::default();
+    let mut size_table = MsgSizeTable::default();
 
     for h_traffic_peers in h_traffic_peer_set {
         let total_peers = n_traffic_peers + h_traffic_peers;
@@ -284,7 +296,7 @@ async fn run_traffic_test(
 
         let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
         let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-        node.start().await;
+        node.start().await.expect(ERR_NODE_START);
 
         let node_addr = node.net_addr().expect(ERR_NODE_ADDR);
 
@@ -294,16 +306,9 @@ async fn run_traffic_test(
         for _ in 0..total_peers {
             // If there is address for our thread in the pool we can use it.
             // Otherwise we'll not set bound_addr and use local IP addr (127.0.0.1).
-            let ip = ips.pop().unwrap_or("127.0.0.1".to_string());
-
-            let ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::from_str(&ip).unwrap()), 0);
-            let socket = TcpSocket::new_v4().unwrap();
+            let ip = ips.pop().unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
 
-            // Make sure we can reuse the address and port
-            socket.set_reuseaddr(true).unwrap();
-            socket.set_reuseport(true).unwrap();
-
-            socket.bind(ip).expect(ERR_SOCKET_BIND);
+            let socket = bind_socket(ip).expect(ERR_SOCKET_BIND);
             synth_sockets.push(socket);
         }
 
@@ -351,12 +356,14 @@ async fn run_traffic_test(
                 ));
             }
         }
+        add_msg_size_rows(&mut size_table, &snapshot, total_peers as u16);
 
-        node.stop().expect(ERR_NODE_STOP);
+        node.stop().await.expect(ERR_NODE_STOP);
     }
 
     // Display results table
     println!("\r\n{}", table);
+    println!("\r\n{}", size_table);
 }
 
 #[allow(unused_must_use)]
@@ -402,9 +409,10 @@ async fn simulate_normal_traffic_peer(
         timeout(RESPONSE_TIMEOUT, async {
             loop {
                 let m = synth_node.recv_message().await.1;
+                record_msg_size(&m);
                 // TODO[asmie]: matcher should be taken from the factory or should depened on factory payload type used
                 if matches!(&m, AlgoMsg { payload: Payload::TopicMsgResp(TopicMsgResp::UniEnsBlockRsp(rsp)), ..}
-                     if rsp.block.is_some() && rsp.block.as_ref().unwrap().round == ROUND_KEY && rsp.cert.is_some()) {
+                     if rsp.block_round() == Some(ROUND_KEY) && rsp.has_cert()) {
                     metrics::histogram!(METRIC_LATENCY, duration_as_ms(now.elapsed()));
                     break;
                 }
@@ -447,10 +455,13 @@ async fn simulate_high_priority_peer(
             .expect(ERR_SYNTH_UNICAST);
 
         // Just check if there is anything to read in the incoming queue. If so, read and
-        // discard it. We don't care about the response.
-        let _ = synth_node
+        // discard it. We don't care about the response, only about its size.
+        if let Ok((_, msg)) = synth_node
             .recv_message_timeout(Duration::from_micros(10))
-            .await;
+            .await
+        {
+            record_msg_size(&msg);
+        }
     }
 
     synth_node.shut_down().await
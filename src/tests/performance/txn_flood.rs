@@ -0,0 +1,219 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tempfile::TempDir;
+use tokio::{
+    net::TcpSocket,
+    sync::Barrier,
+    task::JoinSet,
+    time::{sleep, timeout},
+};
+use ziggurat_core_metrics::{
+    latency_tables::{LatencyRequestStats, LatencyRequestsTable},
+    recorder::TestMetrics,
+    tables::duration_as_ms,
+};
+use ziggurat_core_utils::err_constants::{
+    ERR_KMD_STOP, ERR_NODE_ADDR, ERR_NODE_BUILD, ERR_NODE_STOP, ERR_SOCKET_BIND, ERR_SYNTH_BUILD,
+    ERR_SYNTH_CONNECT, ERR_SYNTH_UNICAST, ERR_TEMPDIR_NEW,
+};
+
+use crate::{
+    protocol::codecs::payload::Payload,
+    setup::node::{rest_api::client::RestClient, Node},
+    tools::{
+        constants::ERR_NODE_START,
+        helpers::{
+            get_pub_key_addr, get_self_payment_txn, get_signed_tagged_txn, get_wallet_token,
+            start_kmd,
+        },
+        ips::{bind_socket, ips},
+        synthetic_node::SyntheticNodeBuilder,
+    },
+};
+
+const METRIC_LATENCY: &str = "txn_flood_test_latency";
+// number of transactions to send per peer
+const REQUESTS: u16 = 20;
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(3);
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A signed transaction ready to be sent and its ID used for the pending transaction lookup.
+struct PreparedTxn {
+    tx_id: String,
+    signed_tagged_txn: Vec<u8>,
+}
+
+#[cfg_attr(
+    not(feature = "performance"),
+    ignore = "run this test with the 'performance' feature enabled"
+)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+#[allow(non_snake_case)]
+async fn p003_TXN_FLOOD_latency() {
+    // ZG-PERFORMANCE-003, Transaction acceptance latency
+    //
+    // This test checks if node behaves as expected under a flood of transactions from other peers.
+    // We measure the time between sending a transaction and the node reporting it as pending.
+    //
+    // Results should be inspected manually as they are strongly dependent on the machine.
+    //
+    // *NOTE* run with `cargo test --release  tests::performance::txn_flood -- --nocapture`
+    // Before running test generate dummy devices with different ips using toos/ips.py
+
+    let synth_counts = vec![1, 10, 25, 50, 100];
+
+    let mut table = LatencyRequestsTable::default();
+
+    for synth_count in synth_counts {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+        node.start().await.expect(ERR_NODE_START);
+
+        let mut kmd = start_kmd(target.path()).await;
+
+        let node_addr = node.net_addr().expect(ERR_NODE_ADDR);
+
+        // Pre-sign all transactions so signing doesn't affect the measurements.
+        let wallet_token = get_wallet_token(&mut kmd).await;
+        let addr = get_pub_key_addr(&mut kmd, wallet_token.clone()).await;
+        let txn = get_self_payment_txn(&mut node, addr, 1000).await;
+
+        let mut batches = Vec::with_capacity(synth_count);
+        for peer in 0..synth_count {
+            let mut batch = Vec::with_capacity(REQUESTS as usize);
+            for request in 0..REQUESTS {
+                // A unique note makes each transaction distinct.
+                let mut txn = txn.clone();
+                txn.note = format!("{peer}-{request}").into_bytes();
+
+                batch.push(PreparedTxn {
+                    tx_id: format!("{:?}", txn.id()),
+                    signed_tagged_txn: get_signed_tagged_txn(&mut kmd, wallet_token.clone(), &txn)
+                        .await,
+                });
+            }
+            batches.push(batch);
+        }
+
+        let rest_client = node
+            .rest_client()
+            .expect("couldn't get the REST client")
+            .clone();
+
+        let mut synth_sockets = Vec::with_capacity(synth_count);
+        let mut ips = ips();
+
+        for _ in 0..synth_count {
+            // If there is address for our thread in the pool we can use it.
+            // Otherwise we'll not set bound_addr and use local IP addr (127.0.0.1).
+            let ip = ips.pop().unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+            let socket = bind_socket(ip).expect(ERR_SOCKET_BIND);
+            synth_sockets.push(socket);
+        }
+
+        // setup metrics recorder
+        let test_metrics = TestMetrics::default();
+        // clear metrics and register metrics
+        metrics::register_histogram!(METRIC_LATENCY);
+
+        let mut synth_handles = JoinSet::new();
+        let test_start = tokio::time::Instant::now();
+
+        let barrier = Arc::new(Barrier::new(synth_count));
+
+        for (socket, batch) in synth_sockets.into_iter().zip(batches) {
+            let arc_barrier = barrier.clone();
+            synth_handles.spawn(simulate_peer(
+                node_addr,
+                socket,
+                arc_barrier,
+                rest_client.clone(),
+                batch,
+            ));
+        }
+
+        // wait for peers to complete
+        while (synth_handles.join_next().await).is_some() {}
+
+        let time_taken_secs = test_start.elapsed().as_secs_f64();
+
+        let snapshot = test_metrics.take_snapshot();
+        if let Some(latencies) = snapshot.construct_histogram(METRIC_LATENCY) {
+            if latencies.entries() >= 1 {
+                // add stats to table display
+                table.add_row(LatencyRequestStats::new(
+                    synth_count as u16,
+                    REQUESTS,
+                    latencies,
+                    time_taken_secs,
+                ));
+            }
+        }
+
+        kmd.stop().expect(ERR_KMD_STOP);
+        node.stop().await.expect(ERR_NODE_STOP);
+    }
+
+    // Display results table
+    println!("\r\n{}", table);
+}
+
+#[allow(unused_must_use)] // just for result of the timeout
+async fn simulate_peer(
+    node_addr: SocketAddr,
+    socket: TcpSocket,
+    start_barrier: Arc<Barrier>,
+    rest_client: RestClient,
+    txns: Vec<PreparedTxn>,
+) {
+    let synth_node = SyntheticNodeBuilder::default()
+        .build()
+        .await
+        .expect(ERR_SYNTH_BUILD);
+
+    // Establish peer connection
+    synth_node
+        .connect_from(node_addr, socket)
+        .await
+        .expect(ERR_SYNTH_CONNECT);
+
+    // Wait for all peers to connect
+    start_barrier.wait().await;
+
+    for txn in txns {
+        // Submit transaction via peer protocol.
+        if !synth_node.is_connected(node_addr) {
+            break;
+        }
+
+        synth_node
+            .unicast(node_addr, Payload::RawBytes(txn.signed_tagged_txn))
+            .expect(ERR_SYNTH_UNICAST);
+
+        let now = Instant::now();
+
+        // The transaction is accepted once the node reports it as pending. On timeout, the
+        // transaction is simply not registered and we move on to the next one.
+        timeout(RESPONSE_TIMEOUT, async {
+            loop {
+                if let Ok(Some(_)) = rest_client
+                    .pending_transaction_information(&txn.tx_id)
+                    .await
+                {
+                    metrics::histogram!(METRIC_LATENCY, duration_as_ms(now.elapsed()));
+                    break;
+                }
+
+                sleep(POLL_INTERVAL).await;
+            }
+        })
+        .await;
+    }
+
+    synth_node.shut_down().await
+}
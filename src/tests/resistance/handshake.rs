@@ -1,4 +1,9 @@
 use tempfile::TempDir;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    time::timeout,
+};
 use ziggurat_core_utils::err_constants::{
     ERR_NODE_ADDR, ERR_NODE_BUILD, ERR_NODE_STOP, ERR_SYNTH_BUILD, ERR_TEMPDIR_NEW,
 };
@@ -6,10 +11,16 @@ use ziggurat_core_utils::err_constants::{
 use crate::{
     protocol::{
         codecs::payload::Payload,
-        handshake::{HandshakeCfg, SecWebSocket, X_AG_ACCEPT_VERSION, X_AG_ALGORAND_VERSION},
+        handshake::{
+            HandshakeCfg, SecWebSocket, X_AG_ACCEPT_VERSION, X_AG_ALGORAND_GENESIS,
+            X_AG_ALGORAND_VERSION,
+        },
     },
     setup::node::{ChildExitCode, Node},
-    tools::synthetic_node::SyntheticNodeBuilder,
+    tools::{
+        constants::{ERR_NODE_START, EXPECT_MSG_TIMEOUT},
+        synthetic_node::SyntheticNodeBuilder,
+    },
 };
 
 // Empirical values based on some unofficial testing.
@@ -25,7 +36,7 @@ async fn run_handshake_req_test_with_cfg(cfg: HandshakeCfg, debug: bool) -> bool
         .log_to_stdout(debug)
         .build(target.path())
         .expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and enable handshaking.
     let mut synthetic_node = SyntheticNodeBuilder::default()
@@ -48,7 +59,10 @@ async fn run_handshake_req_test_with_cfg(cfg: HandshakeCfg, debug: bool) -> bool
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    assert_eq!(node.stop().expect(ERR_NODE_STOP), ChildExitCode::Success);
+    assert_eq!(
+        node.stop().await.expect(ERR_NODE_STOP),
+        ChildExitCode::Success
+    );
 
     handshake_established
 }
@@ -63,6 +77,31 @@ async fn normal_handshake() {
     );
 }
 
+#[tokio::test]
+async fn handshake_fails_when_peer_closes_connection() {
+    // A peer which accepts the connection and immediately closes it.
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("couldn't bind the listener");
+    let peer_addr = listener.local_addr().expect("couldn't get the local addr");
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            drop(stream);
+        }
+    });
+
+    let synthetic_node = SyntheticNodeBuilder::default()
+        .build()
+        .await
+        .expect(ERR_SYNTH_BUILD);
+
+    // The handshake should fail gracefully instead of panicking.
+    assert!(synthetic_node.connect(peer_addr).await.is_err());
+    assert_eq!(synthetic_node.num_connected(), 0);
+
+    synthetic_node.shut_down().await;
+}
+
 /// Generate a string with a given length.
 fn gen_huge_string(len: usize) -> String {
     vec!['y'; len].into_iter().collect::<String>()
@@ -74,7 +113,7 @@ async fn r002_t1_HANDSHAKE_instance_name() {
     // ZG-RESISTANCE-002
 
     let gen_cfg = |len| HandshakeCfg {
-        ar_instance_name: gen_huge_string(len),
+        ar_instance_name: Some(gen_huge_string(len)),
         ..Default::default()
     };
 
@@ -102,7 +141,7 @@ async fn r002_t2_HANDSHAKE_node_random() {
     // ZG-RESISTANCE-002
 
     let gen_cfg = |len| HandshakeCfg {
-        ar_node_random: gen_huge_string(len),
+        ar_node_random: Some(gen_huge_string(len)),
         ..Default::default()
     };
 
@@ -130,6 +169,8 @@ async fn r002_t3_HANDSHAKE_genesis() {
 
     let gen_cfg = |len| HandshakeCfg {
         ar_genesis: gen_huge_string(len),
+        // Keep the request path valid, only the header is tested.
+        gossip_genesis: Some(X_AG_ALGORAND_GENESIS.into()),
         ..Default::default()
     };
 
@@ -157,7 +198,7 @@ async fn r002_t4_HANDSHAKE_user_agent() {
     // ZG-RESISTANCE-002
 
     let gen_cfg = |len| HandshakeCfg {
-        user_agent: gen_huge_string(len),
+        user_agent: Some(gen_huge_string(len)),
         ..Default::default()
     };
 
@@ -185,11 +226,11 @@ async fn r002_t5_HANDSHAKE_ws_version() {
     // ZG-RESISTANCE-002
 
     let gen_cfg_huge = |len| HandshakeCfg {
-        ws_version: gen_huge_string(len),
+        ws_version: Some(gen_huge_string(len)),
         ..Default::default()
     };
     let gen_cfg_with = |version: usize| HandshakeCfg {
-        ws_version: version.to_string(),
+        ws_version: Some(version.to_string()),
         ..Default::default()
     };
 
@@ -306,13 +347,13 @@ async fn r002_t9_HANDSHAKE_version() {
     // ZG-RESISTANCE-002
 
     let gen_cfg_huge = |len| HandshakeCfg {
-        ar_version: gen_huge_string(len),
-        ar_accept_version: "".into(),
+        ar_version: Some(gen_huge_string(len)),
+        ar_accept_version: Some(String::new()),
         ..Default::default()
     };
     let gen_cfg_with = |version, accept_version| HandshakeCfg {
-        ar_version: version,
-        ar_accept_version: accept_version,
+        ar_version: Some(version),
+        ar_accept_version: Some(accept_version),
         ..Default::default()
     };
 
@@ -355,13 +396,13 @@ async fn r002_t10_HANDSHAKE_accept_version() {
     // ZG-RESISTANCE-002
 
     let gen_cfg_huge = |len| HandshakeCfg {
-        ar_accept_version: gen_huge_string(len),
-        ar_version: "".into(),
+        ar_accept_version: Some(gen_huge_string(len)),
+        ar_version: Some(String::new()),
         ..Default::default()
     };
     let gen_cfg_with = |version, accept_version| HandshakeCfg {
-        ar_version: version,
-        ar_accept_version: accept_version,
+        ar_version: Some(version),
+        ar_accept_version: Some(accept_version),
         ..Default::default()
     };
 
@@ -397,3 +438,77 @@ async fn r002_t10_HANDSHAKE_accept_version() {
     let cfg = gen_cfg_huge(WS_HTTP_HEADER_INVALID_SIZE);
     assert!(!run_handshake_req_test_with_cfg(cfg, false).await);
 }
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn r002_t11_HANDSHAKE_mismatched_genesis() {
+    // ZG-RESISTANCE-002
+
+    // A valid genesis ID of a different network.
+    let cfg = HandshakeCfg::default().with_genesis("testnet-v1.0");
+    assert!(
+        !run_handshake_req_test_with_cfg(cfg, false).await,
+        "the node accepted a handshake for a different network"
+    );
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn r002_t12_HANDSHAKE_omitted_instance_name() {
+    // ZG-RESISTANCE-002
+
+    let cfg = HandshakeCfg {
+        ar_instance_name: None,
+        ..Default::default()
+    };
+
+    // The node requires the instance name, just as it rejects an empty one.
+    assert!(
+        !run_handshake_req_test_with_cfg(cfg, false).await,
+        "the node accepted a handshake without the instance name"
+    );
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn r002_t13_HANDSHAKE_plain_http_request() {
+    // ZG-RESISTANCE-002
+
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
+
+    // Open a plain TCP connection, without asking for the WebSocket upgrade.
+    let mut stream = SyntheticNodeBuilder::default()
+        .raw_tcp(net_addr)
+        .await
+        .expect("couldn't connect to the node");
+
+    let req = format!("GET / HTTP/1.1\r\nHost: {net_addr}\r\n\r\n");
+    stream
+        .write_all(req.as_bytes())
+        .await
+        .expect("couldn't send the request");
+
+    // The node either answers with an HTTP error or closes the connection.
+    let mut rsp = vec![0; 1024];
+    let len = timeout(EXPECT_MSG_TIMEOUT, stream.read(&mut rsp))
+        .await
+        .expect("the node neither answered nor closed the connection")
+        .unwrap_or(0);
+    let rsp = String::from_utf8_lossy(&rsp[..len]);
+    assert!(
+        !rsp.starts_with("HTTP/1.1 101"),
+        "the node upgraded a plain HTTP request: {rsp}"
+    );
+
+    // Gracefully shut down the node.
+    drop(stream);
+    assert_eq!(
+        node.stop().await.expect(ERR_NODE_STOP),
+        ChildExitCode::Success
+    );
+}
@@ -1,23 +1,21 @@
 use tempfile::TempDir;
 use tokio::time::{sleep, timeout, Duration};
 use ziggurat_core_utils::err_constants::{
-    ERR_KMD_BUILD, ERR_KMD_STOP, ERR_NODE_ADDR, ERR_NODE_BUILD, ERR_NODE_STOP, ERR_SYNTH_UNICAST,
-    ERR_TEMPDIR_NEW,
+    ERR_KMD_STOP, ERR_NODE_ADDR, ERR_NODE_BUILD, ERR_NODE_STOP, ERR_SYNTH_UNICAST, ERR_TEMPDIR_NEW,
 };
 
 use crate::{
     protocol::codecs::{
-        algomsg::AlgoMsg,
-        msgpack::{Payment, Transaction, TransactionType},
-        payload::Payload,
-        tagmsg::Tag,
+        algomsg::AlgoMsg, msgpack::MAX_TXN_NOTE_LEN, payload::Payload, tagmsg::Tag,
     },
-    setup::{kmd::Kmd, node::Node},
-    tests::conformance::post_handshake::cmd::{
-        get_handshaked_synth_node, get_pub_key_addr, get_signed_tagged_txn, get_txn_params,
-        get_wallet_token,
+    setup::node::Node,
+    tools::{
+        constants::{ERR_NODE_START, EXPECT_MSG_TIMEOUT},
+        helpers::{
+            get_handshaked_synth_node, get_pub_key_addr, get_signed_tagged_txn, get_txn_params,
+            get_wallet_token, start_kmd,
+        },
     },
-    tools::constants::EXPECT_MSG_TIMEOUT,
 };
 
 // Generates a valid proposal payload message which contains a massive amount of transactions.
@@ -25,13 +23,9 @@ pub async fn get_huge_proposal_payload() -> AlgoMsg {
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
-    let mut kmd = Kmd::builder()
-        .build(target.path())
-        .await
-        .expect(ERR_KMD_BUILD);
-    kmd.start().await;
+    let mut kmd = start_kmd(target.path()).await;
 
     let wallet_token = get_wallet_token(&mut kmd).await;
     let txn_params = get_txn_params(&mut node).await;
@@ -43,26 +37,11 @@ pub async fn get_huge_proposal_payload() -> AlgoMsg {
     let mut txns = Vec::with_capacity(TXN_CNT as usize);
 
     for i in 0..TXN_CNT {
-        let txn_type = TransactionType::Payment(Payment {
-            receiver: rx_addr,
-            amount: 1000 + i,
-            close_remainder_to: None,
-        });
-
         // Create a huge transaction - use a maximum note length.
-        let txn = Transaction {
-            sender: tx_addr,
-            fee: txn_params.min_fee,
-            first_valid: txn_params.last_round,
-            last_valid: txn_params.last_round + 1000,
-            note: vec![b'y'; 1024],
-            genesis_id: txn_params.genesis_id.clone(),
-            genesis_hash: txn_params.genesis_hash,
-            group: None,
-            lease: None,
-            txn_type,
-            rekey_to: None,
-        };
+        let mut txn = txn_params
+            .payment(tx_addr, rx_addr, 1000 + i)
+            .expect("the last valid round overflows");
+        txn.note = vec![b'y'; MAX_TXN_NOTE_LEN];
 
         txns.push(Payload::RawBytes(
             get_signed_tagged_txn(&mut kmd, wallet_token.clone(), &txn).await,
@@ -91,7 +70,7 @@ pub async fn get_huge_proposal_payload() -> AlgoMsg {
 
         loop {
             let m = synthetic_node.recv_message().await.1;
-            if matches!(&m, AlgoMsg { payload: Payload::ProposalPayload(_), raw } if raw.len() > PP_MSG_LEN) {
+            if matches!(&m, AlgoMsg { payload: Payload::ProposalPayload(_), raw, .. } if raw.len() > PP_MSG_LEN) {
                 return m
             }
         }
@@ -99,7 +78,7 @@ pub async fn get_huge_proposal_payload() -> AlgoMsg {
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 
     proposal_payload_msg
 }
@@ -117,7 +96,7 @@ async fn r004_t1_PROPOPSAL_PAYLOAD_send_a_huge_valid_msg() {
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node.
     let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
@@ -132,11 +111,9 @@ async fn r004_t1_PROPOPSAL_PAYLOAD_send_a_huge_valid_msg() {
     assert!(synthetic_node.unicast(net_addr, msg.clone()).is_ok());
 
     // Clear the inbound queue.
-    while synthetic_node
-        .recv_message_timeout(Duration::from_millis(10))
-        .await
-        .is_ok()
-    {}
+    synthetic_node
+        .drain_inbound(Duration::from_millis(10))
+        .await;
 
     // Check that we are still receiving ProposalPayload messages.
     assert!(
@@ -146,7 +123,7 @@ async fn r004_t1_PROPOPSAL_PAYLOAD_send_a_huge_valid_msg() {
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
 
 #[tokio::test]
@@ -165,7 +142,7 @@ async fn r004_t2_MSG_DIGEST_SKIP_send_a_huge_invalid_msg() {
     // Spin up a node instance.
     let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
     let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node.
     let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
@@ -176,11 +153,9 @@ async fn r004_t2_MSG_DIGEST_SKIP_send_a_huge_invalid_msg() {
         .expect(ERR_SYNTH_UNICAST);
 
     // Clear the inbound queue.
-    while synthetic_node
-        .recv_message_timeout(Duration::from_millis(50))
-        .await
-        .is_ok()
-    {}
+    synthetic_node
+        .drain_inbound(Duration::from_millis(50))
+        .await;
 
     // Check that we are still receiving messages.
     assert!(
@@ -192,5 +167,5 @@ async fn r004_t2_MSG_DIGEST_SKIP_send_a_huge_invalid_msg() {
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 }
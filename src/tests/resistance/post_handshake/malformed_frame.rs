@@ -0,0 +1,80 @@
+use tempfile::TempDir;
+use ziggurat_core_utils::err_constants::{
+    ERR_NODE_ADDR, ERR_NODE_BUILD, ERR_NODE_STOP, ERR_SYNTH_BUILD, ERR_SYNTH_CONNECT,
+    ERR_SYNTH_UNICAST, ERR_TEMPDIR_NEW,
+};
+
+use crate::{
+    setup::node::Node,
+    tests::resistance::WAIT_FOR_DISCONNECT,
+    tools::{constants::ERR_NODE_START, synthetic_node::SyntheticNodeBuilder},
+};
+
+/// The masking key for the client frames, the node doesn't care about its value.
+const MASK: [u8; 4] = [0x12, 0x34, 0x56, 0x78];
+
+/// Send the frame directly to the node after the handshake and return the connection status.
+async fn send_frame_to_the_node(frame: Vec<u8>) -> bool {
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    // Create a synthetic node and enable handshaking.
+    let synthetic_node = SyntheticNodeBuilder::default()
+        .build()
+        .await
+        .expect(ERR_SYNTH_BUILD);
+
+    let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
+
+    // Connect to the node and initiate the handshake.
+    synthetic_node
+        .connect(net_addr)
+        .await
+        .expect(ERR_SYNTH_CONNECT);
+
+    synthetic_node
+        .send_raw_frame(net_addr, frame)
+        .expect(ERR_SYNTH_UNICAST);
+
+    // Give some time to the node to kill our connection.
+    let is_connected = !synthetic_node
+        .expect_disconnect(net_addr, WAIT_FOR_DISCONNECT)
+        .await;
+
+    // Gracefully shut down the nodes.
+    synthetic_node.shut_down().await;
+    node.stop().await.expect(ERR_NODE_STOP);
+
+    is_connected
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn r005_t1_MALFORMED_FRAME_send_a_frame_with_a_reserved_opcode() {
+    // ZG-RESISTANCE-005
+
+    // A final, masked frame with the reserved 0x3 opcode and an empty payload.
+    let mut frame = vec![0x83, 0x80];
+    frame.extend(MASK);
+
+    assert!(
+        !send_frame_to_the_node(frame).await,
+        "the node shouldn't keep the connection alive after receiving a reserved opcode"
+    );
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn r005_t2_MALFORMED_FRAME_send_an_unmasked_frame() {
+    // ZG-RESISTANCE-005
+
+    // A final, unmasked binary frame, which clients must never send.
+    let frame = vec![0x82, 0x02, b'P', b'I'];
+
+    assert!(
+        !send_frame_to_the_node(frame).await,
+        "the node shouldn't keep the connection alive after receiving an unmasked frame"
+    );
+}
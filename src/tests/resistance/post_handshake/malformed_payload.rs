@@ -0,0 +1,82 @@
+use tempfile::TempDir;
+use ziggurat_core_utils::err_constants::{
+    ERR_NODE_ADDR, ERR_NODE_BUILD, ERR_NODE_STOP, ERR_SYNTH_BUILD, ERR_SYNTH_CONNECT,
+    ERR_SYNTH_UNICAST, ERR_TEMPDIR_NEW,
+};
+
+use crate::{
+    protocol::codecs::{
+        msgpack::{Round, VoteBundle},
+        payload::Payload,
+    },
+    setup::node::Node,
+    tests::resistance::WAIT_FOR_DISCONNECT,
+    tools::{constants::ERR_NODE_START, synthetic_node::SyntheticNodeBuilder, util::corrupt_field},
+};
+
+/// Send the tagged message to the node after the handshake and return the connection status.
+async fn send_msg_to_the_node(msg: Vec<u8>) -> bool {
+    // Spin up a node instance.
+    let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+    let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+    node.start().await.expect(ERR_NODE_START);
+
+    // Create a synthetic node and enable handshaking.
+    let synthetic_node = SyntheticNodeBuilder::default()
+        .build()
+        .await
+        .expect(ERR_SYNTH_BUILD);
+
+    let net_addr = node.net_addr().expect(ERR_NODE_ADDR);
+
+    // Connect to the node and initiate the handshake.
+    synthetic_node
+        .connect(net_addr)
+        .await
+        .expect(ERR_SYNTH_CONNECT);
+
+    synthetic_node
+        .unicast(net_addr, Payload::RawBytes(msg))
+        .expect(ERR_SYNTH_UNICAST);
+
+    // Give some time to the node to kill our connection.
+    let is_connected = !synthetic_node
+        .expect_disconnect(net_addr, WAIT_FOR_DISCONNECT)
+        .await;
+
+    // Gracefully shut down the nodes.
+    synthetic_node.shut_down().await;
+    node.stop().await.expect(ERR_NODE_STOP);
+
+    is_connected
+}
+
+fn vote_bundle() -> Payload {
+    Payload::VoteBundle(Box::new(VoteBundle::empty(Round(1))))
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn r006_t1_MALFORMED_PAYLOAD_send_a_vote_bundle_with_a_string_round() {
+    // ZG-RESISTANCE-006
+
+    let msg = corrupt_field(vote_bundle(), "rnd").expect("couldn't corrupt the vote bundle");
+
+    assert!(
+        !send_msg_to_the_node(msg).await,
+        "the node shouldn't keep the connection alive after receiving a malformed vote bundle"
+    );
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn r006_t2_MALFORMED_PAYLOAD_send_a_vote_bundle_with_a_string_step() {
+    // ZG-RESISTANCE-006
+
+    let msg = corrupt_field(vote_bundle(), "step").expect("couldn't corrupt the vote bundle");
+
+    assert!(
+        !send_msg_to_the_node(msg).await,
+        "the node shouldn't keep the connection alive after receiving a malformed vote bundle"
+    );
+}
@@ -1,2 +1,4 @@
 pub mod enormous_message;
+pub mod malformed_frame;
+pub mod malformed_payload;
 pub mod random_bytes;
@@ -1,5 +1,4 @@
 use tempfile::TempDir;
-use tokio::time::sleep;
 use ziggurat_core_utils::err_constants::{
     ERR_NODE_ADDR, ERR_NODE_BUILD, ERR_NODE_STOP, ERR_SYNTH_BUILD, ERR_SYNTH_CONNECT,
     ERR_SYNTH_UNICAST, ERR_TEMPDIR_NEW,
@@ -9,7 +8,9 @@ use crate::{
     protocol::codecs::payload::Payload,
     setup::node::Node,
     tests::resistance::WAIT_FOR_DISCONNECT,
-    tools::{synthetic_node::SyntheticNodeBuilder, util::gen_rand_bytes},
+    tools::{
+        constants::ERR_NODE_START, synthetic_node::SyntheticNodeBuilder, util::gen_rand_bytes,
+    },
 };
 
 /// Send some randomly generated data to the node before the handshake and check the connection status.
@@ -20,7 +21,7 @@ async fn send_random_data_to_the_node_pre_handshake(len: usize, debug: bool) ->
         .log_to_stdout(debug)
         .build(target.path())
         .expect(ERR_NODE_BUILD);
-    node.start().await;
+    node.start().await.expect(ERR_NODE_START);
 
     // Create a synthetic node and disable handshaking.
     let synthetic_node = SyntheticNodeBuilder::default()
@@ -44,13 +45,13 @@ async fn send_random_data_to_the_node_pre_handshake(len: usize, debug: bool) ->
         .expect(ERR_SYNTH_UNICAST);
 
     // Give some time to the node to kill our connection.
-    sleep(WAIT_FOR_DISCONNECT).await;
-
-    let is_connected = synthetic_node.is_connected(net_addr);
+    let is_connected = !synthetic_node
+        .expect_disconnect(net_addr, WAIT_FOR_DISCONNECT)
+        .await;
 
     // Gracefully shut down the nodes.
     synthetic_node.shut_down().await;
-    node.stop().expect(ERR_NODE_STOP);
+    node.stop().await.expect(ERR_NODE_STOP);
 
     is_connected
 }
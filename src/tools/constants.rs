@@ -7,3 +7,6 @@ pub const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Timeout when waiting for an expected message or a change in the node's state.
 pub const EXPECT_MSG_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Error message for a node which couldn't be started.
+pub const ERR_NODE_START: &str = "couldn't start the node";
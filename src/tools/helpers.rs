@@ -0,0 +1,264 @@
+//! Helpers shared by the tests which need a handshaked synthetic node or a funded node account.
+
+use std::{net::SocketAddr, path::Path};
+
+use tokio::time::{sleep, timeout, Duration};
+use ziggurat_core_utils::err_constants::{ERR_KMD_BUILD, ERR_SYNTH_BUILD, ERR_SYNTH_CONNECT};
+
+use crate::{
+    protocol::codecs::{
+        msgpack::{compute_group_id, Address, Payment, Round, Transaction, TransactionType},
+        payload::Payload,
+        tagmsg::{Tag, TAG_LEN},
+    },
+    setup::{
+        kmd::Kmd,
+        node::{
+            rest_api::{client::RestClient, message::TransactionParams},
+            Node,
+        },
+    },
+    tools::synthetic_node::{SyntheticNode, SyntheticNodeBuilder},
+};
+
+pub async fn get_handshaked_synth_node(net_addr: SocketAddr) -> SyntheticNode {
+    // Create a synthetic node and enable handshaking.
+    let synthetic_node = SyntheticNodeBuilder::default()
+        .build()
+        .await
+        .expect(ERR_SYNTH_BUILD);
+
+    // Connect to the node and initiate the handshake.
+    synthetic_node
+        .connect(net_addr)
+        .await
+        .expect(ERR_SYNTH_CONNECT);
+
+    synthetic_node
+}
+
+/// Starts a kmd instance which uses the data directory of the node built in the target directory.
+pub async fn start_kmd(target: &Path) -> Kmd {
+    let mut kmd = Kmd::builder().build(target).await.expect(ERR_KMD_BUILD);
+    kmd.start().await;
+    kmd
+}
+
+pub async fn get_wallet_token(kmd: &mut Kmd) -> String {
+    get_named_wallet_token(kmd, "unencrypted-default-wallet", "").await
+}
+
+/// Returns a handle token for the wallet with the given name.
+pub async fn get_named_wallet_token(
+    kmd: &mut Kmd,
+    wallet_name: &str,
+    wallet_password: &str,
+) -> String {
+    let wallets = kmd.get_wallets().await.expect("couldn't get the wallets");
+
+    let wallet_id = wallets
+        .wallets
+        .into_iter()
+        .find(|wallet| wallet.name == wallet_name)
+        .expect("couldn't find the wallet")
+        .id;
+
+    kmd.get_wallet_handle_token(wallet_id, wallet_password.into())
+        .await
+        .expect("couldn't get the wallet token")
+        .wallet_handle_token
+}
+
+pub async fn get_txn_params(node: &mut Node) -> TransactionParams {
+    node.rest_client()
+        .expect("couldn't get the REST client")
+        .get_transaction_params()
+        .await
+        .expect("couldn't get the transaction parameters")
+}
+
+/// Creates a payment of `amount` micro-Algos from the address to itself.
+pub async fn get_self_payment_txn(node: &mut Node, addr: Address, amount: u64) -> Transaction {
+    get_payment_txn(node, addr, addr, amount).await
+}
+
+/// Creates a payment of `amount` micro-Algos from the sender to the receiver.
+pub async fn get_payment_txn(
+    node: &mut Node,
+    sender: Address,
+    receiver: Address,
+    amount: u64,
+) -> Transaction {
+    get_txn_params(node)
+        .await
+        .payment(sender, receiver, amount)
+        .expect("the last valid round overflows")
+}
+
+/// Creates a payment which closes the sender's account and sends all its remaining micro-Algos to
+/// the `close_to` address.
+pub async fn get_close_out_txn(node: &mut Node, sender: Address, close_to: Address) -> Transaction {
+    let mut txn = get_payment_txn(node, sender, close_to, 0).await;
+    txn.txn_type = TransactionType::Payment(Payment {
+        receiver: close_to,
+        amount: 0,
+        close_remainder_to: Some(close_to),
+    });
+
+    txn
+}
+
+/// Creates an atomic group of payments of the `amounts` from the address to itself.
+pub async fn get_self_payment_group(
+    node: &mut Node,
+    addr: Address,
+    amounts: &[u64],
+) -> Vec<Transaction> {
+    let mut txns = Vec::new();
+    for &amount in amounts {
+        txns.push(get_self_payment_txn(node, addr, amount).await);
+    }
+
+    let group_id = compute_group_id(&txns);
+    for txn in &mut txns {
+        txn.group = Some(group_id);
+    }
+
+    txns
+}
+
+/// Waits until the submitted transaction is confirmed.
+///
+/// The transactions sent over the gossip connection reach the node's pool asynchronously, so if
+/// the transaction was `gossiped`, the node not knowing about it yet is treated as pending.
+/// Otherwise the transaction must be known to the node.
+pub async fn wait_for_txn_confirmation(
+    rest_client: &RestClient,
+    tx_id: &str,
+    gossiped: bool,
+) -> Round {
+    // On average, new blocks are generated every 4 seconds.
+    timeout(Duration::from_secs(20), async {
+        loop {
+            let info = rest_client
+                .pending_transaction_information(tx_id)
+                .await
+                .expect("couldn't get the pending transaction information");
+
+            match info {
+                Some(info) => {
+                    assert!(info.pool_error.is_empty(), "{}", info.pool_error);
+
+                    if let Some(round) = info.confirmed_round {
+                        return round;
+                    }
+                }
+                None => assert!(gossiped, "the node doesn't know the transaction {tx_id}"),
+            }
+
+            sleep(Duration::from_millis(500)).await;
+        }
+    })
+    .await
+    .expect("the transaction wasn't confirmed")
+}
+
+/// Waits until all transactions of the atomic group are confirmed and returns the round.
+///
+/// The transactions of a group are confirmed in the same round or not at all. See
+/// [wait_for_txn_confirmation] for the `gossiped` groups.
+pub async fn wait_for_group_confirmation(
+    rest_client: &RestClient,
+    txns: &[Transaction],
+    gossiped: bool,
+) -> Round {
+    let mut rounds = Vec::new();
+    for txn in txns {
+        let tx_id = format!("{:?}", txn.id());
+        rounds.push(wait_for_txn_confirmation(rest_client, &tx_id, gossiped).await);
+    }
+
+    let round = rounds[0];
+    assert!(
+        rounds.iter().all(|&r| r == round),
+        "the group was confirmed in rounds {rounds:?}"
+    );
+    round
+}
+
+pub async fn get_pub_key_addr(kmd: &mut Kmd, wallet_token: String) -> Address {
+    let pub_key = kmd
+        .get_keys(wallet_token)
+        .await
+        .expect("couldn't get the wallet keys")
+        .addresses
+        .pop()
+        .expect("couldn't find any public keys in the wallet");
+
+    Address::from_string(&pub_key).expect("couldn't convert public key to address")
+}
+
+pub async fn get_signed_txn(kmd: &mut Kmd, wallet_token: String, txn: &Transaction) -> Vec<u8> {
+    kmd.sign_transaction(wallet_token, "".to_string(), txn)
+        .await
+        .expect("couldn't sign the transaction")
+        .signed_transaction
+}
+
+/// Rekeys the account to a new key generated in the wallet and returns the new auth address.
+///
+/// Once rekeyed, the account's transactions must be signed with the new key.
+pub async fn rekey_to_new_key(
+    node: &mut Node,
+    kmd: &mut Kmd,
+    wallet_token: String,
+    addr: Address,
+) -> Address {
+    let auth_addr = kmd
+        .generate_key(wallet_token.clone())
+        .await
+        .expect("couldn't generate a key");
+    let auth_addr = Address::from_string(&auth_addr).expect("invalid address");
+
+    let mut txn = get_self_payment_txn(node, addr, 0).await;
+    txn.rekey_to = Some(auth_addr);
+    let signed_txn = get_signed_txn(kmd, wallet_token, &txn).await;
+
+    let rest_client = node.rest_client().expect("couldn't get the REST client");
+    let tx_id = rest_client
+        .send_raw_transaction(&signed_txn)
+        .await
+        .expect("couldn't submit the rekey transaction");
+    wait_for_txn_confirmation(rest_client, &tx_id, false).await;
+
+    auth_addr
+}
+
+pub async fn get_signed_tagged_txn(
+    kmd: &mut Kmd,
+    wallet_token: String,
+    txn: &Transaction,
+) -> Vec<u8> {
+    kmd.sign_tagged_transaction(wallet_token, "".to_string(), txn)
+        .await
+        .expect("couldn't sign the transaction")
+}
+
+/// Combines the signed tagged transactions of an atomic group into a single gossip message.
+///
+/// The node expects a group as a single Txn tag followed by the concatenated signed transactions,
+/// so the tag is stripped from each transaction and a single tag is put in front of them.
+pub fn concat_signed_tagged_txns(signed_tagged_txns: &[Vec<u8>]) -> Payload {
+    let tag = Tag::Txn.get_tag_str().as_bytes();
+
+    let mut msg = tag.to_vec();
+    for signed_tagged_txn in signed_tagged_txns {
+        assert!(
+            signed_tagged_txn.starts_with(tag),
+            "the signed transaction isn't tagged as a transaction"
+        );
+        msg.extend_from_slice(&signed_tagged_txn[TAG_LEN..]);
+    }
+
+    Payload::RawBytes(msg)
+}
@@ -1,15 +1,56 @@
-use std::net::SocketAddr;
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
 
 use pea2pea::{Node, Pea2Pea};
 use tokio::sync::mpsc::Sender;
+use tracing::debug;
 
-use crate::protocol::{codecs::algomsg::AlgoMsg, handshake::HandshakeCfg};
+use crate::protocol::{
+    codecs::{algomsg::AlgoMsg, tagmsg::Tag, topic::MsgOfInterest},
+    handshake::{HandshakeCfg, HandshakeResponse},
+};
+
+/// Statistics of the messages received by the node.
+#[derive(Debug, Clone, Default)]
+pub struct MessageStats {
+    /// Total number of received messages.
+    pub messages: u64,
+    /// Total number of received message bytes (excluding the WebSocket framing).
+    pub bytes: u64,
+    /// Number of received messages per tag.
+    pub per_tag: HashMap<Tag, u64>,
+}
+
+impl MessageStats {
+    /// Accounts for a received message.
+    pub fn record(&mut self, msg: &AlgoMsg) {
+        self.messages += 1;
+        self.bytes += msg.raw.len() as u64;
+        *self.per_tag.entry(msg.tag()).or_default() += 1;
+    }
+}
 
 #[derive(Clone)]
 pub struct InnerNode {
     node: Node,
     pub handshake_cfg: HandshakeCfg,
     pub inbound_tx: Sender<(SocketAddr, AlgoMsg)>,
+    /// Raw bytes of all received messages, recorded only when enabled.
+    pub recorded_messages: Arc<Mutex<Option<Vec<Vec<u8>>>>>,
+    /// Statistics of all received messages.
+    pub stats: Arc<Mutex<MessageStats>>,
+    /// Handshake responses received from the peers the node connected to.
+    pub handshake_responses: Arc<Mutex<HashMap<SocketAddr, HandshakeResponse>>>,
+    /// Local addresses of the connections, keyed by the peer address, recorded after a successful
+    /// handshake.
+    pub local_addrs: Arc<Mutex<HashMap<SocketAddr, SocketAddr>>>,
+    /// Whether to automatically answer the received Ping messages.
+    pub reply_to_pings: bool,
+    /// The message sent to the peers right after the handshake to subscribe to the message tags.
+    pub msg_of_interest: Option<MsgOfInterest>,
 }
 
 impl InnerNode {
@@ -22,10 +63,30 @@ impl InnerNode {
             node,
             inbound_tx: tx,
             handshake_cfg,
+            recorded_messages: Default::default(),
+            stats: Default::default(),
+            handshake_responses: Default::default(),
+            local_addrs: Default::default(),
+            reply_to_pings: true,
+            msg_of_interest: None,
         }
     }
 }
 
+impl InnerNode {
+    /// Logs a received message, so the tracing output gives a timeline of the decoded payloads.
+    pub fn log_received(&self, source: SocketAddr, msg: &AlgoMsg) {
+        debug!(
+            parent: self.node().span(),
+            %source,
+            tag = ?msg.tag(),
+            len = msg.raw.len(),
+            "decoded a payload: {:?}",
+            msg.payload
+        );
+    }
+}
+
 impl Pea2Pea for InnerNode {
     fn node(&self) -> &Node {
         &self.node
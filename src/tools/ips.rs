@@ -9,9 +9,13 @@
 /// for MacOS:
 /// sudo python3 ./tools/ips.py --subnet 1.1.1.0/24 --file tools/ips_list.json --dev lo0
 /// For more information read the documentation of the ips.py script.
-use std::fs;
+use std::{
+    fs, io,
+    net::{IpAddr, SocketAddr},
+};
 
 use serde::Deserialize;
+use tokio::net::TcpSocket;
 
 const IPS_LIST_PATH: &str = "./tools/ips_list.json";
 
@@ -37,6 +41,46 @@ fn load_ips_nodes(filepath: &str) -> Vec<String> {
 
 /// Called by clients to obtain a list of
 /// nodes generated by the ips.py script.
-pub fn ips() -> Vec<String> {
+///
+/// The list may contain both IPv4 and IPv6 addresses.
+pub fn ips() -> Vec<IpAddr> {
     load_ips_nodes(IPS_LIST_PATH)
+        .iter()
+        .map(|ip| {
+            ip.parse()
+                .unwrap_or_else(|_| panic!("invalid IP address in {IPS_LIST_PATH}: {ip}"))
+        })
+        .collect()
+}
+
+/// Creates a socket bound to the IP address, on a port chosen by the OS.
+///
+/// The socket's address family follows the one of the address.
+pub fn bind_socket(ip: IpAddr) -> io::Result<TcpSocket> {
+    let socket = match ip {
+        IpAddr::V4(_) => TcpSocket::new_v4()?,
+        IpAddr::V6(_) => TcpSocket::new_v6()?,
+    };
+
+    // Make sure we can reuse the address and port
+    socket.set_reuseaddr(true)?;
+    socket.set_reuseport(true)?;
+
+    socket.bind(SocketAddr::new(ip, 0))?;
+    Ok(socket)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn bind_socket_to_ipv6_address() {
+        let ip = "::1".parse().expect("invalid IPv6 address");
+
+        let socket = bind_socket(ip).expect("couldn't bind the socket");
+        let addr = socket.local_addr().expect("couldn't get the local address");
+        assert!(addr.is_ipv6());
+        assert_eq!(addr.ip(), ip);
+    }
 }
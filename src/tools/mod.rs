@@ -2,10 +2,14 @@
 
 #[allow(dead_code)]
 pub mod constants;
+#[cfg(test)]
+pub mod helpers;
 pub mod inner_node;
 #[allow(dead_code)]
 pub mod ips;
 #[allow(dead_code)]
+pub mod rpc;
+#[allow(dead_code)]
 pub mod synthetic_node;
 #[allow(dead_code)]
 pub mod util;
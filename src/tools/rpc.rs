@@ -0,0 +1,111 @@
+//! Utilities for reading the node's internal state through its RPC endpoints.
+
+use std::{collections::HashMap, net::SocketAddr};
+
+use reqwest::Client;
+
+/// The prefix of all metrics reported by algod.
+const ALGOD_METRIC_PREFIX: &str = "algod_";
+
+/// Fetches the metrics exposed by the node in the Prometheus text format.
+///
+/// Some of the metrics of interest:
+/// - `algod_network_connections_dropped_total` - the number of connections dropped by the node,
+/// - `algod_transaction_messages_handled` - the number of transaction messages handled.
+#[derive(Clone)]
+pub struct NodeMetrics {
+    url: String,
+    http_client: Client,
+}
+
+impl NodeMetrics {
+    /// Creates a new [NodeMetrics] fetcher for the node's REST API address.
+    pub fn new(rest_api_addr: SocketAddr) -> Self {
+        Self {
+            url: format!("http://{rest_api_addr}/metrics"),
+            http_client: Client::new(),
+        }
+    }
+
+    /// Returns the current values of the `algod_*` metrics.
+    ///
+    /// Labeled metrics keep their labels in the key, e.g. `algod_metric{reason="x"}`.
+    pub async fn fetch(&self) -> anyhow::Result<HashMap<String, f64>> {
+        let rsp = self.http_client.get(&self.url).send().await?;
+        if let Err(e) = rsp.error_for_status_ref() {
+            anyhow::bail!("{e}: {}", rsp.text().await?);
+        }
+
+        Ok(parse_metrics(&rsp.text().await?))
+    }
+}
+
+/// Parses the `algod_*` samples from the Prometheus text format.
+fn parse_metrics(text: &str) -> HashMap<String, f64> {
+    text.lines()
+        .map(str::trim)
+        // Skip the HELP and TYPE comments and the metrics not reported by algod.
+        .filter(|line| line.starts_with(ALGOD_METRIC_PREFIX))
+        .filter_map(|line| {
+            // The label values may contain whitespace, so a labeled name ends with the last brace.
+            // The value may be followed by a timestamp.
+            let name_len = match line.rfind('}') {
+                Some(labels_end) => labels_end + 1,
+                None => line.find(char::is_whitespace)?,
+            };
+            let (name, rest) = line.split_at(name_len);
+            let value = rest.split_whitespace().next()?;
+            Some((name.to_string(), value.parse().ok()?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+    use ziggurat_core_utils::err_constants::{ERR_NODE_BUILD, ERR_NODE_STOP, ERR_TEMPDIR_NEW};
+
+    use super::*;
+    use crate::{setup::node::Node, tools::constants::ERR_NODE_START};
+
+    #[test]
+    fn parse_algod_metrics() {
+        let text = "\
+            # HELP algod_network_connections_dropped_total number of connections dropped\n\
+            # TYPE algod_network_connections_dropped_total counter\n\
+            algod_network_connections_dropped_total{reason=\"write err\"} 2\n\
+            algod_transaction_messages_handled 15\n\
+            algod_ledger_round 7 1395066363000\n\
+            go_goroutines 42\n\
+            algod_invalid_sample not-a-number\n";
+
+        let metrics = parse_metrics(text);
+        assert_eq!(metrics.len(), 3);
+        assert_eq!(
+            metrics.get("algod_network_connections_dropped_total{reason=\"write err\"}"),
+            Some(&2.0)
+        );
+        assert_eq!(
+            metrics.get("algod_transaction_messages_handled"),
+            Some(&15.0)
+        );
+        // The trailing timestamp isn't taken as the value.
+        assert_eq!(metrics.get("algod_ledger_round"), Some(&7.0));
+    }
+
+    #[tokio::test]
+    async fn scrape_node_metrics() {
+        let target = TempDir::new().expect(ERR_TEMPDIR_NEW);
+        let mut node = Node::builder().build(target.path()).expect(ERR_NODE_BUILD);
+        node.start().await.expect(ERR_NODE_START);
+
+        let rest_api_addr = node.rest_api_addr().expect("missing the REST API address");
+        let metrics = NodeMetrics::new(rest_api_addr)
+            .fetch()
+            .await
+            .expect("couldn't fetch the node's metrics");
+        assert!(!metrics.is_empty());
+
+        node.stop().await.expect(ERR_NODE_STOP);
+    }
+}
@@ -1,6 +1,7 @@
 //! A lightweight node implementation to be used as peers in tests.
 
 use std::{
+    collections::{HashMap, HashSet},
     io,
     net::{IpAddr, Ipv4Addr, SocketAddr},
 };
@@ -10,18 +11,27 @@ use pea2pea::{
     Config as NodeConfig, Node, Pea2Pea,
 };
 use tokio::{
-    net::TcpSocket,
+    net::{TcpSocket, TcpStream},
     sync::mpsc::{self, Receiver},
-    time::{sleep, timeout, Duration},
+    time::{sleep, timeout, Duration, Instant},
 };
 use tracing::trace;
 
 use crate::{
     protocol::{
-        codecs::{algomsg::AlgoMsg, payload::Payload},
-        handshake::HandshakeCfg,
+        codecs::{
+            algomsg::AlgoMsg,
+            payload::{Payload, PingData},
+            tagmsg::Tag,
+            topic::MsgOfInterest,
+        },
+        handshake::{HandshakeCfg, HandshakeResponse},
+    },
+    tools::{
+        constants::EXPECT_MSG_TIMEOUT,
+        inner_node::{InnerNode, MessageStats},
+        ips::bind_socket,
     },
-    tools::{constants::EXPECT_MSG_TIMEOUT, inner_node::InnerNode},
 };
 
 /// Enables tracing for all [`SyntheticNode`] instances (usually scoped by test).
@@ -41,8 +51,16 @@ pub struct SyntheticNodeBuilder {
     network_config: NodeConfig,
     /// Whether or not to call `enable_handshake` when creating a new node.
     handshake: bool,
-    /// Network priority challenge sent to clients which try to connect to the node.
-    handshake_cfg: HandshakeCfg,
+    /// The handshake configuration, generated with a unique identity for each node if not set.
+    handshake_cfg: Option<HandshakeCfg>,
+    /// The number of messages the inbound queue can hold before the reading is paused.
+    inbound_queue_size: usize,
+    /// Whether to automatically answer the received Ping messages.
+    ping_replies: bool,
+    /// The message tags to subscribe to right after the handshake.
+    subscribe_tags: Option<HashSet<Tag>>,
+    /// Whether to send a WebSocket Close frame to the peers on shutdown.
+    graceful_close: bool,
 }
 
 impl Default for SyntheticNodeBuilder {
@@ -54,6 +72,10 @@ impl Default for SyntheticNodeBuilder {
             },
             handshake: true,
             handshake_cfg: Default::default(),
+            inbound_queue_size: 100,
+            ping_replies: true,
+            subscribe_tags: None,
+            graceful_close: false,
         }
     }
 }
@@ -64,10 +86,19 @@ impl SyntheticNodeBuilder {
         // Create the pea2pea node from the config.
         let node = Node::new(self.network_config.clone());
 
-        // Inbound channel size of 100 messages.
-        let (tx, rx) = mpsc::channel(100);
+        let (tx, rx) = mpsc::channel(self.inbound_queue_size);
 
-        let inner_node = InnerNode::new(node, tx, self.handshake_cfg.clone()).await;
+        let handshake_cfg = self
+            .handshake_cfg
+            .clone()
+            .unwrap_or_else(|| HandshakeCfg::default().with_unique_identity());
+        let mut inner_node = InnerNode::new(node, tx, handshake_cfg).await;
+        inner_node.reply_to_pings = self.ping_replies;
+        inner_node.msg_of_interest = self
+            .subscribe_tags
+            .clone()
+            .map(MsgOfInterest::new)
+            .transpose()?;
 
         // Enable the handshake protocol.
         if self.handshake {
@@ -80,9 +111,24 @@ impl SyntheticNodeBuilder {
         Ok(SyntheticNode {
             inner: inner_node,
             inbound_rx: rx,
+            graceful_close: self.graceful_close,
         })
     }
 
+    /// Opens a plain TCP connection to the target instead of building a [`SyntheticNode`].
+    ///
+    /// Nothing is sent over the connection, so the test is in full control of its bytes, e.g. to
+    /// probe how the node handles clients which don't ask for the WebSocket upgrade. The
+    /// connection is made from the configured listener IP address.
+    pub async fn raw_tcp(&self, target: SocketAddr) -> io::Result<TcpStream> {
+        let ip = self
+            .network_config
+            .listener_ip
+            .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        bind_socket(ip)?.connect(target).await
+    }
+
     /// Choose whether or not the node should perform the handshake procedure.
     pub fn with_handshake(mut self, handshake: bool) -> Self {
         self.handshake = handshake;
@@ -90,8 +136,48 @@ impl SyntheticNodeBuilder {
     }
 
     /// Choose the handshake configuration.
+    ///
+    /// The configuration is used as is, so nodes built with it share the same identity.
     pub fn with_handshake_configuration(mut self, cfg: HandshakeCfg) -> Self {
-        self.handshake_cfg = cfg;
+        self.handshake_cfg = Some(cfg);
+        self
+    }
+
+    /// Choose whether or not the node should automatically answer the received Ping messages.
+    ///
+    /// The Ping messages are forwarded to the inbound queue either way.
+    pub fn with_ping_replies(mut self, ping_replies: bool) -> Self {
+        self.ping_replies = ping_replies;
+        self
+    }
+
+    /// Choose the message tags to subscribe to with a MsgOfInterest message sent right after the
+    /// handshake.
+    ///
+    /// No MsgOfInterest message is sent automatically if the tags aren't set.
+    pub fn subscribe_tags(mut self, tags: HashSet<Tag>) -> Self {
+        self.subscribe_tags = Some(tags);
+        self
+    }
+
+    /// Choose the number of received messages which can be queued before they are read.
+    pub fn with_inbound_queue_size(mut self, size: usize) -> Self {
+        self.inbound_queue_size = size;
+        self
+    }
+
+    /// Choose whether or not the node should send a WebSocket Close frame to every connected peer
+    /// before it shuts down.
+    ///
+    /// Otherwise the connections are closed without the WebSocket closing handshake.
+    pub fn graceful_close(mut self, graceful_close: bool) -> Self {
+        self.graceful_close = graceful_close;
+        self
+    }
+
+    /// Choose the IP address the node listens on, which is the localhost by default.
+    pub fn listener_ip(mut self, ip: IpAddr) -> Self {
+        self.network_config.listener_ip = Some(ip);
         self
     }
 }
@@ -100,6 +186,8 @@ impl SyntheticNodeBuilder {
 pub struct SyntheticNode {
     inner: InnerNode,
     inbound_rx: Receiver<(SocketAddr, AlgoMsg)>,
+    /// Whether to send a WebSocket Close frame to the peers on shutdown.
+    graceful_close: bool,
 }
 
 impl SyntheticNode {
@@ -110,6 +198,44 @@ impl SyntheticNode {
         self.inner.node().connect(target).await
     }
 
+    /// Connects to the target address, failing if the connection isn't established within the
+    /// `duration`.
+    ///
+    /// If the handshake protocol is enabled it has to finish within the `duration` as well.
+    pub async fn connect_timeout(&self, target: SocketAddr, duration: Duration) -> io::Result<()> {
+        timeout(duration, self.connect(target)).await.map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("could not connect to {target} after: {duration:?}"),
+            )
+        })?
+    }
+
+    /// Connects to the target address, retrying with a fixed `backoff` between the failed attempts.
+    ///
+    /// The connection is attempted at least once. Returns the last error if all the attempts fail.
+    pub async fn connect_with_retry(
+        &self,
+        target: SocketAddr,
+        attempts: usize,
+        backoff: Duration,
+    ) -> io::Result<()> {
+        let mut attempt = 1;
+        loop {
+            match self.connect(target).await {
+                Err(e) if attempt < attempts => {
+                    trace!(
+                        parent: self.inner.node().span(),
+                        "connection attempt {attempt} to {target} failed: {e}"
+                    );
+                    attempt += 1;
+                    sleep(backoff).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
     /// Connects to the target address using specified source socket.
     ///
     /// If the handshake protocol is enabled it will be executed as well.
@@ -124,11 +250,31 @@ impl SyntheticNode {
         self.inner.node().start_listening().await
     }
 
+    /// Disconnects from the peer at the `addr` while keeping other connections alive.
+    ///
+    /// Returns `true` if the peer was connected.
+    pub async fn disconnect(&self, addr: SocketAddr) -> bool {
+        self.inner
+            .local_addrs
+            .lock()
+            .expect("poisoned lock")
+            .remove(&addr);
+
+        self.inner.node().disconnect(addr).await
+    }
+
     /// Indicates if the `addr` is registered as a connected peer.
     pub fn is_connected(&self, addr: SocketAddr) -> bool {
         self.inner.node().is_connected(addr)
     }
 
+    /// Waits until the `addr` is no longer a connected peer or the `duration` elapses.
+    ///
+    /// Returns `true` if the peer got disconnected in time.
+    pub async fn expect_disconnect(&self, addr: SocketAddr, duration: Duration) -> bool {
+        poll_until(|| !self.is_connected(addr), duration).await
+    }
+
     /// Returns the number of connected peers.
     pub fn num_connected(&self) -> usize {
         self.inner.node().num_connected()
@@ -139,6 +285,19 @@ impl SyntheticNode {
         self.inner.node().connected_addrs()
     }
 
+    /// Returns the local address of each active connection, keyed by the peer address.
+    ///
+    /// Useful to check which source address a peer sees, e.g. for connections made with
+    /// [`SyntheticNode::connect_from`]. Only available if the handshake is enabled.
+    pub fn connection_info(&self) -> HashMap<SocketAddr, SocketAddr> {
+        let local_addrs = self.inner.local_addrs.lock().expect("poisoned lock");
+
+        self.connected_peers()
+            .into_iter()
+            .filter_map(|addr| Some((addr, *local_addrs.get(&addr)?)))
+            .collect()
+    }
+
     /// Waits until the node has at least one connection, and returns its SocketAddr.
     pub async fn wait_for_connection(&self) -> SocketAddr {
         const SLEEP: Duration = Duration::from_millis(50);
@@ -152,16 +311,46 @@ impl SyntheticNode {
         }
     }
 
+    /// Waits until the node has at least `count` connections or the `duration` elapses.
+    ///
+    /// Returns `true` if the connections were established in time.
+    pub async fn wait_for_connections(&self, count: usize, duration: Duration) -> bool {
+        poll_until(|| self.num_connected() >= count, duration).await
+    }
+
     /// Returns the listening address of the node.
     pub fn listening_addr(&self) -> io::Result<SocketAddr> {
         self.inner.node().listening_addr()
     }
 
     /// Gracefully shuts down the node.
+    ///
+    /// If enabled with [`SyntheticNodeBuilder::graceful_close`], a WebSocket Close frame is sent to
+    /// every connected peer first.
     pub async fn shut_down(&self) {
+        if self.graceful_close {
+            for target in self.connected_peers() {
+                self.send_close_frame(target).await;
+            }
+        }
+
         self.inner.node().shut_down().await
     }
 
+    /// Sends a WebSocket Close frame to the target and waits until it's written.
+    async fn send_close_frame(&self, target: SocketAddr) {
+        let result = match self.inner.unicast(target, Payload::WsClose) {
+            Ok(sent) => sent
+                .await
+                .unwrap_or_else(|_| Err(io::ErrorKind::BrokenPipe.into())),
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
+            trace!(parent: self.inner.node().span(), "couldn't send a Close frame to {target}: {e}");
+        }
+    }
+
     /// Sends a direct message to the target address.
     pub fn unicast(&self, target: SocketAddr, message: Payload) -> io::Result<()> {
         trace!(parent: self.inner.node().span(), "unicast send msg to {target}: {:?}", message);
@@ -169,6 +358,82 @@ impl SyntheticNode {
         Ok(())
     }
 
+    /// Sends the bytes to the target address as is, bypassing the tag and the WebSocket framing.
+    ///
+    /// Useful for crafting malformed WebSocket frames.
+    pub fn send_raw_frame(&self, target: SocketAddr, data: Vec<u8>) -> io::Result<()> {
+        self.unicast(target, Payload::RawFrame(data))
+    }
+
+    /// Sends a message to all connected peers.
+    ///
+    /// The message is sent to every peer even if some of the sends fail, in which case the first
+    /// error is returned.
+    pub fn broadcast(&self, message: Payload) -> io::Result<()> {
+        let mut result = Ok(());
+
+        for target in self.connected_peers() {
+            if let Err(e) = self.unicast(target, message.clone()) {
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Starts recording raw bytes of all received messages.
+    ///
+    /// Recorded messages can later be replayed with [`Payload::RawBytes`].
+    pub fn enable_recording(&self) {
+        let mut recorded = self.inner.recorded_messages.lock().expect("poisoned lock");
+        if recorded.is_none() {
+            *recorded = Some(Vec::new());
+        }
+    }
+
+    /// Returns raw bytes of all messages received since the recording was enabled.
+    pub fn recorded_messages(&self) -> Vec<Vec<u8>> {
+        self.inner
+            .recorded_messages
+            .lock()
+            .expect("poisoned lock")
+            .clone()
+            .unwrap_or_default()
+    }
+
+    /// Returns statistics of all messages received so far.
+    pub fn stats(&self) -> MessageStats {
+        self.inner.stats.lock().expect("poisoned lock").clone()
+    }
+
+    /// Returns the handshake response received from the peer at `addr`.
+    ///
+    /// Only available for the connections initiated by this node.
+    pub fn handshake_response(&self, addr: SocketAddr) -> Option<HandshakeResponse> {
+        self.inner
+            .handshake_responses
+            .lock()
+            .expect("poisoned lock")
+            .get(&addr)
+            .cloned()
+    }
+
+    /// Returns the public address advertised by the peer at `addr` in its handshake response.
+    ///
+    /// Only available for the connections initiated by this node.
+    pub fn peer_location(&self, addr: SocketAddr) -> Option<String> {
+        self.handshake_response(addr)?.ar_location
+    }
+
+    /// Returns the telemetry ID advertised by the peer at `addr` in its handshake response.
+    ///
+    /// Only available for the connections initiated by this node.
+    pub fn peer_telemetry_id(&self, addr: SocketAddr) -> Option<String> {
+        self.handshake_response(addr)?.ar_tel_id
+    }
+
     /// Reads a message from the inbound (internal) queue of the node.
     pub async fn recv_message(&mut self) -> (SocketAddr, AlgoMsg) {
         match self.inbound_rx.recv().await {
@@ -192,10 +457,25 @@ impl SyntheticNode {
         }
     }
 
+    /// Discards the received messages until none arrives for `quiet_for`.
+    ///
+    /// Returns the number of discarded messages.
+    pub async fn drain_inbound(&mut self, quiet_for: Duration) -> usize {
+        let mut drained = 0;
+        while self.recv_message_timeout(quiet_for).await.is_ok() {
+            drained += 1;
+        }
+
+        drained
+    }
+
     /// Expects a message.
+    ///
+    /// The `check` predicate may mutate its captured state, e.g. to only pass once it has seen a
+    /// number of distinct messages.
     pub async fn expect_message(
         &mut self,
-        check: &dyn Fn(&Payload) -> bool,
+        mut check: impl FnMut(&Payload) -> bool,
         override_timeout: Option<Duration>,
     ) -> bool {
         let duration = override_timeout.unwrap_or(EXPECT_MSG_TIMEOUT);
@@ -211,4 +491,964 @@ impl SyntheticNode {
         .await
         .is_ok()
     }
+
+    /// Sends a Ping message with a random nonce to the target and waits for the matching
+    /// PingReply.
+    ///
+    /// Returns the measured round-trip time. Messages received in the meantime are discarded.
+    pub async fn ping(&mut self, target: SocketAddr) -> io::Result<Duration> {
+        let nonce = rand::random::<[u8; 8]>();
+
+        let start = Instant::now();
+        self.unicast(target, Payload::Ping(PingData { nonce }))?;
+
+        timeout(EXPECT_MSG_TIMEOUT, async {
+            loop {
+                let (source, msg) = self.recv_message().await;
+                if let Payload::PingReply(reply) = msg.payload {
+                    if source == target && reply.nonce == nonce {
+                        return start.elapsed();
+                    }
+                }
+            }
+        })
+        .await
+        .map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("no PingReply from {target} after: {EXPECT_MSG_TIMEOUT:?}"),
+            )
+        })
+    }
+
+    /// Sends the payload to the target and waits for the first message from the target which passes
+    /// the `check` predicate.
+    ///
+    /// Returns `None` if the payload couldn't be sent or no matching message arrived in time.
+    /// Messages received in the meantime are discarded.
+    pub async fn request(
+        &mut self,
+        target: SocketAddr,
+        payload: Payload,
+        mut check: impl FnMut(&Payload) -> bool,
+        override_timeout: Option<Duration>,
+    ) -> Option<AlgoMsg> {
+        let duration = override_timeout.unwrap_or(EXPECT_MSG_TIMEOUT);
+
+        self.unicast(target, payload).ok()?;
+
+        timeout(duration, async {
+            loop {
+                let (source, msg) = self.recv_message().await;
+                if source == target && check(&msg.payload) {
+                    return msg;
+                }
+            }
+        })
+        .await
+        .ok()
+    }
+
+    /// Counts the messages which pass the `check` predicate.
+    ///
+    /// Messages are received until the timeout elapses or until `count` matching messages have
+    /// been received, whichever comes first. Use a `count` larger than expected to verify that no
+    /// more than the expected number of messages arrive within the window.
+    pub async fn expect_messages(
+        &mut self,
+        mut check: impl FnMut(&Payload) -> bool,
+        count: usize,
+        override_timeout: Option<Duration>,
+    ) -> usize {
+        let duration = override_timeout.unwrap_or(EXPECT_MSG_TIMEOUT);
+        let mut matched = 0;
+
+        let _ = timeout(duration, async {
+            while matched < count {
+                let (_, msg) = self.recv_message().await;
+                if check(&msg.payload) {
+                    matched += 1;
+                }
+            }
+        })
+        .await;
+
+        matched
+    }
+}
+
+/// Polls the `condition` until it holds or the `duration` elapses.
+///
+/// Returns `true` if the condition held in time.
+async fn poll_until(condition: impl Fn() -> bool, duration: Duration) -> bool {
+    const SLEEP: Duration = Duration::from_millis(50);
+    timeout(duration, async {
+        while !condition() {
+            sleep(SLEEP).await;
+        }
+    })
+    .await
+    .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use bytes::BytesMut;
+    use tokio::io::AsyncReadExt;
+    use tokio_util::codec::Decoder;
+    use tracing::Span;
+    use ziggurat_core_utils::err_constants::{ERR_SYNTH_BUILD, ERR_SYNTH_CONNECT};
+
+    use super::*;
+    use crate::protocol::codecs::tagmsg::TagMsgCodec;
+
+    #[tokio::test]
+    async fn connect_timeout_to_a_stalled_listener() {
+        // The listener accepts TCP connections but never answers the handshake.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let target = listener.local_addr().unwrap();
+        let _stalled = tokio::spawn(async move {
+            let mut streams = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                streams.push(stream);
+            }
+        });
+
+        let synthetic_node = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+
+        let err = synthetic_node
+            .connect_timeout(target, Duration::from_millis(500))
+            .await
+            .expect_err("the connection should time out");
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(!synthetic_node.is_connected(target));
+
+        synthetic_node.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn connect_with_retry_to_a_briefly_unavailable_peer() {
+        const BACKOFF: Duration = Duration::from_millis(500);
+
+        // Reserve an address which nobody listens on for now.
+        let target = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let synthetic_node = SyntheticNodeBuilder::default()
+            .with_handshake(false)
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+
+        // All the attempts fail while the peer is unavailable.
+        let err = synthetic_node
+            .connect_with_retry(target, 2, Duration::from_millis(10))
+            .await
+            .expect_err("the peer shouldn't be available yet");
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+
+        // The peer becomes available after the first attempt fails.
+        let _peer = tokio::spawn(async move {
+            sleep(BACKOFF / 2).await;
+            let listener = tokio::net::TcpListener::bind(target).await.unwrap();
+            let mut streams = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                streams.push(stream);
+            }
+        });
+
+        synthetic_node
+            .connect_with_retry(target, 3, BACKOFF)
+            .await
+            .expect(ERR_SYNTH_CONNECT);
+        assert!(synthetic_node.is_connected(target));
+
+        synthetic_node.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn send_a_close_frame_on_shutdown() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let target = listener.local_addr().unwrap();
+        // The peer collects everything it receives until the connection is closed.
+        let peer = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut received = Vec::new();
+            stream.read_to_end(&mut received).await.unwrap();
+            received
+        });
+
+        let synthetic_node = SyntheticNodeBuilder::default()
+            .with_handshake(false)
+            .graceful_close(true)
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        synthetic_node
+            .connect(target)
+            .await
+            .expect(ERR_SYNTH_CONNECT);
+        synthetic_node.shut_down().await;
+
+        let received = peer.await.unwrap();
+        let frame = websocket_codec::MessageCodec::server()
+            .decode(&mut BytesMut::from(received.as_slice()))
+            .expect("invalid WebSocket frame")
+            .expect("missing the Close frame");
+        assert_eq!(frame.opcode(), websocket_codec::Opcode::Close);
+    }
+
+    #[tokio::test]
+    async fn nodes_get_unique_identities() {
+        let builder = SyntheticNodeBuilder::default();
+        let node_a = builder.build().await.expect(ERR_SYNTH_BUILD);
+        let node_b = builder.build().await.expect(ERR_SYNTH_BUILD);
+
+        let (cfg_a, cfg_b) = (&node_a.inner.handshake_cfg, &node_b.inner.handshake_cfg);
+        assert_ne!(cfg_a.ar_instance_name, cfg_b.ar_instance_name);
+        assert_ne!(cfg_a.ar_node_random, cfg_b.ar_node_random);
+
+        // An explicit configuration is kept as is.
+        let builder = builder.with_handshake_configuration(HandshakeCfg::default());
+        let node_c = builder.build().await.expect(ERR_SYNTH_BUILD);
+        assert_eq!(
+            node_c.inner.handshake_cfg.ar_instance_name,
+            HandshakeCfg::default().ar_instance_name
+        );
+
+        for node in [node_a, node_b, node_c] {
+            node.shut_down().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn reject_connection_with_the_same_node_random() {
+        // An explicit configuration gives both nodes the same node random.
+        let builder =
+            SyntheticNodeBuilder::default().with_handshake_configuration(HandshakeCfg::default());
+
+        let responder = builder.build().await.expect(ERR_SYNTH_BUILD);
+        let addr = responder
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let initiator = builder.build().await.expect(ERR_SYNTH_BUILD);
+        assert!(initiator.connect(addr).await.is_err());
+
+        assert!(!initiator.is_connected(addr));
+        assert_eq!(responder.num_connected(), 0);
+
+        initiator.shut_down().await;
+        responder.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn broadcast_reaches_all_peers() {
+        let mut receivers = Vec::new();
+        for _ in 0..2 {
+            let receiver = SyntheticNodeBuilder::default()
+                .build()
+                .await
+                .expect(ERR_SYNTH_BUILD);
+            receiver
+                .start_listening()
+                .await
+                .expect("couldn't start listening");
+            receivers.push(receiver);
+        }
+
+        let sender = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        for receiver in &receivers {
+            let addr = receiver.listening_addr().expect("couldn't get the address");
+            sender.connect(addr).await.expect(ERR_SYNTH_CONNECT);
+        }
+        assert_eq!(sender.num_connected(), 2);
+
+        let tags = HashSet::from([Tag::Txn]);
+        let message = Payload::MsgOfInterest(MsgOfInterest { tags });
+        assert!(sender.broadcast(message).is_ok());
+
+        let check =
+            |m: &Payload| matches!(m, Payload::MsgOfInterest(msg) if msg.tags.contains(&Tag::Txn));
+        for receiver in &mut receivers {
+            assert!(receiver.expect_message(&check, None).await);
+        }
+
+        sender.shut_down().await;
+        for receiver in &receivers {
+            receiver.shut_down().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn connection_info_reports_the_bound_source_address() {
+        let peer = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let peer_addr = peer
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let synthetic_node = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let socket =
+            bind_socket(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2))).expect("couldn't bind the socket");
+        let source = socket.local_addr().expect("couldn't get the local address");
+        synthetic_node
+            .connect_from(peer_addr, socket)
+            .await
+            .expect(ERR_SYNTH_CONNECT);
+
+        let info = synthetic_node.connection_info();
+        assert_eq!(info.len(), 1);
+        assert_eq!(info.get(&peer_addr), Some(&source));
+
+        // The peer sees the connection coming from the bound source address.
+        assert_eq!(peer.wait_for_connection().await, source);
+        assert_eq!(peer.connection_info().get(&source), Some(&peer_addr));
+
+        synthetic_node.shut_down().await;
+        peer.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn disconnect_from_a_single_peer() {
+        let mut peers = Vec::new();
+        for _ in 0..2 {
+            let peer = SyntheticNodeBuilder::default()
+                .build()
+                .await
+                .expect(ERR_SYNTH_BUILD);
+            let addr = peer
+                .start_listening()
+                .await
+                .expect("couldn't start listening");
+            peers.push((peer, addr));
+        }
+
+        let synthetic_node = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        for (_, addr) in &peers {
+            synthetic_node
+                .connect(*addr)
+                .await
+                .expect(ERR_SYNTH_CONNECT);
+        }
+        assert_eq!(synthetic_node.num_connected(), 2);
+
+        let (left, right) = (peers[0].1, peers[1].1);
+        assert!(synthetic_node.disconnect(left).await);
+        // The peer is already gone.
+        assert!(!synthetic_node.disconnect(left).await);
+
+        assert!(!synthetic_node.is_connected(left));
+        assert!(synthetic_node.is_connected(right));
+        assert_eq!(synthetic_node.num_connected(), 1);
+
+        synthetic_node.shut_down().await;
+        for (peer, _) in &peers {
+            peer.shut_down().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn wait_for_multiple_connections() {
+        let listener = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let addr = listener
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let mut peers = Vec::new();
+        for _ in 0..2 {
+            let peer = SyntheticNodeBuilder::default()
+                .build()
+                .await
+                .expect(ERR_SYNTH_BUILD);
+            peer.connect(addr).await.expect(ERR_SYNTH_CONNECT);
+            peers.push(peer);
+        }
+
+        assert!(
+            listener
+                .wait_for_connections(2, Duration::from_secs(1))
+                .await
+        );
+        assert!(
+            !listener
+                .wait_for_connections(3, Duration::from_millis(200))
+                .await
+        );
+
+        listener.shut_down().await;
+        for peer in &peers {
+            peer.shut_down().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn listen_on_a_loopback_alias() {
+        let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2));
+        let synthetic_node = SyntheticNodeBuilder::default()
+            .listener_ip(ip)
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+
+        let addr = synthetic_node
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+        assert_eq!(addr.ip(), ip);
+        assert_eq!(synthetic_node.listening_addr().ok(), Some(addr));
+
+        synthetic_node.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn expect_disconnect_after_sending_garbage_pre_handshake() {
+        let peer = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let addr = peer
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let synthetic_node = SyntheticNodeBuilder::default()
+            .with_handshake(false)
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        synthetic_node.connect(addr).await.expect(ERR_SYNTH_CONNECT);
+
+        // The peer is waiting for a handshake request, which can't start with these bytes.
+        synthetic_node
+            .send_raw_frame(addr, vec![0xff; 32])
+            .expect("couldn't send the garbage");
+
+        assert!(
+            synthetic_node
+                .expect_disconnect(addr, Duration::from_secs(1))
+                .await
+        );
+
+        synthetic_node.shut_down().await;
+        peer.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn read_genesis_from_handshake_response() {
+        const GENESIS: &str = "testnet-v1.0";
+
+        let cfg = HandshakeCfg::default().with_genesis(GENESIS);
+        let responder = SyntheticNodeBuilder::default()
+            .with_handshake_configuration(cfg)
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let addr = responder
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let initiator = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        assert!(initiator.handshake_response(addr).is_none());
+        initiator.connect(addr).await.expect(ERR_SYNTH_CONNECT);
+
+        let rsp = initiator
+            .handshake_response(addr)
+            .expect("missing the handshake response");
+        assert_eq!(rsp.ar_genesis.as_deref(), Some(GENESIS));
+
+        initiator.shut_down().await;
+        responder.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn read_location_and_telemetry_id_from_handshake_response() {
+        const LOCATION: &str = "127.0.0.1:4161";
+
+        let cfg = HandshakeCfg {
+            ar_location: Some(LOCATION.into()),
+            ..Default::default()
+        };
+        let located = SyntheticNodeBuilder::default()
+            .with_handshake_configuration(cfg)
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let located_addr = located
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let cfg = HandshakeCfg {
+            ar_tel_id: Some("telemetry".into()),
+            ..Default::default()
+        };
+        let telemetered = SyntheticNodeBuilder::default()
+            .with_handshake_configuration(cfg)
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let telemetered_addr = telemetered
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let initiator = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        for addr in [located_addr, telemetered_addr] {
+            initiator.connect(addr).await.expect(ERR_SYNTH_CONNECT);
+        }
+
+        assert_eq!(
+            initiator.peer_location(located_addr).as_deref(),
+            Some(LOCATION)
+        );
+        assert!(initiator.peer_telemetry_id(located_addr).is_none());
+        assert!(initiator.peer_location(telemetered_addr).is_none());
+        assert_eq!(
+            initiator.peer_telemetry_id(telemetered_addr).as_deref(),
+            Some("telemetry")
+        );
+
+        initiator.shut_down().await;
+        located.shut_down().await;
+        telemetered.shut_down().await;
+    }
+
+    /// Collects the tracing output of a test.
+    #[derive(Clone, Default)]
+    struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn log_decoded_payloads() {
+        use tracing_subscriber::{fmt, util::SubscriberInitExt};
+
+        // The test runtime is single-threaded, so all the node tasks use this subscriber.
+        let logs = LogBuffer::default();
+        let writer = logs.clone();
+        let _guard = fmt()
+            .with_ansi(false)
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(move || writer.clone())
+            .finish()
+            .set_default();
+
+        let mut responder = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let addr = responder
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let initiator = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        initiator.connect(addr).await.expect(ERR_SYNTH_CONNECT);
+
+        let msg_of_interest = MsgOfInterest::new([Tag::Txn]).unwrap();
+        initiator
+            .unicast(addr, Payload::MsgOfInterest(msg_of_interest))
+            .unwrap();
+        let check = |m: &Payload| matches!(m, Payload::MsgOfInterest(_));
+        assert!(responder.expect_message(check, None).await);
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(logs
+            .lines()
+            .any(|line| line.contains("decoded a payload") && line.contains("tag=MsgOfInterest")));
+
+        initiator.shut_down().await;
+        responder.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn subscribe_to_tags_after_the_handshake() {
+        let mut responder = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let addr = responder
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let tags = HashSet::from([Tag::Txn]);
+        let initiator = SyntheticNodeBuilder::default()
+            .subscribe_tags(tags.clone())
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        initiator.connect(addr).await.expect(ERR_SYNTH_CONNECT);
+
+        let check = |m: &Payload| matches!(m, Payload::MsgOfInterest(moi) if moi.tags == tags);
+        assert!(responder.expect_message(check, None).await);
+
+        initiator.shut_down().await;
+        responder.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn reply_to_pings_automatically() {
+        let mut responder = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let addr = responder
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let mut initiator = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        initiator.connect(addr).await.expect(ERR_SYNTH_CONNECT);
+
+        // Nobody reads the responder's inbound queue, so the reply is sent automatically.
+        assert!(initiator.ping(addr).await.is_ok());
+        // The ping is still forwarded to the responder.
+        let check = |m: &Payload| matches!(m, Payload::Ping(_));
+        assert!(responder.expect_message(check, None).await);
+
+        initiator.shut_down().await;
+        responder.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn ping_measures_round_trip_time() {
+        // The replies are sent manually.
+        let mut responder = SyntheticNodeBuilder::default()
+            .with_ping_replies(false)
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let addr = responder
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let mut initiator = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        initiator.connect(addr).await.expect(ERR_SYNTH_CONNECT);
+
+        let replier = tokio::spawn(async move {
+            let (source, msg) = responder.recv_message().await;
+            let nonce = match msg.payload {
+                Payload::Ping(PingData { nonce }) => nonce,
+                payload => panic!("unexpected payload: {payload:?}"),
+            };
+
+            // A reply with a different nonce must not be mistaken for the matching one.
+            let mut wrong_nonce = nonce;
+            wrong_nonce[0] = wrong_nonce[0].wrapping_add(1);
+            for nonce in [wrong_nonce, nonce] {
+                let reply = Payload::PingReply(PingData { nonce });
+                responder
+                    .unicast(source, reply)
+                    .expect("couldn't send a reply");
+            }
+
+            responder
+        });
+
+        let rtt = initiator.ping(addr).await.expect("couldn't ping the peer");
+        assert!(!rtt.is_zero());
+
+        let responder = replier.await.expect("the replier panicked");
+        // The reply with the wrong nonce should've been skipped.
+        assert_eq!(initiator.stats().per_tag.get(&Tag::PingReply), Some(&2));
+
+        initiator.shut_down().await;
+        responder.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn expect_messages_counts_matching_messages() {
+        let mut receiver = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let addr = receiver
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let sender = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        sender.connect(addr).await.expect(ERR_SYNTH_CONNECT);
+
+        const NUM_MESSAGES: usize = 5;
+        for _ in 0..NUM_MESSAGES {
+            let tags = HashSet::from([Tag::Txn]);
+            let message = Payload::MsgOfInterest(MsgOfInterest { tags });
+            assert!(sender.unicast(addr, message).is_ok());
+        }
+
+        // Ask for more messages than were sent so the whole window is drained.
+        let check = |m: &Payload| matches!(m, Payload::MsgOfInterest(_));
+        let duration = Some(Duration::from_secs(1));
+        assert_eq!(
+            receiver
+                .expect_messages(&check, NUM_MESSAGES + 1, duration)
+                .await,
+            NUM_MESSAGES
+        );
+
+        sender.shut_down().await;
+        receiver.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn expect_message_with_a_stateful_check() {
+        let mut receiver = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let addr = receiver
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let sender = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        sender.connect(addr).await.expect(ERR_SYNTH_CONNECT);
+
+        const NUM_MESSAGES: usize = 3;
+        for _ in 0..NUM_MESSAGES {
+            let tags = HashSet::from([Tag::Txn]);
+            let message = Payload::MsgOfInterest(MsgOfInterest { tags });
+            assert!(sender.unicast(addr, message).is_ok());
+        }
+
+        // Pass only once all the messages have been seen.
+        let mut seen = 0;
+        let check = |m: &Payload| {
+            if matches!(m, Payload::MsgOfInterest(_)) {
+                seen += 1;
+            }
+            seen == NUM_MESSAGES
+        };
+        assert!(receiver.expect_message(check, None).await);
+        assert_eq!(seen, NUM_MESSAGES);
+
+        sender.shut_down().await;
+        receiver.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn record_received_messages() {
+        let mut receiver = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let addr = receiver
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+        receiver.enable_recording();
+
+        let sender = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        sender.connect(addr).await.expect(ERR_SYNTH_CONNECT);
+
+        let tags = HashSet::from([Tag::Txn, Tag::VoteBundle]);
+        let message = Payload::MsgOfInterest(MsgOfInterest { tags: tags.clone() });
+        assert!(sender.unicast(addr, message).is_ok());
+
+        let check = |m: &Payload| matches!(m, Payload::MsgOfInterest(_));
+        assert!(receiver.expect_message(&check, None).await);
+
+        let recorded = receiver.recorded_messages();
+        assert_eq!(recorded.len(), 1);
+
+        // The recorded bytes should decode back to the same message.
+        let mut raw = BytesMut::from(&recorded[0][..]);
+        let payload = TagMsgCodec::new(Span::none())
+            .decode(&mut raw)
+            .expect("couldn't decode the recorded message")
+            .expect("missing the recorded message");
+        assert!(matches!(payload, Payload::MsgOfInterest(msg) if msg.tags == tags));
+
+        sender.shut_down().await;
+        receiver.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn stats_count_received_messages() {
+        let mut receiver = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let addr = receiver
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let sender = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        sender.connect(addr).await.expect(ERR_SYNTH_CONNECT);
+
+        assert_eq!(receiver.stats().messages, 0);
+
+        let tags = HashSet::from([Tag::Txn]);
+        let message = Payload::MsgOfInterest(MsgOfInterest { tags });
+        assert!(sender.unicast(addr, message).is_ok());
+
+        let check = |m: &Payload| matches!(m, Payload::MsgOfInterest(_));
+        assert!(receiver.expect_message(&check, None).await);
+
+        let stats = receiver.stats();
+        assert_eq!(stats.messages, 1);
+        assert!(stats.bytes > 0);
+        assert_eq!(stats.per_tag.get(&Tag::MsgOfInterest), Some(&1));
+
+        sender.shut_down().await;
+        receiver.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn drain_queued_messages() {
+        const NUM_MESSAGES: usize = 5;
+
+        let mut receiver = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let addr = receiver
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let sender = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        sender.connect(addr).await.expect(ERR_SYNTH_CONNECT);
+
+        for _ in 0..NUM_MESSAGES {
+            let tags = HashSet::from([Tag::Txn]);
+            let message = Payload::MsgOfInterest(MsgOfInterest { tags });
+            assert!(sender.unicast(addr, message).is_ok());
+        }
+
+        // Wait until all the messages are queued.
+        let queued = timeout(Duration::from_secs(1), async {
+            while receiver.stats().messages < NUM_MESSAGES as u64 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+        assert!(queued.is_ok(), "the messages weren't received");
+
+        let quiet_for = Duration::from_millis(100);
+        assert_eq!(receiver.drain_inbound(quiet_for).await, NUM_MESSAGES);
+        assert_eq!(receiver.drain_inbound(quiet_for).await, 0);
+
+        sender.shut_down().await;
+        receiver.shut_down().await;
+    }
+
+    #[tokio::test]
+    async fn large_inbound_queue_holds_many_messages() {
+        const NUM_MESSAGES: usize = 500;
+
+        let mut receiver = SyntheticNodeBuilder::default()
+            .with_inbound_queue_size(NUM_MESSAGES)
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        let addr = receiver
+            .start_listening()
+            .await
+            .expect("couldn't start listening");
+
+        let sender = SyntheticNodeBuilder::default()
+            .build()
+            .await
+            .expect(ERR_SYNTH_BUILD);
+        sender.connect(addr).await.expect(ERR_SYNTH_CONNECT);
+
+        for _ in 0..NUM_MESSAGES {
+            let tags = HashSet::from([Tag::Txn]);
+            let message = Payload::MsgOfInterest(MsgOfInterest { tags });
+            assert!(sender.unicast(addr, message).is_ok());
+            // Don't overflow the sender's outbound queue.
+            sleep(Duration::from_millis(1)).await;
+        }
+
+        // All messages are processed without anyone reading the inbound queue.
+        let queued = timeout(Duration::from_secs(5), async {
+            while receiver.stats().messages < NUM_MESSAGES as u64 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+        assert!(queued.is_ok(), "the inbound queue is full");
+
+        let check = |m: &Payload| matches!(m, Payload::MsgOfInterest(_));
+        let duration = Some(Duration::from_secs(1));
+        assert_eq!(
+            receiver
+                .expect_messages(&check, NUM_MESSAGES, duration)
+                .await,
+            NUM_MESSAGES
+        );
+
+        sender.shut_down().await;
+        receiver.shut_down().await;
+    }
 }
@@ -1,14 +1,63 @@
+use std::io;
+
+use bytes::BytesMut;
+use tokio_util::codec::Encoder;
+use tracing::Span;
+
+use crate::protocol::{
+    codecs::{
+        msgpack::CanonicalValue,
+        payload::Payload,
+        tagmsg::{TagMsgCodec, TAG_LEN},
+    },
+    invalid_data,
+};
+
 /// Generate a random data.
 pub fn gen_rand_bytes(len: usize) -> Vec<u8> {
     (0..len).map(|_| rand::random::<u8>()).collect()
 }
 
+/// Encodes the payload as a tagged message in which the value of the field is replaced with a
+/// value of another type, so the message is still valid msgpack, but not a valid payload.
+///
+/// The field is looked up by its name, so it must be a field of a payload encoded as a msgpack map.
+pub fn corrupt_field(payload: Payload, field: &str) -> io::Result<Vec<u8>> {
+    let mut msg = BytesMut::new();
+    TagMsgCodec::new(Span::none()).encode(payload, &mut msg)?;
+
+    let mut value = CanonicalValue::decode(&msg[TAG_LEN..])?;
+
+    // A string replaces values of any other type and an integer replaces strings.
+    let replacement = match value.get(field) {
+        Some(CanonicalValue::Str(_)) => CanonicalValue::Uint(42),
+        Some(_) => CanonicalValue::Str("ziggurat".into()),
+        None => return Err(invalid_data!(format!("the payload has no {field} field"))),
+    };
+    value.insert(field, replacement)?;
+
+    let mut corrupted = msg[..TAG_LEN].to_vec();
+    corrupted.extend(value.encode()?);
+    Ok(corrupted)
+}
+
 #[cfg(test)]
 mod test {
+    use tokio_util::codec::Decoder;
+
     use super::*;
+    use crate::protocol::codecs::msgpack::{Round, VoteBundle};
 
     const LEN: usize = 512;
 
+    fn vote_bundle() -> Payload {
+        Payload::VoteBundle(Box::new(VoteBundle::empty(Round(7))))
+    }
+
+    fn decode(msg: &[u8]) -> io::Result<Option<Payload>> {
+        TagMsgCodec::new(Span::none()).decode(&mut BytesMut::from(msg))
+    }
+
     #[tokio::test]
     async fn gen_rand_bytes_length_same() {
         let a = gen_rand_bytes(LEN);
@@ -26,4 +75,31 @@ mod test {
         assert_ne!(a.len(), b.len());
         assert_ne!(a, b);
     }
+
+    #[test]
+    fn corrupt_a_field_of_the_payload() {
+        let mut valid_msg = BytesMut::new();
+        TagMsgCodec::new(Span::none())
+            .encode(vote_bundle(), &mut valid_msg)
+            .expect("couldn't encode the payload");
+        assert!(decode(&valid_msg).is_ok());
+
+        let msg = corrupt_field(vote_bundle(), "step").expect("couldn't corrupt the field");
+        assert_eq!(msg[..TAG_LEN], valid_msg[..TAG_LEN]);
+
+        // The payload is still a msgpack value, with a string in place of the step.
+        let value =
+            CanonicalValue::decode(&msg[TAG_LEN..]).expect("the payload isn't valid msgpack");
+        assert_eq!(
+            value.get("step"),
+            Some(&CanonicalValue::Str("ziggurat".into()))
+        );
+
+        assert!(decode(&msg).is_err());
+    }
+
+    #[test]
+    fn corrupt_a_missing_field() {
+        assert!(corrupt_field(vote_bundle(), "nonexistent").is_err());
+    }
 }